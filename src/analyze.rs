@@ -26,6 +26,23 @@ use open::*;
 use regex::*;
 use crate::{anomalies, Args};
 use crate::anomalies::*;
+use crate::anomaly_detectors::{AnomalyDetector, build_anomaly_detector};
+use crate::sql_regression::report_sql_regressions;
+use crate::correlation::{CorrelationThresholds, correlation_details};
+use crate::changepoints::{report_change_points, add_change_point_trace, add_change_point_shapes, is_db_time_change_point};
+use crate::decomposition::report_db_time_decomposition;
+use crate::capacity::report_cpu_capacity;
+use crate::forecast::report_capacity_forecast;
+use crate::regimes::{cluster_workload_regimes, report_workload_regimes, add_regime_traces, add_regime_shapes};
+use crate::rules::report_rule_findings;
+use crate::parameters::report_parameters;
+use crate::redo::report_redo_commits;
+use crate::hot_objects::report_hot_objects;
+use crate::shared_pool::report_shared_pool_health;
+use crate::latches::report_latch_mutex_contention;
+use crate::io_slo::report_io_slo;
+use crate::heatmaps::report_heatmaps;
+use crate::snapshots::{generate_snapshot_pages, SNAPSHOT_CLICK_SCRIPT};
 
 use crate::make_notes;
 use crate::debug_note;
@@ -58,7 +75,9 @@ use crate::reasonings::{StatisticsDescription,
                         DbTimeGradientSection,
                         strip_gradient_descriptions,
                         VifDiagnostic,
-                        CollinearGroupImpact,};
+                        CollinearGroupImpact,
+                        CorrelationDetails,
+                        TargetGradientSection,};
 
 use crate::gradient::*;
 use crate::gradient::{EventSeriesMap,
@@ -100,7 +119,7 @@ fn parse_snap_range(snap_range: &str) -> Result<(u64, u64), String> {
 
 //We don't want to plot everything, because it would cause to much trouble 
 //we need to find only essential wait events and SQLIDs 
-fn find_top_stats(awrs: &Vec<AWR>, db_time_cpu_ratio: f64, filter_db_time: f64, snap_range: &(u64,u64), logfile_name: &str, args: &Args, report_for_ai: &mut ReportForAI) -> TopStats {
+fn find_top_stats(awrs: &Vec<AWR>, db_time_cpu_ratio: f64, filter_db_time: f64, snap_range: &(u64,u64), anomaly_detector: &dyn AnomalyDetector, logfile_name: &str, args: &Args, report_for_ai: &mut ReportForAI) -> TopStats {
     let mut event_names: BTreeMap<String, u8> = BTreeMap::new();
     let mut bgevent_names: BTreeMap<String, u8> = BTreeMap::new();
     let mut sql_ids: BTreeMap<String, String> = BTreeMap::new();
@@ -122,9 +141,9 @@ fn find_top_stats(awrs: &Vec<AWR>, db_time_cpu_ratio: f64, filter_db_time: f64,
     }
     make_notes!(&logfile_name, false, 1, "{}", "MEDIAN ABSOLUTE DEVIATION".bold().green());
     make_notes!(&logfile_name, false, 0, 
-        "\nMAD threshold = {}\nMAD window size={}% ({} of probes out of {})\n\n", args.mad_threshold, args.mad_window_size, full_window_size, awrs.len());
+        "\nAnomaly detector = {} (threshold = {})\nTOP anomalies per statistic = {}\nMAD window size={}% ({} of probes out of {})\nMAD seasonality={}\n\n", anomaly_detector.name(), anomaly_detector.threshold(), args.mad_threshold, args.mad_window_size, full_window_size, awrs.len(), args.mad_seasonality);
     
-    stats_description.median_absolute_deviation = format!("MAD threshold = {}\nMAD window size={}% ({} of probes out of {})\n\n", args.mad_threshold, args.mad_window_size, full_window_size, awrs.len());
+    stats_description.median_absolute_deviation = format!("Anomaly detector = {} (threshold = {})\nTOP anomalies per statistic = {}\nMAD window size={}% ({} of probes out of {})\nMAD seasonality={}\n\n", anomaly_detector.name(), anomaly_detector.threshold(), args.mad_threshold, args.mad_window_size, full_window_size, awrs.len(), args.mad_seasonality);
     
     let mut top_spikes: Vec<TopPeaksSelected> = Vec::new();
     let (f_begin_snap,f_end_snap) = snap_range;
@@ -160,7 +179,7 @@ fn find_top_stats(awrs: &Vec<AWR>, db_time_cpu_ratio: f64, filter_db_time: f64,
                 //println!("Analyzing a peak in {} ({}) for ratio: [{:.2}/{:.2}] = {:.2}", awr.file_name, awr.snap_info.begin_snap_time, cputime, dbtime, (cputime/dbtime));
                 make_notes!(&logfile_name, false, 0, "Analyzing a peak in {} ({}) for ratio: [{:.2}/{:.2}] = {:.2}\n", awr.file_name, awr.snap_info.begin_snap_time, cputime, dbtime, (cputime/dbtime));
                 
-                top_spikes.push(TopPeaksSelected { report_name: awr.file_name.clone(), report_date: awr.snap_info.begin_snap_time.clone(), snap_id: awr.snap_info.begin_snap_id, db_time_value: dbtime, db_cpu_value: cputime, dbcpu_dbtime_ratio: (cputime/dbtime) });
+                top_spikes.push(TopPeaksSelected { report_name: awr.file_name.clone(), report_date: awr.snap_info.begin_snap_time.clone(), snap_id: awr.snap_info.begin_snap_id, db_time_value: dbtime, db_cpu_value: cputime, dbcpu_dbtime_ratio: (cputime/dbtime), db_time_vs_median: Vec::new() });
 
                 let mut events: Vec<WaitEvents> = awr.foreground_wait_events.clone();
                 let mut bgevents: Vec<WaitEvents> = awr.background_wait_events.clone();
@@ -217,26 +236,25 @@ fn find_top_stats(awrs: &Vec<AWR>, db_time_cpu_ratio: f64, filter_db_time: f64,
         }
     }
     
-    if args.mad_window_size == 100 {
+    if args.mad_seasonality != "none" {
+        println!("\n****Detecting anamalies using MAD from {} seasonal baselines****\n", args.mad_seasonality);
+    } else if args.mad_window_size == 100 {
         println!("\n****Detecting anamalies using MAD from all probes****\n");
     } else {
         println!("\n****Detecting anamalies using MAD sliding window****\n");
     }
     
-    let awrs: Vec<AWR> = awrs.clone().iter()
-                                  .filter(|a| a.snap_info.begin_snap_id>= *f_begin_snap && a.snap_info.end_snap_id<=*f_end_snap)
-                                  .cloned().collect();
-
-    let event_anomalies = detect_event_anomalies_mad(&awrs, &args, "FOREGROUND");
+    //Detector is aligned with all loaded snapshots, wait events and SQLs are scored only in snap_range
+    let event_anomalies = detect_event_anomalies_mad(awrs, snap_range, anomaly_detector, args.mad_threshold, "FOREGROUND");
     for a in &event_anomalies {
         event_names.entry(a.0.to_string()).or_insert(1);
     }
-    let bgevent_anomalies = detect_event_anomalies_mad(&awrs, &args, "BACKGROUND");
+    let bgevent_anomalies = detect_event_anomalies_mad(awrs, snap_range, anomaly_detector, args.mad_threshold, "BACKGROUND");
     for a in &bgevent_anomalies {
         bgevent_names.entry(a.0.to_string()).or_insert(1);
     }
 
-    let sql_anomalies = detect_sql_anomalies_mad(&awrs, &args, "ELAPSED_TIME");
+    let sql_anomalies = detect_sql_anomalies_mad(awrs, snap_range, anomaly_detector, args.mad_threshold, "ELAPSED_TIME");
     for a in &sql_anomalies {
         sql_ids.entry(a.0.to_string()).or_insert(String::new());
     }
@@ -312,24 +330,83 @@ fn report_top_sql_sections(sqlid: &str, awrs: &Vec<AWR>) -> HashMap<String, f64>
     top_sections
 }
 
-fn report_instance_stats_cor(instance_stats: HashMap<String, Vec<f64>>, dbtime_vec: Vec<f64>) -> (BTreeMap<(i64, String), f64>,f64) {
-    let mut sorted_correlation: BTreeMap<(i64, String), f64> = BTreeMap::new();
+fn report_instance_stats_cor(instance_stats: HashMap<String, Vec<f64>>, dbtime_vec: Vec<f64>, executes_vec: &[f64], max_lag: usize) -> (BTreeMap<(i64, String), (f64, CorrelationDetails)>, CorrelationThresholds) {
     let num_stats = instance_stats.len();
-    let r_threshold = bonferroni_significance_threshold(num_stats, 0.05, dbtime_vec.len());
-    // Use max(0.5, r_threshold) 
-    let effective_threshold = r_threshold.max(0.5);
-
-    for (k,v) in &instance_stats {
-        if v.len() == dbtime_vec.len() {
-            let crr = pearson_correlation_2v(&v, &dbtime_vec);
-            if crr >= effective_threshold || crr <= effective_threshold*-1.0 {
-                sorted_correlation.insert(((crr * 1000.0) as i64 , k.clone()), crr);
+    // Bonferroni corrected thresholds for each method, never below max(0.5, r_threshold) used for Pearson
+    let thresholds = CorrelationThresholds::new(num_stats, dbtime_vec.len(), max_lag);
+
+    let sorted_correlation: BTreeMap<(i64, String), (f64, CorrelationDetails)> = instance_stats
+        .par_iter() //Kendall tau is O(n^2) so each thread will process one statistic
+        .filter_map(|(k,v)| {
+            if v.len() == dbtime_vec.len() {
+                let (crr, details) = correlation_details(v, &dbtime_vec, executes_vec, max_lag, &thresholds);
+                //Statistic is reported when at least one of the methods finds significant correlation
+                if !details.significant_by.is_empty() {
+                    return Some((((crr * 1000.0) as i64 , k.clone()), (crr, details)));
+                }
+            } else {
+                println!("Can't calculate correlation for {} - diff was {}", &k, dbtime_vec.len() - v.len());
             }
-        } else {
-            println!("Can't calculate correlation for {} - diff was {}", &k, dbtime_vec.len() - v.len());
+            None
+        })
+        .collect();
+    (sorted_correlation, thresholds)
+}
+
+/// Series of any metric (gradient target, heatmap) - aligned with snapshots in snap_range.
+/// Metric is given as FAMILY:name, missing values in a snapshot are 0.
+pub fn metric_series(awrs: &[AWR], snap_range: &(u64,u64), target: &str) -> Result<Vec<f64>, String> {
+    let (family, name) = target.split_once(':')
+        .map(|(f, n)| (f.trim().to_uppercase(), n.trim().to_string()))
+        .ok_or_else(|| format!("'{}' should be given as FAMILY:name", target))?;
+    if family == "SERVICE" {
+        return Err("SERVICE targets are not supported - per-service DB Time is not parsed from the reports".to_string());
+    }
+    let (f_begin_snap, f_end_snap) = snap_range;
+
+    let mut found = false;
+    let series: Vec<f64> = awrs
+        .iter()
+        .filter(|awr| awr.snap_info.begin_snap_id >= *f_begin_snap && awr.snap_info.end_snap_id <= *f_end_snap)
+        .map(|awr| {
+            let value: Option<f64> = match family.as_str() {
+                "EVENT"   => awr.foreground_wait_events.iter().find(|e| e.event == name).map(|e| e.total_wait_time_s),
+                "BGEVENT" => awr.background_wait_events.iter().find(|e| e.event == name).map(|e| e.total_wait_time_s),
+                "STAT"    => awr.instance_stats.iter().find(|st| st.statname == name).map(|st| st.total as f64),
+                "LP"      => awr.load_profile.iter().find(|lp| lp.stat_name.starts_with(&name)).map(|lp| lp.per_second),
+                "TM"      => awr.time_model_stats.iter().find(|tm| tm.stat_name == name).map(|tm| tm.time_s),
+                "IO"      => awr.io_stats_byfunc.get(&name).map(|io| io.avg_time.unwrap_or(0.0)),
+                "SQL"     => awr.sql_elapsed_time.iter().find(|sq| sq.sql_id == name).map(|sq| sq.elapsed_time_s),
+                _ => None,
+            };
+            found |= value.is_some();
+            value.unwrap_or(0.0)
+        })
+        .collect();
+
+    if !["EVENT", "BGEVENT", "STAT", "LP", "TM", "IO", "SQL"].contains(&family.as_str()) {
+        return Err(format!("unknown metric family '{}', expected EVENT, BGEVENT, STAT, LP, TM, IO or SQL", family));
+    }
+    if !found {
+        return Err(format!("'{}' was not found in any snapshot", target));
+    }
+    Ok(series)
+}
+
+/// True when the tracked stat reads the same AWR row as the `metric_series` target `FAMILY:name`.
+/// LP features are named by `display_name` ("Executes/s"), so they have to be matched by source.
+fn tracked_stat_is_target(spec: &TrackedStatSpec, target: &str) -> bool {
+    let Some((family, name)) = target.split_once(':') else { return false };
+    let (family, name) = (family.trim().to_uppercase(), name.trim());
+    match (family.as_str(), &spec.source) {
+        ("LP", StatSource::LoadProfilePerSec(prefix) | StatSource::LoadProfilePerSecScaled(prefix, _)) => {
+            let (prefix, name) = (prefix.to_lowercase(), name.to_lowercase());
+            prefix.starts_with(&name) || name.starts_with(&prefix)
         }
+        ("STAT", StatSource::InstanceStatExact(statname)) => *statname == name,
+        ("STAT", StatSource::InstanceStatPrefix(prefix)) => name.starts_with(prefix),
+        _ => false,
     }
-    (sorted_correlation,effective_threshold)
 }
 
 //Add SQL_IDs found in ASH to event charts
@@ -2472,7 +2549,7 @@ pub fn main_report_builder(collection: AWRSCollection, args: Args, events_sqls:
         eprintln!("⚠️ Failed to create base directory {:?}: {}", html_dir, e);
     }
     // Create all required subdirectories dir tree under html
-    let subdirs = ["fg", "bg", "latches", "iostats", "segstats", "sqlid", "stats","jasmin/anomalies"];
+    let subdirs = ["fg", "bg", "latches", "iostats", "segstats", "sqlid", "stats", "snapshots", "jasmin/anomalies"];
     for sub in subdirs {
         let path = Path::new(&html_dir).join(sub);
         if let Err(e) = fs::create_dir_all(&path) {
@@ -2507,7 +2584,10 @@ pub fn main_report_builder(collection: AWRSCollection, args: Args, events_sqls:
     let mut x_vals: Vec<String> = Vec::new();
     
     println!("{}","\n==== ANALYZING ===".bold().bright_cyan());
-    let top_stats: TopStats = find_top_stats(&collection.awrs, db_time_cpu_ratio, filter_db_time, &snap_range, &logfile_name, &args, &mut report_for_ai);  
+    //One detector for the whole run - seasonal buckets and regimes are computed once for all loaded snapshots
+    let workload_regimes = cluster_workload_regimes(&collection.awrs, &args);
+    let anomaly_detector = build_anomaly_detector(&collection.awrs, &args, workload_regimes.as_ref());
+    let top_stats: TopStats = find_top_stats(&collection.awrs, db_time_cpu_ratio, filter_db_time, &snap_range, anomaly_detector.as_ref(), &logfile_name, &args, &mut report_for_ai);  
     
     println!("{}","\n==== CREATING PLOTS ===".bold().bright_cyan()); 
     generate_events_plotfiles(&collection.awrs, &top_stats.events, true, &snap_range, &html_dir);
@@ -2913,6 +2993,8 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
     //println!("{}","Foreground Wait Events");
     make_notes!(&logfile_name, false, 2, "\n{}\n","Foreground Wait Events".yellow());
     let mut top_fg_events: Vec<TopForegroundWaitEvents> = Vec::new();
+    let executes_vec = raw_values_of(&tracked_stats, TrackedStatKey::ExecutesPerSec);
+    let fg_corr_thresholds = CorrelationThresholds::new(y_vals_events_sorted.len(), y_vals_dbtime.len(), args.corr_max_lag);
     
     for (key, yv) in &y_vals_events_sorted {
         let mut event_data = TopForegroundWaitEvents::default();
@@ -2936,6 +3018,13 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         } else {
             make_notes!(&logfile_name, args.quiet, 0, "{: >50}", correlation_info);
         }
+        let (_, corr_details) = correlation_details(yv, &y_vals_dbtime, &executes_vec, args.corr_max_lag, &fg_corr_thresholds);
+        let corr_details_info: String = format!("--- Spearman: {:.2} Kendall: {:.2} Lag {:+}: {:.2}", corr_details.spearman, corr_details.kendall_tau, corr_details.best_lag, corr_details.best_lag_correlation);
+        if corr_details.significant_by.is_empty() {
+            make_notes!(&logfile_name, args.quiet, 0, "\n{: >50}", corr_details_info);
+        } else {
+            make_notes!(&logfile_name, args.quiet, 0, "\n{: >50} [{}]", corr_details_info.red().bold(), corr_details.significant_by.join(", "));
+        }
 
         /* STDDEV/AVG Calculations */
         let x_n: Vec<f64> = y_vals_events_n.get(&event_name).unwrap().clone();
@@ -2962,6 +3051,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         
         event_data.event_name = event_name.clone();
         event_data.correlation_with_db_time = corr;
+        event_data.correlation_details = corr_details;
         event_data.marked_as_top_in_pct_of_probes = (x_n.len() as f64 / x_vals.len() as f64 )* 100.0;
         event_data.avg_pct_of_dbtime = avg_exec_t; 
         event_data.stddev_pct_of_db_time = stddev_exec_t;
@@ -2979,14 +3069,14 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
 
         if let Some(anomalies) = top_stats.event_anomalies_mad.get(&key.1) {
             let mut mad_events: MadAnomaliesEvents = MadAnomaliesEvents::default();
-            let anomalies_detection_msg = "Detected anomalies using Median Absolute Deviation on the following dates:".to_string().red();
+            let anomalies_detection_msg = format!("Detected anomalies using {} on the following dates:", anomaly_detector.name()).red();
             anomalies_flag = true;
             make_notes!(&logfile_name, args.quiet, 0, "\t\t{}\n",  anomalies_detection_msg);
             
             let mut table = Table::new();
             table.set_titles(Row::new(vec![
                     Cell::new("Date"),
-                    Cell::new("MAD Score"),
+                    Cell::new("Anomaly Score"),
                     Cell::new("Total Wait (s)"),
                     Cell::new("Waits"),
                     Cell::new("AVG Wait (ms)"),
@@ -3045,7 +3135,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
                 make_notes!(&logfile_name, args.quiet, 0, "\t\t{}\n", table_line);
             }
         } else {
-            let no_anomalies_txt = format!("\t\tNo anomalies detected based on MAD threshold: {}\n", args.mad_threshold);
+            let no_anomalies_txt = format!("\t\tNo anomalies detected based on {} threshold: {}\n", anomaly_detector.name(), anomaly_detector.threshold());
             anomalies_flag = false;
             make_notes!(&logfile_name, args.quiet, 0, "{}", no_anomalies_txt.green().italic());
         }
@@ -3094,7 +3184,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
                             <thead>
                                 <tr>
                                     <th onclick="sortInnerTable('{0}',0)" style="cursor: pointer;">Date</th>
-                                    <th onclick="sortInnerTable('{0}',1)" style="cursor: pointer;">MAD Score</th>
+                                    <th onclick="sortInnerTable('{0}',1)" style="cursor: pointer;">Anomaly Score</th>
                                     <th onclick="sortInnerTable('{0}',2)" style="cursor: pointer;">Total Wait (s)</th>
                                     <th onclick="sortInnerTable('{0}',3)" style="cursor: pointer;">Waits</th>
                                     <th onclick="sortInnerTable('{0}',4)" style="cursor: pointer;">AVG Wait (ms)</th>
@@ -3144,6 +3234,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
     //println!("{}","Background Wait Events");
     make_notes!(&logfile_name, false, 2, "{}\n","Background Wait Events".yellow());
     let mut top_bg_events: Vec<TopBackgroundWaitEvents> = Vec::new();
+    let bg_corr_thresholds = CorrelationThresholds::new(y_vals_bgevents_sorted.len(), y_vals_dbtime.len(), args.corr_max_lag);
 
     for (key, yv) in &y_vals_bgevents_sorted {
         let mut event_data = TopBackgroundWaitEvents::default();
@@ -3178,6 +3269,13 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         } else {
             make_notes!(&logfile_name, args.quiet, 0, "{: >50}", correlation_info);
         }
+        let (_, corr_details) = correlation_details(yv, &y_vals_dbtime, &executes_vec, args.corr_max_lag, &bg_corr_thresholds);
+        let corr_details_info: String = format!("--- Spearman: {:.2} Kendall: {:.2} Lag {:+}: {:.2}", corr_details.spearman, corr_details.kendall_tau, corr_details.best_lag, corr_details.best_lag_correlation);
+        if corr_details.significant_by.is_empty() {
+            make_notes!(&logfile_name, args.quiet, 0, "\n{: >50}", corr_details_info);
+        } else {
+            make_notes!(&logfile_name, args.quiet, 0, "\n{: >50} [{}]", corr_details_info.red().bold(), corr_details.significant_by.join(", "));
+        }
         make_notes!(&logfile_name, args.quiet, 0, "\t\tMarked as TOP in {:.2}% of probes\n",  (x_n.len() as f64 / x_vals.len() as f64 )* 100.0);
         make_notes!(&logfile_name, args.quiet, 0, "\t\t--- AVG PCT of DB Time: {:>15.2}% \tSTDDEV PCT of DB Time: {:>15.2}%\n", &avg_exec_t, &stddev_exec_t);
         make_notes!(&logfile_name, args.quiet, 0, "\t\t--- AVG Wait Time (s): {:>16.2} \tSTDDEV Wait Time (s): {:>16.2}\n", &avg_exec_s, &stddev_exec_s);
@@ -3186,6 +3284,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         
         event_data.event_name = event_name.clone();
         event_data.correlation_with_db_time = corr;
+        event_data.correlation_details = corr_details;
         event_data.marked_as_top_in_pct_of_probes = (x_n.len() as f64 / x_vals.len() as f64 )* 100.0;
         event_data.avg_pct_of_dbtime = avg_exec_t;
         event_data.stddev_pct_of_db_time = stddev_exec_t;
@@ -3205,14 +3304,14 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         if let Some(anomalies) = top_stats.bgevent_anomalies_mad.get(&key.1) {
             let mut mad_events: MadAnomaliesEvents = MadAnomaliesEvents::default();
 
-            let anomalies_detection_msg = "Detected anomalies using Median Absolute Deviation on the following dates:".to_string().red();
+            let anomalies_detection_msg = format!("Detected anomalies using {} on the following dates:", anomaly_detector.name()).red();
             anomalies_flag = true;
             make_notes!(&logfile_name, args.quiet, 0, "\t\t{}\n",  anomalies_detection_msg);
 
             let mut table = Table::new();
             table.set_titles(Row::new(vec![
                 Cell::new("Date"),
-                Cell::new("MAD Score"),
+                Cell::new("Anomaly Score"),
                 Cell::new("Total Wait (s)"),
                 Cell::new("Waits"),
                 Cell::new("AVG Wait (ms)"),
@@ -3272,7 +3371,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
                 make_notes!(&logfile_name, args.quiet, 0, "\t\t{}\n", table_line);
             }
         } else {
-            let no_anomalies_txt = format!("\t\tNo anomalies detected based on MAD threshold: {}\n", args.mad_threshold);
+            let no_anomalies_txt = format!("\t\tNo anomalies detected based on {} threshold: {}\n", anomaly_detector.name(), anomaly_detector.threshold());
             anomalies_flag = false;
             make_notes!(&logfile_name, args.quiet, 0, "{}", no_anomalies_txt.green().italic());
         }
@@ -3321,7 +3420,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
                             <thead>
                                 <tr>
                                     <th onclick="sortInnerTable('{0}',0)" style="cursor: pointer;">Date</th>
-                                    <th onclick="sortInnerTable('{0}',1)" style="cursor: pointer;">MAD Score</th>
+                                    <th onclick="sortInnerTable('{0}',1)" style="cursor: pointer;">Anomaly Score</th>
                                     <th onclick="sortInnerTable('{0}',2)" style="cursor: pointer;">Total Wait (s)</th>
                                     <th onclick="sortInnerTable('{0}',3)" style="cursor: pointer;">Waits</th>
                                     <th onclick="sortInnerTable('{0}',4)" style="cursor: pointer;">AVG Wait (ms)</th>
@@ -3496,14 +3595,14 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         let mut anomalies_flag: bool = false;
 
         if let Some(anomalies) = top_stats.sql_elapsed_time_anomalies_mad.get(&key.1) {
-            let anomalies_detection_msg = "Detected anomalies using Median Absolute Deviation on the following dates:".to_string().red();
+            let anomalies_detection_msg = format!("Detected anomalies using {} on the following dates:", anomaly_detector.name()).red();
             anomalies_flag = true;
             make_notes!(&logfile_name, args.quiet, 0, "\t\t{}\n",  anomalies_detection_msg);
 
             let mut table = Table::new();
             table.set_titles(Row::new(vec![
                 Cell::new("Date"),
-                Cell::new("MAD Score"),
+                Cell::new("Anomaly Score"),
                 Cell::new("Elapsed Time (s)"),
                 Cell::new("Executions"),
                 Cell::new("Ela time / exec (s)")
@@ -3562,7 +3661,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
             }
             
         } else {
-            let no_anomalies_txt = format!("\t\tNo anomalies detected based on MAD threshold: {}\n", args.mad_threshold);
+            let no_anomalies_txt = format!("\t\tNo anomalies detected based on {} threshold: {}\n", anomaly_detector.name(), anomaly_detector.threshold());
             anomalies_flag = false;
             make_notes!(&logfile_name, args.quiet, 0, "{}", no_anomalies_txt.green().italic());
         }
@@ -3612,7 +3711,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
                             <thead>
                                 <tr>
                                     <th onclick="sortInnerTable('{0}',0)" style="cursor: pointer;">Date</th>
-                                    <th onclick="sortInnerTable('{0}',1)" style="cursor: pointer;">MAD Score</th>
+                                    <th onclick="sortInnerTable('{0}',1)" style="cursor: pointer;">Anomaly Score</th>
                                     <th onclick="sortInnerTable('{0}',2)" style="cursor: pointer;">Total Wait (s)</th>
                                     <th onclick="sortInnerTable('{0}',3)" style="cursor: pointer;">Waits</th>
                                     <th onclick="sortInnerTable('{0}',4)" style="cursor: pointer;">AVG Wait (s)</th>
@@ -3842,6 +3941,104 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         table_sqls
     );
 
+    /* SQL regressions on per execution metrics */
+    let sql_regression_html: String = format!(
+        r#"
+        <table id="sqlreg-table">
+            <thead>
+                <tr>
+                    <th onclick="sortTable('sqlreg-table',0)" style="cursor: pointer;">SQL ID</th>
+                    <th onclick="sortTable('sqlreg-table',1)" style="cursor: pointer;">Metric</th>
+                    <th onclick="sortTable('sqlreg-table',2)" style="cursor: pointer;">Cause</th>
+                    <th onclick="sortTable('sqlreg-table',3)" style="cursor: pointer;">Change Began</th>
+                    <th onclick="sortTable('sqlreg-table',4)" style="cursor: pointer;">Median Before</th>
+                    <th onclick="sortTable('sqlreg-table',5)" style="cursor: pointer;">Median After</th>
+                    <th onclick="sortTable('sqlreg-table',6)" style="cursor: pointer;">Ratio</th>
+                    <th onclick="sortTable('sqlreg-table',7)" style="cursor: pointer;">Shift Score</th>
+                    <th onclick="sortTable('sqlreg-table',8)" style="cursor: pointer;">Median Execs Before</th>
+                    <th onclick="sortTable('sqlreg-table',9)" style="cursor: pointer;">Median Execs After</th>
+                </tr>
+            </thead>
+            <tbody>
+            {}
+            </tbody>
+        </table>
+        "#,
+        report_sql_regressions(&collection.awrs, &snap_range, &args, &logfile_name, &html_dir, &mut report_for_ai)
+    );
+
+    /* Change points - lasting level or variance shifts of DB Time, DB CPU, top wait events and load profile */
+    let mut change_point_series: BTreeMap<(String, String), Vec<f64>> = BTreeMap::new();
+    change_point_series.insert(("TM".to_string(), "DB Time (s/s)".to_string()), y_vals_dbtime.clone());
+    change_point_series.insert(("TM".to_string(), "DB CPU (s/s)".to_string()), y_vals_dbcpu.clone());
+    for (event, yv) in &y_vals_events {
+        change_point_series.insert(("EVENT".to_string(), event.clone()), yv.clone());
+    }
+    for spec in tracked_stats_specs() {
+        if matches!(spec.source, StatSource::LoadProfilePerSec(_) | StatSource::LoadProfilePerSecScaled(_, _)) {
+            change_point_series.insert(("LP".to_string(), spec.display_name.to_string()), raw_values_of(&tracked_stats, spec.key));
+        }
+    }
+
+    let change_points_html: String = format!(
+        r#"
+        <table id="changepoints-table">
+            <thead>
+                <tr>
+                    <th onclick="sortTable('changepoints-table',0)" style="cursor: pointer;">Type</th>
+                    <th onclick="sortTable('changepoints-table',1)" style="cursor: pointer;">Series</th>
+                    <th onclick="sortTable('changepoints-table',2)" style="cursor: pointer;">Change Began</th>
+                    <th onclick="sortTable('changepoints-table',3)" style="cursor: pointer;">Mean Before</th>
+                    <th onclick="sortTable('changepoints-table',4)" style="cursor: pointer;">Mean After</th>
+                    <th onclick="sortTable('changepoints-table',5)" style="cursor: pointer;">Median Before</th>
+                    <th onclick="sortTable('changepoints-table',6)" style="cursor: pointer;">Median After</th>
+                    <th onclick="sortTable('changepoints-table',7)" style="cursor: pointer;">StdDev Before</th>
+                    <th onclick="sortTable('changepoints-table',8)" style="cursor: pointer;">StdDev After</th>
+                    <th onclick="sortTable('changepoints-table',9)" style="cursor: pointer;">Change %</th>
+                </tr>
+            </thead>
+            <tbody>
+            {}
+            </tbody>
+        </table>
+        "#,
+        report_change_points(&collection.awrs, &snap_range, &change_point_series, &args, &logfile_name, &mut report_for_ai)
+    );
+    add_change_point_trace(&mut plot_main, &report_for_ai.change_points, &x_vals, &y_vals_dbtime);
+
+    /* DB Time decomposition - DB CPU + wait classes per snapshot and what changed in top peaks vs median */
+    report_db_time_decomposition(&collection.awrs, &snap_range, &args, &logfile_name, &html_dir, &mut report_for_ai);
+
+    /* CPU capacity - Average Active Sessions vs host and instance CPUs */
+    let cpu_capacity_plot: String = report_cpu_capacity(&collection.awrs, &snap_range, &collection.db_instance_information, &args, &logfile_name, &mut report_for_ai);
+
+    /* Capacity forecast - Holt-Winters projection of workload and threshold crossing dates */
+    report_capacity_forecast(&collection.awrs, &snap_range, &tracked_stats, &args, &logfile_name, &html_dir, &mut report_for_ai);
+
+    /* Workload regimes - clusters of similar snapshots, main chart is colored by regime */
+    let workload_regimes = report_workload_regimes(&collection.awrs, &snap_range, workload_regimes, &args, &logfile_name, &html_dir, &mut report_for_ai);
+    if let Some(regimes) = &workload_regimes {
+        add_regime_traces(&mut plot_main, regimes, &collection.awrs, &snap_range, &x_vals, &y_vals_dbtime);
+    }
+
+    /* Initialization parameters - changes between snapshots vs DB Time change points and risky settings */
+    let parameters_page = report_parameters(&collection, &snap_range, &args, &logfile_name, &html_dir, &mut report_for_ai);
+
+    /* Redo and commits - redo MB per log switch, recommended log size, log file sync vs log file parallel write */
+    let redo_page = report_redo_commits(&collection.awrs, &snap_range, &args, &logfile_name, &html_dir, &mut report_for_ai);
+
+    /* Shared pool - library cache reloads and invalidations, dictionary cache misses vs hard parses and mutex waits */
+    let shared_pool_page = report_shared_pool_health(&collection.awrs, &snap_range, &args, &logfile_name, &html_dir, &mut report_for_ai);
+
+    /* Latches and mutexes - latch: and mutex wait events mapped to Latch Sleep Breakdown and Mutex Sleep Summary */
+    let latches_page = report_latch_mutex_contention(&collection.awrs, &snap_range, &args, &logfile_name, &html_dir, &mut report_for_ai);
+
+    /* IO latency SLOs - average latency of IO functions and wait events vs --io-slo limits */
+    let io_slo_page = report_io_slo(&collection.awrs, &snap_range, &args, &logfile_name, &html_dir, &mut report_for_ai);
+
+    /* Calendar heatmaps - hour of day x day of week and date x hour for DB Time, AAS, top events and SQLs, --heatmaps */
+    let heatmaps_page = report_heatmaps(&collection.awrs, &snap_range, &tracked_stats, &args, &logfile_name, &html_dir, &mut report_for_ai);
+
     /* If ASH data is present, add SQL_ID information to wait event html reports */
     if !ash_event_sql_map.is_empty() {
         merge_ash_sqls_to_events(ash_event_sql_map, &html_dir);
@@ -3917,18 +4114,29 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
     let segstats = report_segments_summary(&collection.awrs, &args, &logfile_name, &html_dir, &mut report_for_ai);
     /********************************************/
 
-    let mut sorted_correlation = report_instance_stats_cor(instance_stats.clone(), y_vals_dbtime.clone());
-    let corr_txt = format!("Instance Statistics: Correlation with DB Time for values >= {} and <= -{}", sorted_correlation.1, sorted_correlation.1);
+    /* Hot objects - segment statistics over time, persistence, correlation with DB Time and top SQLs touching them */
+    let hot_objects_page = report_hot_objects(&collection.awrs, &snap_range, &collection.sql_text, &args, &logfile_name, &html_dir, &mut report_for_ai);
+
+    let mut sorted_correlation = report_instance_stats_cor(instance_stats.clone(), y_vals_dbtime.clone(), &raw_values_of(&tracked_stats, TrackedStatKey::ExecutesPerSec), args.corr_max_lag);
+    let corr_thresholds = sorted_correlation.1;
+    let corr_txt = format!("Instance Statistics: Correlation with DB Time - Bonferroni thresholds |r| >= {:.3} (Pearson/Spearman), |tau| >= {:.3} (Kendall), |r| >= {:.3} (lags -{}..+{}), |r| >= {:.3} (partial given Executes/s)",
+                           corr_thresholds.pearson, corr_thresholds.kendall, corr_thresholds.lagged, args.corr_max_lag, args.corr_max_lag, corr_thresholds.partial);
     make_notes!(&logfile_name, args.quiet, 0, "\n\n");
     make_notes!(&logfile_name, false, 2, "{}", corr_txt.yellow());
     make_notes!(&logfile_name, args.quiet, 0, "\n\n");
     
     let mut stats_table_rows = String::new();
-    for ((score, key), value) in sorted_correlation.0.iter().rev() { // Sort in descending order
+    for ((score, key), (value, details)) in sorted_correlation.0.iter().rev() { // Sort in descending order
         stats_table_rows.push_str(&format!(
-            r#"<tr><td>{}</td><td>{:.3}</td></tr>"#,
+            r#"<tr><td>{}</td><td>{:.3}</td><td>{:.3}</td><td>{:.3}</td><td>{:+}</td><td>{:.3}</td><td>{}</td><td>{}</td></tr>"#,
             key,
-            value
+            value,
+            details.spearman,
+            details.kendall_tau,
+            details.best_lag,
+            details.best_lag_correlation,
+            details.partial_correlation_given_executes.map_or("-".to_string(), |p| format!("{:.3}", p)),
+            details.significant_by.join(", ")
         ));
     }
     table_stat_corr = format!(
@@ -3942,7 +4150,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
                 body {{ font-family: Arial, sans-serif; }}
                 .content {{ font-size: 14px; }}
                 table {{
-                    width: 30%;
+                    width: 70%;
                     border-collapse: collapse;
                     margin-top: 20px;
                 }}
@@ -3991,12 +4199,20 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
                 <p><a href="https://github.com/ora600pl/jas-min" target="_blank">
                 <img src="https://raw.githubusercontent.com/rakustow/jas-min/main/img/jasmin_LOGO_white.png" width="150" alt="JAS-MIN" onerror="this.style.display='none';"/>
                 </a></p>
-                <p><span style="font-size:20px;font-weight:bold;">Correlation of Instance Statistics with DB Time for Values >= {} and <= -{}</span></p>
+                <p><span style="font-size:20px;font-weight:bold;">Correlation of Instance Statistics with DB Time</span></p>
+                <p>Bonferroni corrected thresholds: |r| >= {:.3} for Pearson and Spearman, |tau| >= {:.3} for Kendall, |r| >= {:.3} for lags -{}..+{}, |r| >= {:.3} for partial correlation given Executes/s.<br>
+                Positive lag means that the statistic leads DB Time.</p>
                 <table id="stats_corr_table" >
                     <thead>
                         <tr>
                             <th onclick="sortTable('stats_corr_table',0)" style="cursor: pointer;">Instance Statistic</th>
-                            <th onclick="sortTable('stats_corr_table',1)" style="cursor: pointer;">Correlation</th>
+                            <th onclick="sortTable('stats_corr_table',1)" style="cursor: pointer;">Pearson</th>
+                            <th onclick="sortTable('stats_corr_table',2)" style="cursor: pointer;">Spearman</th>
+                            <th onclick="sortTable('stats_corr_table',3)" style="cursor: pointer;">Kendall Tau</th>
+                            <th onclick="sortTable('stats_corr_table',4)" style="cursor: pointer;">Best Lag</th>
+                            <th onclick="sortTable('stats_corr_table',5)" style="cursor: pointer;">Correlation at Best Lag</th>
+                            <th onclick="sortTable('stats_corr_table',6)" style="cursor: pointer;">Partial (Executes/s)</th>
+                            <th onclick="sortTable('stats_corr_table',7)" style="cursor: pointer;">Significant By</th>
                         </tr>
                     </thead>
                     <tbody>
@@ -4007,7 +4223,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         </body>
         </html>
         "#,
-        sorted_correlation.1, sorted_correlation.1, stats_table_rows
+        corr_thresholds.pearson, corr_thresholds.kendall, corr_thresholds.lagged, args.corr_max_lag, args.corr_max_lag, corr_thresholds.partial, stats_table_rows
     );
 
     // Write to the file
@@ -4015,12 +4231,13 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
     if let Err(e) = fs::write(&stats_corr_filename, table_stat_corr) {
         eprintln!("Error writing file {}: {}", stats_corr_filename, e);
     }
-    for (k,v) in sorted_correlation.0 {
-        make_notes!(&logfile_name, args.quiet, 0, "\t{: >64} : {:.2}\n", &k.1, v);
-        report_for_ai.instance_stats_pearson_correlation.push(InstanceStatisticCorrelation{stat_name: k.1.clone(), pearson_correlation_value: v});
+    for (k,(v, details)) in sorted_correlation.0 {
+        make_notes!(&logfile_name, args.quiet, 0, "\t{: >64} : {:.2} \tspearman: {:.2} \tkendall: {:.2} \tlag {:+}: {:.2} \t[{}]\n",
+                    &k.1, v, details.spearman, details.kendall_tau, details.best_lag, details.best_lag_correlation, details.significant_by.join(", "));
+        report_for_ai.instance_stats_pearson_correlation.push(InstanceStatisticCorrelation{stat_name: k.1.clone(), pearson_correlation_value: v, correlation_details: details});
     }
     /* Add information about stats anomalies to the summary */
-    let stat_anomalies = detect_stats_anomalies_mad(&collection.awrs, &args);
+    let stat_anomalies = detect_stats_anomalies_mad(&collection.awrs, anomaly_detector.as_ref(), args.mad_threshold);
     let all_stats = top_stats.stat_names;
     for s in all_stats {
         if let Some(anomalies) = stat_anomalies.get(&s.0) {
@@ -4041,7 +4258,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
     /********************************************************/
 
     /* Add information about Dictionary Cache anomalies to the summary */
-    let stat_anomalies = detect_dc_anomalies_mad(&collection.awrs, &args);
+    let stat_anomalies = detect_dc_anomalies_mad(&collection.awrs, anomaly_detector.as_ref(), args.mad_threshold);
     let all_stats: HashSet<String> = collection.awrs
                                     .iter()
                                     .flat_map(|a| a.dictionary_cache.clone())
@@ -4066,7 +4283,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
 
 
     /* Add information about Library Cache anomalies to the summary */
-    let stat_anomalies = detect_libcache_anomalies_mad(&collection.awrs, &args);
+    let stat_anomalies = detect_libcache_anomalies_mad(&collection.awrs, anomaly_detector.as_ref(), args.mad_threshold);
     let all_stats: HashSet<String> = collection.awrs
                                     .iter()
                                     .flat_map(|a| a.library_cache.clone())
@@ -4091,7 +4308,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
     /********************************************************/
 
     /* Add information about Latch Activity anomalies to the summary */
-    let stat_anomalies = detect_latch_activity_anomalies_mad(&collection.awrs, &args);
+    let stat_anomalies = detect_latch_activity_anomalies_mad(&collection.awrs, anomaly_detector.as_ref(), args.mad_threshold);
     let all_stats: HashSet<String> = collection.awrs
                                     .iter()
                                     .flat_map(|a| a.latch_activity.clone())
@@ -4117,7 +4334,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
 
 
     /* Add information about Time Model anomalies to the summary */
-    let stat_anomalies = detect_time_model_anomalies_mad(&collection.awrs, &args);
+    let stat_anomalies = detect_time_model_anomalies_mad(&collection.awrs, anomaly_detector.as_ref(), args.mad_threshold);
     let all_stats: HashSet<String> = collection.awrs
                                     .iter()
                                     .flat_map(|a| a.time_model_stats.clone())
@@ -4146,14 +4363,14 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
     make_notes!(&logfile_name, false, 0, "\n\n");
     make_notes!(&logfile_name, false, 1, "{}\n", "ANOMALIES".bold().green());
      /* Load Profile Anomalies detection and report */
-    make_notes!(&logfile_name, false, 2, "\n{} {}\n", "Load Profile Anomalies detection using Median Absolute Deviation threshold:".yellow(), args.mad_threshold);
+    make_notes!(&logfile_name, false, 2, "\n{} {} {}\n", "Load Profile Anomalies detection using".yellow(), anomaly_detector.name().yellow(), format!("threshold: {}", anomaly_detector.threshold()).yellow());
     
     let all_loadprofile: HashSet<String> = collection.awrs
                                                 .iter()
                                                 .flat_map(|awr| &awr.load_profile)
                                                 .map(|l| l.stat_name.clone())
                                                 .collect();
-    let profile_anomalies = detect_loadprofile_anomalies_mad(&collection.awrs, &args);
+    let profile_anomalies = detect_loadprofile_anomalies_mad(&collection.awrs, anomaly_detector.as_ref(), args.mad_threshold);
     for l in all_loadprofile {
         let stat_name = l.bold();
         let per_second_v: Vec<f64>      = collection.awrs
@@ -4168,8 +4385,8 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
             let mut table = Table::new();
             table.set_titles(Row::new(vec![
                 Cell::new("Date"),
-                Cell::new("MAD Score"),
-                Cell::new("MAD Threshold"),
+                Cell::new("Anomaly Score"),
+                Cell::new("Threshold"),
                 Cell::new("Per Second"),
                 Cell::new("AVG Per Second"),
             ]));
@@ -4192,14 +4409,14 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
                 let c_mad = Cell::new(&format!("{:.2}", a.1));
                 let c_per_second = Cell::new(&format!("{}",per_second_this_date));
                 let c_avg_per_second = Cell::new(&format!("{:.2}", mean_per_s));
-                let c_mad_threshold = Cell::new(&format!("{}", args.mad_threshold));
+                let c_mad_threshold = Cell::new(&format!("{}", anomaly_detector.threshold()));
                 table.add_row(Row::new(vec![c_date, c_mad, c_mad_threshold, c_per_second, c_avg_per_second]));
 
                 report_for_ai.load_profile_anomalies.push(LoadProfileAnomalies {
                     load_profile_stat_name: l.clone(),
                     anomaly_date: a.0.clone(),
                     mad_score: a.1,
-                    mad_threshold: 7.0,
+                    mad_threshold: anomaly_detector.threshold(),
                     per_second: per_second_this_date,
                     avg_value_per_second: mean_per_s,
                 });
@@ -4224,6 +4441,9 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         }
     }
     /***********************************************/
+
+    /* Multivariate anomalies - snapshots where many metrics are unusual at the same time */
+    let mv_anomalies = report_multivariate_anomalies(&collection.awrs, &snap_range, &args, &logfile_name, &mut anomalies_summary);
     
     let anomalies_summary_html: String = format!(
         r#"
@@ -4243,6 +4463,33 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         "#,
         report_anomalies_summary(&mut anomalies_summary, &args, &logfile_name, &mut report_for_ai)
     );
+
+    for cluster in report_for_ai.anomaly_clusters.iter_mut() {
+        if let Some(mv) = mv_anomalies.iter().find(|a| a.begin_snap_id == cluster.begin_snap_id) {
+            cluster.multivariate_anomaly_score = Some(mv.anomaly_score);
+            cluster.multivariate_top_contributors = mv.top_contributors.clone();
+        }
+    }
+
+    if !mv_anomalies.is_empty() {
+        let mv_hover: Vec<String> = mv_anomalies
+            .iter()
+            .map(|a| a.top_contributors
+                      .iter()
+                      .map(|c| format!("{}: z={:.1} ({:.1}%)", c.metric, c.robust_z, c.contribution_pct))
+                      .collect::<Vec<String>>()
+                      .join("<br>"))
+            .collect();
+        let mv_trace = Scatter::new(x_vals.clone(), mv_anomalies.iter().map(|a| a.anomaly_score).collect::<Vec<f64>>())
+            .mode(Mode::LinesMarkers)
+            .name("Multivariate Anomaly Score")
+            .text_array(mv_hover)
+            .hover_template("<b>Multivariate Anomaly Score</b><br>Snap: %{x}<br>Score: %{y:.2f}<br>%{text}<extra></extra>")
+            .marker(Marker::new().size(4))
+            .x_axis("x1")
+            .y_axis("y2");
+        plot_main.add_trace(mv_trace);
+    }
     
     let mut snap_dates: Vec<String> = Vec::new();
     let mut anomaly_types: BTreeMap<String, usize> = BTreeMap::new();
@@ -4287,7 +4534,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
     /*************************/
         
     // Prepare Plots LAYOUTS
-    let layout_main: Layout = Layout::new()
+    let mut layout_main: Layout = Layout::new()
         .height(1500)
         .grid(
             LayoutGrid::new()
@@ -4588,6 +4835,14 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         );
 
 
+    add_change_point_shapes(&mut layout_main, &report_for_ai.change_points);
+    if let Some(regimes) = &workload_regimes {
+        add_regime_shapes(&mut layout_main, regimes, &collection.awrs, &snap_range);
+    }
+
+    /* Snapshot drill-down pages - every snapshot next to the median of its surrounding window, opened from the main chart */
+    let snapshot_pages = generate_snapshot_pages(&collection.awrs, &snap_range, &anomalies_summary, &args, &html_dir);
+
     println!("\n{}","==== GENERATING PLOTS ====".bold().bright_cyan());
     plot_main.set_layout(layout_main);
     plot_highlight.set_layout(layout_highlight);
@@ -4717,6 +4972,8 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         toggleTable('show-sqls-button', 'sqls-table');
         toggleTable('show-bgevents-button', 'bgevents-table');
         toggleTable('show-anomalies-button', 'anomalies-sum-table');
+        toggleTable('show-sqlreg-button', 'sqlreg-table');
+        toggleTable('show-changepoints-button', 'changepoints-table');
         toggleTable('show-JASMINAI-button', 'chat-container');
         function sortTable(tableId, columnId) {{
             const table = document.getElementById(tableId);
@@ -4846,6 +5103,7 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
             toggleElement('show-iostats-button','iostat_zMAIN-html-element','iocheckbox-container');
             toggleElement('show-segstats-button',null,'segcheckbox-container');
             toggleElement('show-insteff-button','instance-efficiency-plot','');
+            toggleElement('show-cpucap-button','cpu-capacity-plot','');
             toggleElement('show-lpmore-button','highlight2-html-element','');
             toggleTable('show-latchstats-button','latchstat-table');
             const iocheckboxes = document.querySelectorAll('input[type="checkbox"][id$="-iocheckbox"]');
@@ -4901,33 +5159,122 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
     // Inject Buttons and Tables into Main HTML
     plotly_html = plotly_html.replace(
         "<body>",
-        &format!("<body>\n{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}",
+        &format!("<body>\n{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}\n\t{}",
             jasmin_logo,
             db_instance_info_html,
             "<button id=\"show-events-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">TOP Wait Events</span><span>TOP Wait Events</span></button>",
             "<button id=\"show-sqls-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">TOP Wait SQLs</span><span>TOP Wait SQLs</span></button>",
             "<button id=\"show-bgevents-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">TOP Backgrd Events</span><span>TOP Backgrd Events</span></button>",
             "<button id=\"show-anomalies-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Anomalies Summary</span><span>Anomalies Summary</span></button>",
+            "<button id=\"show-sqlreg-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">SQL Regressions</span><span>SQL Regressions</span></button>",
+            "<button id=\"show-changepoints-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Change Points</span><span>Change Points</span></button>",
             format!(
                 "<a href=\"stats/statistics_corr.html\" target=\"_blank\" style=\"text-decoration: none;\">
                     <button id=\"show-stat_corr-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">STATS Correlation</span><span>STATS Correlation</span></button>
                 </a>
+                <a href=\"stats/db_time_decomposition.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                    <button id=\"show-dbtime_decomp-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">DB Time Decomposition</span><span>DB Time Decomposition</span></button>
+                </a>
+                {}
                 <a href=\"stats/gradient.html\" target=\"_blank\" style=\"text-decoration: none;\">
-                    <button id=\"show-stat_corr-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">DB Time Gradient Analyzes</span><span>DB Time Gradient Analyzes</span></button>
+                    <button id=\"show-gradient-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">DB Time Gradient Analyzes</span><span>DB Time Gradient Analyzes</span></button>
                 </a>
                 <a href=\"stats/gradient_cpu.html\" target=\"_blank\" style=\"text-decoration: none;\">
-                    <button id=\"show-stat_corr-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">DB CPU Gradient Analyzes</span><span>DB CPU Gradient Analyzes</span></button>
+                    <button id=\"show-gradient_cpu-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">DB CPU Gradient Analyzes</span><span>DB CPU Gradient Analyzes</span></button>
                 </a>
+                {}
                 {}",
+                format!("{}{}{}{}{}{}{}{}{}{}",
+                    if parameters_page {
+                        "<a href=\"stats/parameters.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                    <button id=\"show-parameters-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Parameters</span><span>Parameters</span></button>
+                </a>"
+                    } else {
+                        ""
+                    },
+                    if redo_page {
+                        "<a href=\"stats/redo_commits.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                    <button id=\"show-redo-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Redo & Commits</span><span>Redo & Commits</span></button>
+                </a>"
+                    } else {
+                        ""
+                    },
+                    if shared_pool_page {
+                        "<a href=\"stats/shared_pool.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                    <button id=\"show-shared_pool-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Shared Pool</span><span>Shared Pool</span></button>
+                </a>"
+                    } else {
+                        ""
+                    },
+                    if latches_page {
+                        "<a href=\"stats/latches_mutexes.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                    <button id=\"show-latches-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Latches & Mutexes</span><span>Latches & Mutexes</span></button>
+                </a>"
+                    } else {
+                        ""
+                    },
+                    if io_slo_page {
+                        "<a href=\"stats/io_slo.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                    <button id=\"show-io_slo-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">IO Latency SLO</span><span>IO Latency SLO</span></button>
+                </a>"
+                    } else {
+                        ""
+                    },
+                    if heatmaps_page {
+                        "<a href=\"stats/heatmaps.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                    <button id=\"show-heatmaps-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Heatmaps</span><span>Heatmaps</span></button>
+                </a>"
+                    } else {
+                        ""
+                    },
+                    if hot_objects_page {
+                        "<a href=\"stats/hot_objects.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                    <button id=\"show-hot_objects-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Hot Objects</span><span>Hot Objects</span></button>
+                </a>"
+                    } else {
+                        ""
+                    },
+                    if !(args.no_builtin_rules && args.rules.is_empty()) {
+                        "<a href=\"stats/rule_findings.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                    <button id=\"show-rule_findings-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Rule Findings</span><span>Rule Findings</span></button>
+                </a>"
+                    } else {
+                        ""
+                    },
+                    if !report_for_ai.capacity_forecast.is_empty() {
+                        "<a href=\"stats/capacity_forecast.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                    <button id=\"show-capacity_forecast-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Capacity Forecast</span><span>Capacity Forecast</span></button>
+                </a>"
+                    } else {
+                        ""
+                    },
+                    if !report_for_ai.workload_regimes.is_empty() {
+                        "<a href=\"stats/workload_regimes.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                    <button id=\"show-workload_regimes-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Workload Regimes</span><span>Workload Regimes</span></button>
+                </a>"
+                    } else {
+                        ""
+                    }
+                ),
                 if !args.gradient_sql.is_empty() {
                     format!(
                         "<a href=\"stats/gradient_sqlid.html\" target=\"_blank\" style=\"text-decoration: none;\">
-                            <button id=\"show-stat_corr-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">SQL_ID Gradient ({})</span><span>Custom Gradient ({})</span></button>
+                            <button id=\"show-gradient_sqlid-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">SQL_ID Gradient ({})</span><span>Custom Gradient ({})</span></button>
                         </a>",
                         args.gradient_sql, args.gradient_sql
                     )
                 } else {
                     String::new()
+                },
+                if !args.gradient_target.is_empty() {
+                    format!(
+                        "<a href=\"stats/gradient_target.html\" target=\"_blank\" style=\"text-decoration: none;\">
+                            <button id=\"show-gradient_target-button\" class=\"button-JASMIN\" role=\"button\"><span class=\"text\">Gradient ({})</span><span>Custom Gradient ({})</span></button>
+                        </a>",
+                        args.gradient_target, args.gradient_target
+                    )
+                } else {
+                    String::new()
                 }
             ),
             if !args.backend_assistant.is_empty() { 
@@ -4939,18 +5286,21 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
             bgevent_table_html,
             anomalies_summary_html,
             sqls_table_html,
+            sql_regression_html,
+            change_points_html,
             jasmin_html_scripts)
     );
     let highlight_title = "<div><h4 style=\"margin-top: 40px;margin-bottom: 0px; width: 100%; text-align: center;\">Load Profile</h4></div>\n";
     let explorer_title = "<div><h4 style=\"margin-top: 40px;margin-bottom: 0px; width: 100%; text-align: center;\">Stats Explorer</h4></div>\n";
     let insight_title = "<div><h4 style=\"margin-top: 40px;margin-bottom: 0px; width: 100%; text-align: center;\">Performance Insight</h4></div>\n";
     let explorer_button = format!(
-        "\n\t{}\t{}\t{}\t{}\n\t<div id=\"iocheckbox-container\" style=\"margin-top: 10px; display: none;\">{}\n\t</div>
+        "\n\t{}\t{}\t{}\t{}\t{}\n\t<div id=\"iocheckbox-container\" style=\"margin-top: 10px; display: none;\">{}\n\t</div>
     <div id=\"segcheckbox-container\" style=\"margin-top: 10px; display: none;\">\n{}\n\t</div>\n",
         "<button id=\"show-iostats-button\" class=\"button-JASMIN-small\" role=\"button\"><span class=\"text\">IO Stats</span><span>IO Stats</span></button>",
         "<button id=\"show-segstats-button\" class=\"button-JASMIN-small\" role=\"button\"><span class=\"text\">SEGMENTS Stats</span><span>SEGMENTS Stats</span></button>",
         "<button id=\"show-latchstats-button\" class=\"button-JASMIN-small\" role=\"button\"><span class=\"text\">LATCH Stats</span><span>LATCH Stats</span></button>",
         "<button id=\"show-insteff-button\" class=\"button-JASMIN-small\" role=\"button\"><span class=\"text\">INSTANCE Efficiency</span><span>INSTANCE Efficiency</span></button>",
+        "<button id=\"show-cpucap-button\" class=\"button-JASMIN-small\" role=\"button\"><span class=\"text\">CPU Capacity</span><span>CPU Capacity</span></button>",
         iostats
             .keys()
             .filter(|&func| func != "zMAIN")
@@ -5022,6 +5372,14 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         &format!("{}\n\t\t\t\t</script><div id=\"plotly-html-element\" class=\"plotly-graph-div\" style=\"height:100%; width:100%;\">",
         instance_eff_plot)
     );
+
+    if !cpu_capacity_plot.is_empty() {
+        plotly_html = plotly_html.replace(
+            "<div id=\"plotly-html-element\" class=\"plotly-graph-div\" style=\"height:100%; width:100%;\">", 
+            &format!("{}\n\t\t\t\t</script><div id=\"plotly-html-element\" class=\"plotly-graph-div\" style=\"height:100%; width:100%;\">",
+            cpu_capacity_plot)
+        );
+    }
     
     plotly_html = plotly_html.replace(
         "<div id=\"plotly-html-element\" class=\"plotly-graph-div\" style=\"height:100%; width:100%;\">", 
@@ -5164,6 +5522,65 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
 
     }
 
+    // Gradient against a target chosen by user - one section for each predictor family
+    let mut target_gradient_specs: Vec<(GradientSectionSpec, String)> = Vec::new();
+    let mut target_series: Vec<f64> = Vec::new();
+    if !args.gradient_target.is_empty() {
+        match metric_series(&collection.awrs, &snap_range, &args.gradient_target) {
+            Ok(series) => target_series = series,
+            Err(e) => make_notes!(&logfile_name, false, 1, "\n\n{} {}\n", "Gradient for custom target skipped:".red(), e),
+        }
+    }
+    if !target_series.is_empty() {
+        let (target_family, target_name) = args.gradient_target.split_once(':')
+            .map(|(f, n)| (f.trim().to_uppercase(), n.trim()))
+            .unwrap_or_default();
+        let mut unknown_families: Vec<String> = Vec::new();
+        for family in args.gradient_target_features.split(',').map(|f| f.trim().to_uppercase()) {
+            let features: BTreeMap<String, Vec<f64>> = match family.as_str() {
+                "EVENT" => y_vals_events.iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+                "BGEVENT" => y_vals_bgevents_sorted.iter()
+                    .map(|((_, k), v)| (k.clone(), v.clone()))
+                    .collect(),
+                "STAT" => instance_stats.iter()
+                    .filter(|(k, _)| is_in_any_categhory(k))
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+                "SQL" => y_vals_sqls.iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+                "LP" => tracked_stats_specs().iter()
+                    .filter(|spec| !tracked_stat_is_target(spec, &args.gradient_target))
+                    .map(|spec| (spec.display_name.to_string(), raw_values_of(&tracked_stats, spec.key)))
+                    .filter(|(_, v)| v.len() == target_series.len())
+                    .collect(),
+                _ => {
+                    unknown_families.push(family);
+                    continue;
+                }
+            };
+            //Target can't explain itself - LP specs were already filtered by source
+            let features: BTreeMap<String, Vec<f64>> = features.into_iter()
+                .filter(|(k, _)| family != target_family || k != target_name)
+                .collect();
+            target_gradient_specs.push((
+                GradientSectionSpec {
+                    target: &target_series,
+                    features,
+                    label: format!("target_{}", family.to_lowercase()),
+                    is_events: false,
+                    display_name: format!("GRADIENT of {} for {}", args.gradient_target, family),
+                },
+                family,
+            ));
+        }
+        if !unknown_families.is_empty() {
+            make_notes!(&logfile_name, false, 1, "\n{} {}\n", "Unknown predictor family for gradient target, expected EVENT, BGEVENT, STAT, SQL or LP:".red(), unknown_families.join(", "));
+        }
+    }
+
     // Process all sections in a loop
     let mut gradient_results: HashMap<&str, String> = HashMap::new();
 
@@ -5196,6 +5613,29 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
         gradient_results.insert(tag, html);
     }
 
+    let mut target_gradient_sections: Vec<GradientHtmlSection> = Vec::new();
+    for (spec, family) in &target_gradient_specs {
+        let (section, html) = run_gradient_section(
+            spec,
+            ridge_lambda,
+            elastic_net_lambda,
+            elastic_net_alpha,
+            elastic_net_max_iter,
+            elastic_net_tol,
+            &logfile_name,
+            &args,
+        );
+        if let Some(mut gradient) = section {
+            gradient.settings.input_db_time_unit = args.gradient_target.clone();
+            report_for_ai.target_gradients.push(TargetGradientSection {
+                target: args.gradient_target.clone(),
+                feature_family: family.clone(),
+                gradient,
+            });
+        }
+        target_gradient_sections.push(GradientHtmlSection { heading: format!("{} vs {}", args.gradient_target, family), html });
+    }
+
     // Extract HTML for template rendering
     let gradient_events      = gradient_results.remove("fg_wait_events").unwrap_or_default();
     let gradient_stats_cnt   = gradient_results.remove("instance_stats_counters").unwrap_or_default();
@@ -5205,9 +5645,59 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
     let gradient_cpu_stats_all = gradient_results.remove("cpu_instance_stats").unwrap_or_default();
     let gradient_cpu_sqls    = gradient_results.remove("cpu_sql_cpu_time").unwrap_or_default();
 
+    // Rolling gradient of DB Time vs wait events - sliding windows or change-point segments
+    let mut gradient_rolling_html = String::new();
+    if args.gradient_windows != "none" {
+        let snaps: Vec<(u64, String)> = collection.awrs.iter()
+            .filter(|awr| awr.snap_info.begin_snap_id >= snap_range.0 && awr.snap_info.end_snap_id <= snap_range.1)
+            .map(|awr| (awr.snap_info.begin_snap_id, awr.snap_info.begin_snap_time.clone()))
+            .collect();
+        let windows = match args.gradient_windows.as_str() {
+            "sliding" => sliding_windows(y_vals_dbtime.len(), args.gradient_window_size, args.gradient_window_step),
+            "segments" => {
+                let change_points: Vec<usize> = report_for_ai.change_points.iter()
+                    .filter(|cp| is_db_time_change_point(cp))
+                    .filter_map(|cp| snaps.iter().position(|s| s.0 == cp.change_begin_snap_id))
+                    .collect();
+                segment_windows(y_vals_dbtime.len(), &change_points)
+            },
+            other => {
+                make_notes!(&logfile_name, false, 1, "\n{} {}\n", "Unknown rolling gradient mode, expected none, sliding or segments:".red(), other);
+                Vec::new()
+            }
+        };
+        if !windows.is_empty() {
+            println!("\n\nBuilding rolling gradient for wait events - {} windows", windows.len());
+            let rolling = compute_rolling_gradient(&y_vals_dbtime, &y_vals_events, &windows, &snaps, ridge_lambda);
+            gradient_rolling_html = print_rolling_gradient_heatmap(&rolling, &logfile_name, &args);
+            report_for_ai.db_time_gradient_rolling = rolling;
+        }
+    }
+
+    // Gradient of DB Time vs wait events within each workload regime
+    let mut gradient_regimes_html = String::new();
+    if args.gradient_per_regime {
+        if let Some(regimes) = &workload_regimes {
+            let labels: Vec<Option<usize>> = collection.awrs.iter()
+                .filter(|awr| awr.snap_info.begin_snap_id >= snap_range.0 && awr.snap_info.end_snap_id <= snap_range.1)
+                .map(|awr| regimes.label_of(awr.snap_info.begin_snap_id))
+                .collect();
+            println!("\n\nBuilding gradient for wait events - {} workload regimes", regimes.names.len());
+            let per_regime = compute_regime_gradient(&y_vals_dbtime, &y_vals_events, &labels, regimes.names.len(), ridge_lambda);
+            for (summary, top_items) in report_for_ai.workload_regimes.iter_mut().zip(per_regime) {
+                summary.db_time_gradient_top = top_items;
+            }
+            gradient_regimes_html = print_regime_gradient_heatmap(&report_for_ai.workload_regimes, &logfile_name, &args);
+        } else {
+            make_notes!(&logfile_name, false, 1, "\n{}\n", "Gradient per regime requires workload regimes (--regimes auto or number of regimes)".red());
+        }
+    }
+
     // ---- DB Time gradient page ----
     let db_time_sections = vec![
         GradientHtmlSection { heading: "DB Time vs Wait Events".to_string(),        html: gradient_events },
+        GradientHtmlSection { heading: "DB Time vs Wait Events - Rolling Windows".to_string(), html: gradient_rolling_html },
+        GradientHtmlSection { heading: "DB Time vs Wait Events - per Workload Regime".to_string(), html: gradient_regimes_html },
         GradientHtmlSection { heading: "DB Time vs Statistic Counters".to_string(), html: gradient_stats_cnt },
         GradientHtmlSection { heading: "DB Time vs Statistic Volumes".to_string(),  html: gradient_stats_volume },
         GradientHtmlSection { heading: "DB Time vs Statistic Time".to_string(),     html: gradient_stats_time },
@@ -5257,13 +5747,33 @@ let user_calls_box_plot = BoxPlot::new(raw_values_of(&tracked_stats, TrackedStat
             sql_gradient_sections,
             );
         
-        let gradient_html = add_links_to_html(gradient_html, events_sqls, "..".to_string(), html_dir.clone());
+        let gradient_html = add_links_to_html(gradient_html, events_sqls.clone(), "..".to_string(), html_dir.clone());
         let gradient_filename: String = format!("{}/stats/gradient_sqlid.html", &html_dir);
         if let Err(e) = fs::write(&gradient_filename, gradient_html) {
             eprintln!("Error writing file {}: {}", gradient_filename, e);
         }
     }
 
+    if !target_gradient_sections.is_empty() {
+        let gradient_html = build_gradient_html(
+            "Gradient Analyzes",
+            &format!("Gradient Analyzes for {}", args.gradient_target),
+            target_gradient_sections,
+        );
+        let gradient_html = add_links_to_html(gradient_html, events_sqls.clone(), "..".to_string(), html_dir.clone());
+        let gradient_filename: String = format!("{}/stats/gradient_target.html", &html_dir);
+        if let Err(e) = fs::write(&gradient_filename, gradient_html) {
+            eprintln!("Error writing file {}: {}", gradient_filename, e);
+        }
+    }
+
+    /* Diagnostic rules - evaluated last, so rules can use every section of ReportForAI */
+    report_rule_findings(&collection.awrs, &snap_range, &collection.initialization_parameters, &args, &logfile_name, &html_dir, &mut report_for_ai);
+
+    if snapshot_pages {
+        plotly_html = plotly_html.replace("</body>", &format!("{}\n</body>", SNAPSHOT_CLICK_SCRIPT));
+    }
+
     // Write the updated HTML back to the file
     fs::write(&fname, plotly_html)
         .expect("Failed to write updated Plotly HTML file");
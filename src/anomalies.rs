@@ -10,7 +10,8 @@ use crate::make_notes;
 use colored::*;
 use open::*; 
 use crate::tools::*; 
-use crate::reasonings::{StatisticsDescription,TopPeaksSelected,MadAnomaliesEvents,MadAnomaliesSQL,TopForegroundWaitEvents,TopBackgroundWaitEvents,PctOfTimesThisSQLFoundInOtherTopSections,WaitEventsWithStrongCorrelation,WaitEventsFromASH,TopSQLsByElapsedTime,StatsSummary,IOStatsByFunctionSummary,LatchActivitySummary,Top10SegmentStats,InstanceStatisticCorrelation,LoadProfileAnomalies,AnomalyDescription,AnomlyCluster,ReportForAI,AppState};
+use crate::anomaly_detectors::AnomalyDetector;
+use crate::reasonings::{StatisticsDescription,TopPeaksSelected,MadAnomaliesEvents,MadAnomaliesSQL,TopForegroundWaitEvents,TopBackgroundWaitEvents,PctOfTimesThisSQLFoundInOtherTopSections,WaitEventsWithStrongCorrelation,WaitEventsFromASH,TopSQLsByElapsedTime,StatsSummary,IOStatsByFunctionSummary,LatchActivitySummary,Top10SegmentStats,InstanceStatisticCorrelation,LoadProfileAnomalies,AnomalyDescription,AnomlyCluster,MultivariateAnomaly,MultivariateContributor,ReportForAI,AppState};
 
 
 fn get_event_map_vectors(awrs: &Vec<AWR>, bg_or_fg: &str) -> HashMap<String, Vec<f64>> {
@@ -330,184 +331,292 @@ fn get_time_model_map_vectors(awrs: &Vec<AWR>) -> HashMap<String, Vec<f64>> {
     stats_map
 }
 
-fn detect_anomalies_mad_sliding(awrs: &Vec<AWR>, stats_vector: &HashMap<String, Vec<f64>>,  args: &Args) -> HashMap<String, Vec<(String,f64)>> {
-    let mut anomalies: HashMap<String, Vec<(String, f64)>> = HashMap::new();
-    //                          event        date   mad => for each event it will collect date of anomaly and value of MAD
-    
-    //if window is 100% don't use sliding window alghorithm - use normal detection for better performance
-    if args.mad_window_size == 100 {
-        anomalies = detect_anomalies_mad(awrs, stats_vector, args);
-        return anomalies;
-    }
-    
-    let threshold = 7.0;//args.mad_threshold;
-    let top_n = args.mad_threshold;
-    let len = awrs.len();
-    let mut full_window_size = ((args.mad_window_size as f32 / 100.0 ) * len as f32) as usize;
-    if full_window_size % 2 == 1 {
-        full_window_size = full_window_size + 1;
-    }
-    let half_window_size = full_window_size / 2;
-
-    //For sliding window there will parallel processing using rayon - Global Thread Pool is configured in main.rs
-    anomalies = stats_vector
+/// Runs the anomaly detector configured for this run over each statistic.
+/// Statistics are aligned with awrs - the snapshots the detector was built from (seasonal buckets and regimes are aligned by probe).
+/// With snap_range only probes from the range are scored, otherwise all loaded snapshots.
+/// Returns for each statistic dates of anomalies with their scores - only top-N (--mad-threshold) highest scores are kept.
+fn detect_anomalies(awrs: &[AWR], snap_range: Option<&(u64,u64)>, stats_vector: &HashMap<String, Vec<f64>>, detector: &dyn AnomalyDetector, top_n: usize) -> HashMap<String, Vec<(String,f64)>> {
+    let threshold = detector.threshold();
+    let probes: Vec<usize> = match snap_range {
+        Some(range) => (0..awrs.len()).filter(|i| in_snap_range(&awrs[*i], range)).collect(),
+        None => (0..awrs.len()).collect(),
+    };
+
+    //Statistics are processed in parallel using rayon - Global Thread Pool is configured in main.rs
+    stats_vector
         .par_iter() //parallel iteration
         .map(|(stat_name, values)| { //each thread will process one statistic
-            let mut local_anomalies = Vec::new();
-            for (i, &val) in values.iter().enumerate() { //For the given statistic process vector values of each snap and define local window
-                
-                /* Define boundries for the window  */
-                let start = if i >= half_window_size {
-                                        i - half_window_size
-                                    } else {
-                                            0
-                                    };
-
-                let end = if start + full_window_size <= len {
-                                        start + full_window_size
-                                } else {
-                                    len
-                                };
-                /* ********************************** */
-
-                let window = &values[start..end]; //local surrounding window
-
-                let local_median = median(window); 
-                let local_mad = mad_with_median(window, local_median);
-
-                if local_mad == 0.0 {
-                    continue; // no scatter - ignore
-                }
-
-                let val_mad_check = ((val - local_median).abs()) / local_mad;
-
-                //if anomaly is bigger than threshold - put event name on index corresponding to detected anomaly
-                if val_mad_check > threshold && val >= 0.0 { //Don't take into considaration negative values that are placeholders
-                    let snap_date = awrs[i].snap_info.begin_snap_time.clone();
-                    local_anomalies.push((snap_date, val_mad_check)); //put in vector date of anomalie and value of MAD
-                } 
-            }
+            let probes: Vec<usize> = probes.iter().copied().filter(|i| *i < values.len()).collect();
+            let probe_values: Vec<f64> = probes.iter().map(|i| values[*i]).collect();
+            let scores = detector.scores_at(&probe_values, &probes);
+            let mut local_anomalies: Vec<(String, f64)> = probes
+                .iter()
+                .zip(probe_values.iter().zip(scores))
+                //Don't take into considaration negative values that are placeholders, NaN scores are never above threshold
+                .filter(|(_, (val, score))| **val >= 0.0 && *score > threshold)
+                .map(|(i, (_, score))| (awrs[*i].snap_info.begin_snap_time.clone(), score)) //put in vector date of anomalie and its score
+                .collect();
+
+            //Sort by score descending and keep only top_n, than restore chronological order for nicer reports
             local_anomalies.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
             local_anomalies.truncate(top_n);
             local_anomalies.sort_by(|a, b| a.0.cmp(&b.0));
             (stat_name.clone(), local_anomalies) //return statistic name and anomalies
         }).filter(|(_, v)| {!v.is_empty()}) //filter out statistics with empty vectors - it means that no anomalie was detected for this stat
-        .collect();
-
-    anomalies    
-    
-}
-
-fn detect_anomalies_mad(awrs: &Vec<AWR>, stats_vector: &HashMap<String, Vec<f64>>,  args: &Args) -> HashMap<String, Vec<(String,f64)>> {
-    let mut anomalies: HashMap<String, Vec<(String, f64)>> = HashMap::new();
-    //                          event        date   mad => for each event it will collect date of anomaly and value of MAD
-    let threshold = 7.0; //args.mad_threshold;
-    let top_n = args.mad_threshold;
-
-    for (stat_name, values) in stats_vector {
-        let med = median(values);
-        let mad_val = mad_with_median(values, med);
-
-        if mad_val == 0.0 {
-            continue; // no anomalies - just move on
-        }
-
-        //Collect all anomalies for this statistic first
-        let mut stat_anomalies: Vec<(String, f64)> = Vec::new();
-
-        for (i, &val) in values.iter().enumerate() {
-            let val_mad_check = ((val - med).abs()) / mad_val;
-
-            //if anomaly is bigger than threshold - put event name on index corresponding to detected anomaly
-            if val_mad_check > threshold && val >= 0.0 { //Don't take into considaration negative values that are placeholders
-                let snap_date = awrs[i].snap_info.begin_snap_time.clone();
-                stat_anomalies.push((snap_date, val_mad_check));
-            }
-        }
-
-        if stat_anomalies.is_empty() {
-            continue;
-        }
-
-        //Sort by MAD score descending and keep only top_n
-        stat_anomalies.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        stat_anomalies.truncate(top_n);
-
-        //Restore chronological order for nicer reports
-        stat_anomalies.sort_by(|a, b| a.0.cmp(&b.0));
-
-        anomalies.insert(stat_name.to_string(), stat_anomalies);
-    }
-
-    anomalies
+        .collect()
 }
 
 //Median Absolute Deviation for anomalies detection in wait events
-pub fn detect_event_anomalies_mad(awrs: &Vec<AWR>, args: &Args, bg_or_fg: &str) -> HashMap<String, Vec<(String,f64)>> {
+pub fn detect_event_anomalies_mad(awrs: &Vec<AWR>, snap_range: &(u64,u64), detector: &dyn AnomalyDetector, top_n: usize, bg_or_fg: &str) -> HashMap<String, Vec<(String,f64)>> {
     let event_map_vectors = get_event_map_vectors(awrs, bg_or_fg);
     //println!("Detecting event anomalies");
-    let anomalies = detect_anomalies_mad_sliding(awrs, &event_map_vectors, args);
+    let anomalies = detect_anomalies(awrs, Some(snap_range), &event_map_vectors, detector, top_n);
     //println!("Detected event anomalies");
     anomalies
 }
 
 //Median Absolute Deviation for anomalies detection in SQLs
-pub fn detect_sql_anomalies_mad(awrs: &Vec<AWR>, args: &Args, sql_type: &str) -> HashMap<String, Vec<(String,f64)>> {
+pub fn detect_sql_anomalies_mad(awrs: &Vec<AWR>, snap_range: &(u64,u64), detector: &dyn AnomalyDetector, top_n: usize, sql_type: &str) -> HashMap<String, Vec<(String,f64)>> {
     
     let sql_map_vectors = get_sql_map_vectors(awrs, sql_type);
-    let anomalies = detect_anomalies_mad_sliding(awrs, &sql_map_vectors, args);
+    let anomalies = detect_anomalies(awrs, Some(snap_range), &sql_map_vectors, detector, top_n);
     
     anomalies
 }
 
 //Median Absolute Deviation for anomalies detection in Load Profile
-pub fn detect_loadprofile_anomalies_mad(awrs: &Vec<AWR>, args: &Args) -> HashMap<String, Vec<(String,f64)>> {
+pub fn detect_loadprofile_anomalies_mad(awrs: &Vec<AWR>, detector: &dyn AnomalyDetector, top_n: usize) -> HashMap<String, Vec<(String,f64)>> {
     let loadprofile_map_vectors = get_loadprofile_map_vectors(awrs);
-    let anomalies = detect_anomalies_mad_sliding(awrs, &loadprofile_map_vectors, args);
+    let anomalies = detect_anomalies(awrs, None, &loadprofile_map_vectors, detector, top_n);
     
     anomalies
 }
 
 //Median Absolute Deviation for anomalies detection in Instance Statistics
-pub fn detect_stats_anomalies_mad(awrs: &Vec<AWR>, args: &Args) -> HashMap<String, Vec<(String,f64)>> {
+pub fn detect_stats_anomalies_mad(awrs: &Vec<AWR>, detector: &dyn AnomalyDetector, top_n: usize) -> HashMap<String, Vec<(String,f64)>> {
     let stats_map_vectors = get_statistics_map_vectors(awrs);    
-    let anomalies = detect_anomalies_mad_sliding(awrs, &stats_map_vectors, args);
+    let anomalies = detect_anomalies(awrs, None, &stats_map_vectors, detector, top_n);
 
     anomalies
 }
 
 //Median Absolute Deviation for anomalies detection in Dictionary Cache stats
-pub fn detect_dc_anomalies_mad(awrs: &Vec<AWR>, args: &Args) -> HashMap<String, Vec<(String,f64)>> {
+pub fn detect_dc_anomalies_mad(awrs: &Vec<AWR>, detector: &dyn AnomalyDetector, top_n: usize) -> HashMap<String, Vec<(String,f64)>> {
     let stats_map_vectors = get_dc_map_vectors(awrs);    
-    let anomalies = detect_anomalies_mad_sliding(awrs, &stats_map_vectors, args);
+    let anomalies = detect_anomalies(awrs, None, &stats_map_vectors, detector, top_n);
 
     anomalies
 }
 
 //Median Absolute Deviation for anomalies detection in Library Cache stats
-pub fn detect_libcache_anomalies_mad(awrs: &Vec<AWR>, args: &Args) -> HashMap<String, Vec<(String,f64)>> {
+pub fn detect_libcache_anomalies_mad(awrs: &Vec<AWR>, detector: &dyn AnomalyDetector, top_n: usize) -> HashMap<String, Vec<(String,f64)>> {
     let stats_map_vectors = get_libcache_map_vectors(awrs);    
-    let anomalies = detect_anomalies_mad_sliding(awrs, &stats_map_vectors, args);
+    let anomalies = detect_anomalies(awrs, None, &stats_map_vectors, detector, top_n);
 
     anomalies
 }
 
 //Median Absolute Deviation for anomalies detection in Latch Activity stats
-pub fn detect_latch_activity_anomalies_mad(awrs: &Vec<AWR>, args: &Args) -> HashMap<String, Vec<(String,f64)>> {
+pub fn detect_latch_activity_anomalies_mad(awrs: &Vec<AWR>, detector: &dyn AnomalyDetector, top_n: usize) -> HashMap<String, Vec<(String,f64)>> {
     let stats_map_vectors = get_latch_activity_map_vectors(awrs);    
-    let anomalies = detect_anomalies_mad_sliding(awrs, &stats_map_vectors, args);
+    let anomalies = detect_anomalies(awrs, None, &stats_map_vectors, detector, top_n);
 
     anomalies
 }
 
 //Median Absolute Deviation for anomalies detection in Time Model stats
-pub fn detect_time_model_anomalies_mad(awrs: &Vec<AWR>, args: &Args) -> HashMap<String, Vec<(String,f64)>> {
+pub fn detect_time_model_anomalies_mad(awrs: &Vec<AWR>, detector: &dyn AnomalyDetector, top_n: usize) -> HashMap<String, Vec<(String,f64)>> {
     let stats_map_vectors = get_time_model_map_vectors(awrs);    
-    let anomalies = detect_anomalies_mad_sliding(awrs, &stats_map_vectors, args);
+    let anomalies = detect_anomalies(awrs, None, &stats_map_vectors, detector, top_n);
 
     anomalies
 }
 
+/* Multivariate anomaly detection.
+   Every statistic above is scored on its own, so a snapshot where twenty metrics are each mildly unusual is missed.
+   Here all metric families are put into one feature matrix (snapshots x metrics), each metric is standardized with
+   median and 1.4826*MAD and the robust Mahalanobis distance of every snapshot is computed:
+        d^2 = (x - mu)' * S^-1 * (x - mu)
+   There are usually more metrics than snapshots, so covariance is shrunk towards identity: S = (1-a)*C + a*I.
+   Robustness comes from reweighting - mean and covariance are estimated again from 75% of snapshots with the smallest
+   distance, so anomalies do not hide themselves by inflating the covariance. */
+
+/// Shrinkage of covariance towards identity
+const MV_SHRINKAGE: f64 = 0.25;
+/// Fraction of snapshots with smallest distance used for reweighted estimate of mean and covariance
+const MV_SUBSET_FRACTION: f64 = 0.75;
+/// Robust z-scores are clipped, so one broken counter can't dominate the distance
+const MV_Z_CLIP: f64 = 10.0;
+/// Number of top contributing metrics reported for each snapshot
+const MV_TOP_CONTRIBUTORS: usize = 5;
+
+//Robust standardized features from load profile, time model, foreground wait events and instance statistics
+fn get_multivariate_features(awrs: &Vec<AWR>) -> Vec<(String, Vec<f64>)> {
+    let families: Vec<(&str, HashMap<String, Vec<f64>>)> = vec![
+        ("LP", get_loadprofile_map_vectors(awrs)),
+        ("TM", get_time_model_map_vectors(awrs)),
+        ("EVENT", get_event_map_vectors(awrs, "FOREGROUND")),
+        ("STAT", get_statistics_map_vectors(awrs)),
+    ];
+
+    let mut features: Vec<(String, Vec<f64>)> = Vec::new();
+    for (family, stats_map) in families {
+        for (name, values) in stats_map {
+            //Negative values are placeholders for metrics missing in the snapshot - it was not there, so it's 0
+            let values: Vec<f64> = values.iter().map(|v| v.max(0.0)).collect();
+            let med = median(&values);
+            let mut scale = 1.4826 * mad_with_median(&values, med);
+            if scale == 0.0 {
+                scale = std_deviation(values.clone()).unwrap_or(0.0);
+            }
+            if scale == 0.0 || !scale.is_finite() {
+                continue; // constant metric carries no information
+            }
+            let z: Vec<f64> = values.iter().map(|v| ((v - med) / scale).clamp(-MV_Z_CLIP, MV_Z_CLIP)).collect();
+            features.push((format!("{}: {}", family, name), z));
+        }
+    }
+    features.sort_by(|a, b| a.0.cmp(&b.0));
+    features
+}
+
+/// Squared Mahalanobis distance of each row with per feature contributions (they sum up to the distance).
+/// Mean and shrunk covariance are estimated from `subset` rows. Inverse of p x p covariance is computed with
+/// Woodbury identity, so only a matrix of size |subset| x |subset| has to be inverted:
+///     S^-1 = 1/a * (I - X' * (a/(1-a) * I + X*X')^-1 * X)
+fn mahalanobis_from_subset(rows: &[Vec<f64>], subset: &[usize]) -> Vec<(f64, Vec<f64>)> {
+    let p = rows[0].len();
+    let h = subset.len();
+    let mut mu = vec![0.0; p];
+    for &i in subset {
+        for j in 0..p {
+            mu[j] += rows[i][j] / h as f64;
+        }
+    }
+
+    //Centered subset scaled by 1/sqrt(h-1), so X'X is the sample covariance
+    let norm = ((h.max(2) - 1) as f64).sqrt();
+    let xh: Vec<Vec<f64>> = subset.iter()
+        .map(|&i| rows[i].iter().zip(&mu).map(|(v, m)| (v - m) / norm).collect())
+        .collect();
+
+    let alpha = MV_SHRINKAGE;
+    let m = nalgebra::DMatrix::from_fn(h, h, |a, b| {
+        let dot: f64 = xh[a].iter().zip(&xh[b]).map(|(x, y)| x * y).sum();
+        dot + if a == b { alpha / (1.0 - alpha) } else { 0.0 }
+    });
+    let m_inv = m.try_inverse().unwrap_or_else(|| nalgebra::DMatrix::zeros(h, h));
+
+    rows.par_iter()
+        .map(|row| {
+            let d: Vec<f64> = row.iter().zip(&mu).map(|(v, m)| v - m).collect();
+            let u = nalgebra::DVector::from_iterator(h, xh.iter().map(|x| x.iter().zip(&d).map(|(a, b)| a * b).sum::<f64>()));
+            let w = &m_inv * u;
+            let mut s = d.clone();
+            for (k, x) in xh.iter().enumerate() {
+                for j in 0..p {
+                    s[j] -= x[j] * w[k];
+                }
+            }
+            let contributions: Vec<f64> = d.iter().zip(&s).map(|(dj, sj)| dj * sj / alpha).collect();
+            (contributions.iter().sum::<f64>().max(0.0), contributions)
+        })
+        .collect()
+}
+
+/// Robust Mahalanobis distance of every snapshot in snap_range across all metric families.
+/// Anomaly score is the robust z-score of the distance among all snapshots - snapshot is an anomaly when it exceeds `threshold`.
+pub fn detect_multivariate_anomalies(awrs: &[AWR], snap_range: &(u64,u64), threshold: f64) -> Vec<MultivariateAnomaly> {
+    let awrs: Vec<AWR> = snaps_in_range(awrs, snap_range)
+                             .cloned().collect();
+    let features = get_multivariate_features(&awrs);
+    let n = awrs.len();
+    if features.is_empty() || n < 4 {
+        return Vec::new();
+    }
+
+    let rows: Vec<Vec<f64>> = (0..n).map(|i| features.iter().map(|f| f.1[i]).collect()).collect();
+
+    //First pass on all snapshots, second on the most typical ones
+    let all: Vec<usize> = (0..n).collect();
+    let first = mahalanobis_from_subset(&rows, &all);
+    let mut order: Vec<usize> = all.clone();
+    order.sort_by(|a, b| first[*a].0.partial_cmp(&first[*b].0).unwrap_or(std::cmp::Ordering::Equal));
+    order.truncate(((n as f64 * MV_SUBSET_FRACTION).ceil() as usize).max(2));
+    let distances = mahalanobis_from_subset(&rows, &order);
+
+    let d: Vec<f64> = distances.iter().map(|(d2, _)| d2.sqrt()).collect();
+    let d_med = median(&d);
+    let d_scale = 1.4826 * mad_with_median(&d, d_med);
+
+    distances.iter().enumerate().map(|(i, (d2, contributions))| {
+        let score = if d_scale > 0.0 { (d[i] - d_med) / d_scale } else { 0.0 };
+        let mut top: Vec<(usize, f64)> = contributions.iter().copied().enumerate().collect();
+        top.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        top.truncate(MV_TOP_CONTRIBUTORS);
+
+        MultivariateAnomaly {
+            begin_snap_id: awrs[i].snap_info.begin_snap_id,
+            begin_snap_date: awrs[i].snap_info.begin_snap_time.clone(),
+            mahalanobis_distance: d[i],
+            anomaly_score: score,
+            is_anomaly: score > threshold,
+            top_contributors: top.iter()
+                                 .filter(|(_, c)| *c > 0.0)
+                                 .map(|(j, c)| MultivariateContributor {
+                                     metric: features[*j].0.clone(),
+                                     robust_z: features[*j].1[i],
+                                     contribution_pct: if *d2 > 0.0 { c / d2 * 100.0 } else { 0.0 },
+                                 })
+                                 .collect(),
+        }
+    }).collect()
+}
+
+/// Prints snapshots flagged by multivariate detection and adds them to anomalies summary as MULTIVARIATE
+pub fn report_multivariate_anomalies(awrs: &[AWR], snap_range: &(u64,u64), args: &Args, logfile_name: &str, anomalies_summary: &mut BTreeMap<(u64, String), BTreeMap<String, Vec<String>>>) -> Vec<MultivariateAnomaly> {
+    let mv_anomalies = detect_multivariate_anomalies(awrs, snap_range, args.multivariate_threshold);
+
+    make_notes!(logfile_name, false, 2, "\n{} {}\n", "Multivariate anomalies - robust Mahalanobis distance across Load Profile, Time Model, Wait Events and Instance Statistics, threshold:".yellow(), args.multivariate_threshold);
+
+    let flagged: Vec<&MultivariateAnomaly> = mv_anomalies.iter().filter(|a| a.is_anomaly).collect();
+    if flagged.is_empty() {
+        let no_anomalies_txt = "\tNo snapshot is unusual across many metrics at once\n".to_string();
+        make_notes!(logfile_name, args.quiet, 0, "{}", no_anomalies_txt.green().italic());
+        return mv_anomalies;
+    }
+
+    let mut table = Table::new();
+    table.set_titles(Row::new(vec![
+        Cell::new("BEGIN SNAP ID"),
+        Cell::new("BEGIN SNAP DATE"),
+        Cell::new("Distance"),
+        Cell::new("Score"),
+        Cell::new("Top contributors (robust z, % of distance)"),
+    ]));
+
+    for a in flagged {
+        let contributors: Vec<String> = a.top_contributors
+                                         .iter()
+                                         .map(|c| format!("{} ({:.1}, {:.1}%)", c.metric, c.robust_z, c.contribution_pct))
+                                         .collect();
+        table.add_row(Row::new(vec![
+            Cell::new(&a.begin_snap_id.to_string()),
+            Cell::new(&a.begin_snap_date),
+            Cell::new(&format!("{:.2}", a.mahalanobis_distance)),
+            Cell::new(&format!("{:.2}", a.anomaly_score)),
+            Cell::new(&contributors.join("\n")),
+        ]));
+
+        let top_names: Vec<String> = a.top_contributors.iter().take(3).map(|c| c.metric.clone()).collect();
+        anomalies_join(anomalies_summary, (a.begin_snap_id, a.begin_snap_date.clone()), "MULTIVARIATE",
+                       format!("score {:.2} - {}", a.anomaly_score, top_names.join(", ")));
+    }
+
+    make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&table));
+    mv_anomalies
+}
+
 pub fn anomalies_join(
     anomalies_summary: &mut BTreeMap<(u64, String), BTreeMap<String, Vec<String>>>,
     key: (u64, String),
@@ -671,7 +780,8 @@ pub fn report_anomalies_summary(anomalies_summary: &mut BTreeMap<(u64, String),
         report_for_ai.anomaly_clusters.push(AnomlyCluster { begin_snap_id: *snap_id, 
                                                             begin_snap_date: snap_date.clone(), 
                                                             anomalies_detected: anomaly_data, 
-                                                            number_of_anomalies: all_lines.len() as u64 });
+                                                            number_of_anomalies: all_lines.len() as u64,
+                                                            ..Default::default() });
 
         table.add_row(Row::new(vec![
             c_begin_snap_id,
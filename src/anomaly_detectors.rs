@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use crate::awr::AWR;
+use crate::Args;
+use crate::regimes::WorkloadRegimes;
+use crate::tools::*;
+use chrono::{Datelike, Timelike};
+use colored::*;
+
+/* Pluggable anomaly detectors.
+   Each detector scores every probe of a statistic; probe is an anomaly when its score is above the detector's threshold.
+   Sliding window and seasonality are wrappers, so they can be combined with any base detector:
+        --anomaly-detector iqr -W 20             -> Tukey fences computed in local window of 20% probes
+        --anomaly-detector ensemble --mad-seasonality daily -> ensemble scoring residuals from hour of the day baselines
+        --regimes auto --mad-per-regime          -> base detector run separately over probes of each workload regime */
+
+pub trait AnomalyDetector: Sync + Send {
+    /// Human readable description of the detector and its settings
+    fn name(&self) -> String;
+    /// Probe is an anomaly when its score is greater than this value
+    fn threshold(&self) -> f64;
+    /// Score for each probe, NaN if the probe could not be scored (for example no scatter at all)
+    fn scores(&self, values: &[f64]) -> Vec<f64>;
+    /// Scores of a subset of snapshots - probes[i] is the position of values[i] among snapshots the detector was built from.
+    /// Detectors aligned with snapshots (seasonal buckets, regimes) use it, the others just score the values.
+    fn scores_at(&self, values: &[f64], _probes: &[usize]) -> Vec<f64> {
+        self.scores(values)
+    }
+}
+
+/// Median Absolute Deviation: |x - median| / MAD
+pub struct MadDetector {
+    pub threshold: f64,
+}
+
+impl AnomalyDetector for MadDetector {
+    fn name(&self) -> String { "MAD".to_string() }
+    fn threshold(&self) -> f64 { self.threshold }
+
+    fn scores(&self, values: &[f64]) -> Vec<f64> {
+        let med = median(values);
+        let mad_val = mad_with_median(values, med);
+        if mad_val == 0.0 {
+            return vec![f64::NAN; values.len()]; // no scatter - no anomalies
+        }
+        values.iter().map(|v| (v - med).abs() / mad_val).collect()
+    }
+}
+
+/// Tukey fences: distance beyond [Q1 - k*IQR, Q3 + k*IQR] expressed in IQR units, threshold is k
+pub struct IqrDetector {
+    pub k: f64,
+}
+
+impl AnomalyDetector for IqrDetector {
+    fn name(&self) -> String { "IQR (Tukey fences)".to_string() }
+    fn threshold(&self) -> f64 { self.k }
+
+    fn scores(&self, values: &[f64]) -> Vec<f64> {
+        let q1 = percentile(values, 25.0);
+        let q3 = percentile(values, 75.0);
+        let iqr = q3 - q1;
+        if !iqr.is_finite() || iqr == 0.0 {
+            return vec![f64::NAN; values.len()];
+        }
+        //Score k means the probe lies exactly on the fence with factor k
+        values.iter().map(|v| {
+            if *v > q3 {
+                (v - q3) / iqr
+            } else if *v < q1 {
+                (q1 - v) / iqr
+            } else {
+                0.0
+            }
+        }).collect()
+    }
+}
+
+/// Robust z-score: |x - median| / (1.4826 * MAD)
+pub struct RobustZDetector {
+    pub threshold: f64,
+}
+
+impl AnomalyDetector for RobustZDetector {
+    fn name(&self) -> String { "Robust z-score".to_string() }
+    fn threshold(&self) -> f64 { self.threshold }
+
+    fn scores(&self, values: &[f64]) -> Vec<f64> {
+        robust_z_score(values).iter().map(|z| z.abs()).collect()
+    }
+}
+
+/// EWMA control chart with robust center line (median) and sigma (1.4826 * MAD).
+/// Score is the distance of EWMA statistic from the center line in units of its standard deviation at time t,
+/// so threshold is the usual L (width of control limits).
+pub struct EwmaDetector {
+    pub lambda: f64,
+    pub l: f64,
+}
+
+impl AnomalyDetector for EwmaDetector {
+    fn name(&self) -> String { format!("EWMA (lambda={})", self.lambda) }
+    fn threshold(&self) -> f64 { self.l }
+
+    fn scores(&self, values: &[f64]) -> Vec<f64> {
+        let lambda = self.lambda.clamp(0.01, 1.0);
+        let center = median(values);
+        let sigma = 1.4826 * mad_with_median(values, center);
+        if sigma == 0.0 {
+            return vec![f64::NAN; values.len()];
+        }
+
+        let mut z = center;
+        values.iter().enumerate().map(|(t, v)| {
+            z = lambda * v + (1.0 - lambda) * z;
+            let sigma_t = sigma * ((lambda / (2.0 - lambda)) * (1.0 - (1.0 - lambda).powi(2 * (t as i32 + 1)))).sqrt();
+            (z - center).abs() / sigma_t
+        }).collect()
+    }
+}
+
+/// Voting of several detectors - probe is an anomaly when at least `min_votes` members flag it.
+/// Score is the mean of member scores divided by their thresholds (only voting members), so it is above 1.0
+/// exactly for probes with enough votes.
+pub struct EnsembleDetector {
+    pub members: Vec<Box<dyn AnomalyDetector>>,
+    pub min_votes: usize,
+}
+
+impl AnomalyDetector for EnsembleDetector {
+    fn name(&self) -> String {
+        let names: Vec<String> = self.members.iter().map(|m| m.name()).collect();
+        format!("Ensemble ({} of: {})", self.min_votes, names.join(", "))
+    }
+    fn threshold(&self) -> f64 { 1.0 }
+
+    fn scores(&self, values: &[f64]) -> Vec<f64> {
+        let member_scores: Vec<(f64, Vec<f64>)> = self.members
+            .iter()
+            .map(|m| (m.threshold(), m.scores(values)))
+            .collect();
+
+        (0..values.len()).map(|i| {
+            let severities: Vec<f64> = member_scores
+                .iter()
+                .filter(|(threshold, scores)| scores[i] > *threshold)
+                .map(|(threshold, scores)| scores[i] / threshold)
+                .collect();
+            if severities.len() >= self.min_votes.max(1) {
+                severities.iter().sum::<f64>() / severities.len() as f64
+            } else {
+                0.0
+            }
+        }).collect()
+    }
+}
+
+/// Scores each probe against its local surrounding window only - window is window_pct % of scored probes
+pub struct SlidingWindowDetector {
+    pub inner: Box<dyn AnomalyDetector>,
+    pub window_pct: usize,
+}
+
+impl AnomalyDetector for SlidingWindowDetector {
+    fn name(&self) -> String { format!("{} in sliding window of {}% probes", self.inner.name(), self.window_pct) }
+    fn threshold(&self) -> f64 { self.inner.threshold() }
+
+    fn scores(&self, values: &[f64]) -> Vec<f64> {
+        let len = values.len();
+        let mut window_size = ((self.window_pct as f32 / 100.0) * len as f32) as usize;
+        if window_size % 2 == 1 {
+            window_size += 1;
+        }
+        if window_size < 2 {
+            return self.inner.scores(values);
+        }
+        let half_window_size = window_size / 2;
+        (0..len).map(|i| {
+            /* Define boundries for the window  */
+            let start = i.saturating_sub(half_window_size);
+            let end = (start + window_size).min(len);
+            let window = &values[start..end]; //local surrounding window
+            self.inner.scores(window)[i - start]
+        }).collect()
+    }
+}
+
+/// Minimum number of probes in a seasonal bucket (e.g. all Mondays 02:00) to trust its baseline
+const MIN_SEASONAL_BUCKET: usize = 3;
+
+/// Seasonality-aware wrapper: each probe is compared with the median of probes from the same hour of the day
+/// (or the same hour of the same day of the week) and inner detector scores residuals from these baselines.
+/// A nightly batch which runs every day is the baseline of its hour and only unusual nights are reported.
+/// Probes in buckets with less than MIN_SEASONAL_BUCKET values are not scored - there is no history to compare with.
+pub struct SeasonalDetector {
+    pub inner: Box<dyn AnomalyDetector>,
+    pub seasonality: String,
+    pub buckets: Vec<Option<(u32, u32)>>,
+}
+
+impl SeasonalDetector {
+    pub fn new(inner: Box<dyn AnomalyDetector>, awrs: &[AWR], seasonality: &str) -> Self {
+        let buckets = awrs
+            .iter()
+            .map(|awr| {
+                let ts = parse_snap_time(&awr.snap_info.begin_snap_time)?;
+                match seasonality {
+                    "daily" => Some((0, ts.hour())),
+                    "weekly" => Some((ts.weekday().num_days_from_monday(), ts.hour())),
+                    _ => None,
+                }
+            })
+            .collect();
+        SeasonalDetector { inner, seasonality: seasonality.to_string(), buckets }
+    }
+}
+
+impl AnomalyDetector for SeasonalDetector {
+    fn name(&self) -> String { format!("{} of residuals from {} seasonal baselines", self.inner.name(), self.seasonality) }
+    fn threshold(&self) -> f64 { self.inner.threshold() }
+
+    fn scores(&self, values: &[f64]) -> Vec<f64> {
+        self.scores_at(values, &(0..values.len()).collect::<Vec<usize>>())
+    }
+
+    fn scores_at(&self, values: &[f64], probes: &[usize]) -> Vec<f64> {
+        let bucket = |i: usize| probes.get(i).and_then(|p| self.buckets.get(*p)).copied().flatten();
+        //Collect values of each bucket, negative values are placeholders for missing probes
+        let mut bucket_values: HashMap<(u32, u32), Vec<f64>> = HashMap::new();
+        for (i, &val) in values.iter().enumerate() {
+            if let Some(b) = bucket(i) {
+                if val >= 0.0 {
+                    bucket_values.entry(b).or_default().push(val);
+                }
+            }
+        }
+        let baselines: HashMap<(u32, u32), f64> = bucket_values
+            .iter()
+            .filter(|(_, v)| v.len() >= MIN_SEASONAL_BUCKET)
+            .map(|(b, v)| (*b, median(v)))
+            .collect();
+
+        //Residuals from seasonal baseline - only for probes which have a bucket and a baseline, the others stay NaN
+        let residuals: Vec<(usize, f64)> = values
+            .iter()
+            .enumerate()
+            .filter(|(_, val)| **val >= 0.0)
+            .filter_map(|(i, val)| bucket(i).and_then(|b| baselines.get(&b)).map(|base| (i, val - base)))
+            .collect();
+
+        let res_values: Vec<f64> = residuals.iter().map(|r| r.1).collect();
+        let res_scores = self.inner.scores(&res_values);
+        let mut scores = vec![f64::NAN; values.len()];
+        for ((i, _), score) in residuals.iter().zip(res_scores) {
+            scores[*i] = score;
+        }
+        scores
+    }
+}
+
+/// Minimum number of probes of a workload regime to score them against each other
+const MIN_REGIME_PROBES: usize = 5;
+
+/// Regime-aware wrapper: inner detector scores probes of each workload regime separately,
+/// so a batch night is compared with other batch nights and not with the OLTP day.
+/// Probes of regimes with less than MIN_REGIME_PROBES values are not scored.
+pub struct RegimeDetector {
+    pub inner: Box<dyn AnomalyDetector>,
+    pub labels: Vec<usize>,
+}
+
+impl AnomalyDetector for RegimeDetector {
+    fn name(&self) -> String { format!("{} within workload regimes", self.inner.name()) }
+    fn threshold(&self) -> f64 { self.inner.threshold() }
+
+    fn scores(&self, values: &[f64]) -> Vec<f64> {
+        self.scores_at(values, &(0..values.len()).collect::<Vec<usize>>())
+    }
+
+    fn scores_at(&self, values: &[f64], probes: &[usize]) -> Vec<f64> {
+        //Positions of probes of each regime, negative values are placeholders for missing probes
+        let mut regime_probes: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (i, &val) in values.iter().enumerate() {
+            if let Some(&regime) = probes.get(i).and_then(|p| self.labels.get(*p)) {
+                if val >= 0.0 {
+                    regime_probes.entry(regime).or_default().push(i);
+                }
+            }
+        }
+
+        let mut scores = vec![f64::NAN; values.len()];
+        for positions in regime_probes.values().filter(|p| p.len() >= MIN_REGIME_PROBES) {
+            let regime_values: Vec<f64> = positions.iter().map(|i| values[*i]).collect();
+            for (i, score) in positions.iter().zip(self.inner.scores(&regime_values)) {
+                scores[*i] = score;
+            }
+        }
+        scores
+    }
+}
+
+//Base detector selected by --anomaly-detector
+fn base_anomaly_detector(name: &str, args: &Args) -> Option<Box<dyn AnomalyDetector>> {
+    match name {
+        "mad" => Some(Box::new(MadDetector { threshold: args.mad_score_threshold })),
+        "iqr" => Some(Box::new(IqrDetector { k: args.iqr_fence })),
+        "robust-z" => Some(Box::new(RobustZDetector { threshold: args.robust_z_threshold })),
+        "ewma" => Some(Box::new(EwmaDetector { lambda: args.ewma_lambda, l: args.ewma_sigma })),
+        "ensemble" => Some(Box::new(EnsembleDetector {
+            members: ["mad", "iqr", "robust-z", "ewma"]
+                .iter()
+                .filter_map(|m| base_anomaly_detector(m, args))
+                .collect(),
+            min_votes: args.ensemble_votes,
+        })),
+        _ => None,
+    }
+}
+
+/// Builds the anomaly detector configured for this run.
+/// Regime baselines take precedence over seasonal baselines and those over sliding window, window of 100% means all probes.
+pub fn build_anomaly_detector(awrs: &[AWR], args: &Args, regimes: Option<&WorkloadRegimes>) -> Box<dyn AnomalyDetector> {
+    let base = base_anomaly_detector(&args.anomaly_detector, args).unwrap_or_else(|| {
+        eprintln!("{} {}", "Unknown anomaly detector, expected mad, iqr, robust-z, ewma or ensemble - falling back to mad:".red(), args.anomaly_detector);
+        Box::new(MadDetector { threshold: args.mad_score_threshold })
+    });
+
+    if args.mad_per_regime {
+        //Without regimes (--regimes none or too few snapshots) the other wrappers apply
+        if let Some(regimes) = regimes {
+            return Box::new(RegimeDetector { inner: base, labels: regimes.labels.clone() });
+        }
+    }
+
+    match args.mad_seasonality.as_str() {
+        "none" => {},
+        "daily" | "weekly" => return Box::new(SeasonalDetector::new(base, awrs, &args.mad_seasonality)),
+        other => eprintln!("{} {}", "Unknown seasonality model, expected none, daily or weekly - ignoring:".red(), other),
+    }
+
+    if args.mad_window_size < 100 {
+        return Box::new(SlidingWindowDetector { inner: base, window_pct: args.mad_window_size });
+    }
+    base
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seasonal_scores_probes_beyond_buckets_as_nan() {
+        let detector = SeasonalDetector {
+            inner: Box::new(MadDetector { threshold: 3.0 }),
+            seasonality: "daily".to_string(),
+            buckets: vec![Some((0, 1)); 4],
+        };
+        let scores = detector.scores(&[1.0, 2.0, 3.0, 10.0, 5.0, 6.0]);
+        assert_eq!(scores.len(), 6);
+        assert!(scores[..4].iter().all(|s| s.is_finite()));
+        assert!(scores[4].is_nan() && scores[5].is_nan());
+    }
+}
@@ -66,17 +66,17 @@ pub struct DBInstance {
 
 #[derive(Default,Serialize, Deserialize, Debug, Clone)]
 pub struct WaitClasses {
-	wait_class: String,
-	waits: u64,
-	total_wait_time_s: f64,
-	avg_wait_ms: f64,
-	db_time_pct: f64,
+	pub wait_class: String,
+	pub waits: u64,
+	pub total_wait_time_s: f64,
+	pub avg_wait_ms: f64,
+	pub db_time_pct: f64,
 }
 
 #[derive(Default,Serialize, Deserialize, Debug, Clone)]
 pub struct HostCPU {
 	pub cpus: u32,
-	cores: u32,
+	pub cores: u32,
 	sockets: u8,
 	load_avg_begin: f64,
 	load_avg_end: f64,
@@ -212,6 +212,8 @@ pub struct DictionaryCache {
 	pub statname: String, 
 	pub get_requests: u64,
 	pub final_usage: u64,
+	#[serde(default)]
+	pub get_pct_miss: f64,
 }
 
 #[derive(Default,Serialize, Deserialize, Debug, Clone)]
@@ -220,6 +222,12 @@ pub struct LibraryCache {
 	pub get_requests: u64,
 	pub get_pct_miss: f64,
 	pub pin_requests: u64,
+	#[serde(default)]
+	pub pin_pct_miss: f64,
+	#[serde(default)]
+	pub reloads: u64,
+	#[serde(default)]
+	pub invalidations: u64,
 }
 
 #[derive(Default,Serialize, Deserialize, Debug, Clone)]
@@ -240,6 +248,39 @@ pub struct SegmentStats {
 	pub stat_vlalue: f64,
 }
 
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct LatchSleep {
+	pub latch_name: String,
+	pub get_requests: u64,
+	pub misses: u64,
+	pub sleeps: u64,
+	pub spin_gets: u64,
+}
+
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct MutexSleep {
+	pub mutex_type: String,
+	pub location: String,
+	pub sleeps: u64,
+	pub wait_time_ms: f64,
+}
+
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct SGATargetAdvice {
+	pub size_mb: f64,
+	pub size_factor: f64,
+	pub est_db_time_s: f64,
+	pub est_physical_reads: f64,
+}
+
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct PGAAdvice {
+	pub target_mb: f64,
+	pub size_factor: f64,
+	pub est_cache_hit_pct: f64,
+	pub est_overalloc_count: f64,
+}
+
 #[derive(Default,Serialize, Deserialize, Debug, Clone)]
 pub struct TopSQLWithTopEvents {
 	pub sql_id: String,
@@ -260,7 +301,7 @@ pub struct AWR {
 	pub load_profile: Vec<LoadProfile>,
 	pub instance_efficiency: Vec<InstanceEfficiency>,
 	pub redo_log: RedoLog,
-	wait_classes: Vec<WaitClasses>,
+	pub wait_classes: Vec<WaitClasses>,
 	pub host_cpu: HostCPU,
 	pub time_model_stats: Vec<TimeModelStats>,
 	pub foreground_wait_events: Vec<WaitEvents>,
@@ -277,6 +318,16 @@ pub struct AWR {
 	pub library_cache: Vec<LibraryCache>,
 	pub latch_activity: Vec<LatchActivity>,
 	pub segment_stats: HashMap<String, Vec<SegmentStats>>,
+	#[serde(default)]
+	pub parameters: HashMap<String, String>, //value at the end of the snapshot
+	#[serde(default)]
+	pub sga_target_advisory: Vec<SGATargetAdvice>,
+	#[serde(default)]
+	pub pga_advisory: Vec<PGAAdvice>,
+	#[serde(default)]
+	pub latch_sleep_breakdown: Vec<LatchSleep>,
+	#[serde(default)]
+	pub mutex_sleep_summary: Vec<MutexSleep>,
 } 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -507,7 +558,15 @@ fn initialization_parameters(table: ElementRef) -> HashMap<String, String> {
 			let pname = pname[0].trim().to_string();
 
 			let pvalue: Vec<&str> =  columns[1].text().collect::<Vec<_>>();
-			let pvalue = pvalue[0].trim().to_string();
+			let mut pvalue = pvalue[0].trim().to_string();
+
+			// End value is shown only when the parameter was changed during the snapshot
+			if columns.len() > 2 {
+				let end_value = columns[2].text().collect::<String>().trim().to_string();
+				if !end_value.is_empty() {
+					pvalue = end_value;
+				}
+			}
 
 			params.entry(pname).or_insert(pvalue);
 		}
@@ -515,6 +574,180 @@ fn initialization_parameters(table: ElementRef) -> HashMap<String, String> {
 	params
 }
 
+/// Index of the column whose header contains given text - headers differ a little between versions
+fn column_index(headers: &[String], pattern: &str) -> Option<usize> {
+	headers.iter().position(|h| h.to_lowercase().contains(pattern))
+}
+
+fn table_headers(table: ElementRef) -> Vec<String> {
+	let header_selector = Selector::parse("th").unwrap();
+	table.select(&header_selector)
+		.map(|th| th.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" "))
+		.collect()
+}
+
+fn sga_target_advisory(table: ElementRef) -> Vec<SGATargetAdvice> {
+	let row_selector = Selector::parse("tr").unwrap();
+    let column_selector = Selector::parse("td").unwrap();
+	let headers = table_headers(table);
+	let factor_idx = column_index(&headers, "factor").unwrap_or(1);
+	let db_time_idx = column_index(&headers, "db time").unwrap_or(2);
+	let reads_idx = column_index(&headers, "physical reads");
+	let mut advice: Vec<SGATargetAdvice> = Vec::new();
+	for row in table.select(&row_selector) {
+		let columns = row.select(&column_selector).collect::<Vec<_>>();
+		if columns.len() > factor_idx.max(db_time_idx) {
+			let value = |i: usize| f64::from_str(&columns.get(i)?.text().collect::<String>().trim().replace(",", "")).ok();
+			if let (Some(size_mb), Some(size_factor), Some(est_db_time_s)) = (value(0), value(factor_idx), value(db_time_idx)) {
+				advice.push(SGATargetAdvice { size_mb, size_factor, est_db_time_s, est_physical_reads: reads_idx.and_then(value).unwrap_or(0.0) });
+			}
+		}
+	}
+	advice
+}
+
+fn pga_advisory(table: ElementRef) -> Vec<PGAAdvice> {
+	let row_selector = Selector::parse("tr").unwrap();
+    let column_selector = Selector::parse("td").unwrap();
+	let headers = table_headers(table);
+	let factor_idx = column_index(&headers, "fact").unwrap_or(1);
+	let hit_idx = column_index(&headers, "hit").unwrap_or(4);
+	let overalloc_idx = column_index(&headers, "overalloc").unwrap_or(5);
+	let mut advice: Vec<PGAAdvice> = Vec::new();
+	for row in table.select(&row_selector) {
+		let columns = row.select(&column_selector).collect::<Vec<_>>();
+		if columns.len() > factor_idx.max(hit_idx).max(overalloc_idx) {
+			let value = |i: usize| f64::from_str(&columns[i].text().collect::<String>().trim().replace(",", "")).ok();
+			if let (Some(target_mb), Some(size_factor), Some(est_cache_hit_pct)) = (value(0), value(factor_idx), value(hit_idx)) {
+				advice.push(PGAAdvice { target_mb, size_factor, est_cache_hit_pct, est_overalloc_count: value(overalloc_idx).unwrap_or(0.0) });
+			}
+		}
+	}
+	advice
+}
+
+/// Numeric rows of a Statspack advisory section - from the section title until the next page
+fn advisory_rows_txt(awr_lines: &[&str], title: &str, min_columns: usize) -> Vec<Vec<f64>> {
+	let Some(start) = awr_lines.iter().position(|l| l.trim_start_matches(12u8 as char).starts_with(title)) else {
+		return Vec::new();
+	};
+	let mut rows: Vec<Vec<f64>> = Vec::new();
+	for line in &awr_lines[start + 1..] {
+		if line.starts_with(12u8 as char) {
+			break;
+		}
+		let values: Vec<Option<f64>> = line.split_whitespace().map(|t| f64::from_str(&t.replace(",", "")).ok()).collect();
+		if values.len() >= min_columns && values.iter().all(|v| v.is_some()) {
+			rows.push(values.into_iter().flatten().collect());
+		} else if !rows.is_empty() && !line.trim().is_empty() {
+			break;
+		}
+	}
+	rows
+}
+
+// Statspack: SGA Target Size (M), SGA Size Factor, Est DB Time (s), Est DB Time Factor, Est Physical Reads
+fn sga_target_advisory_txt(awr_lines: &[&str]) -> Vec<SGATargetAdvice> {
+	advisory_rows_txt(awr_lines, "SGA Target Advisory", 4)
+		.into_iter()
+		.map(|r| SGATargetAdvice { size_mb: r[0], size_factor: r[1], est_db_time_s: r[2], est_physical_reads: r.get(4).copied().unwrap_or(0.0) })
+		.collect()
+}
+
+// Statspack: PGA Aggr Target (M), Size Factr, W/A MB Processed, Estd Extra W/A MB, Estd PGA Cache Hit %, Estd PGA Overalloc Count, Estd Time
+fn pga_advisory_txt(awr_lines: &[&str]) -> Vec<PGAAdvice> {
+	advisory_rows_txt(awr_lines, "PGA Memory Advisory", 6)
+		.into_iter()
+		.map(|r| PGAAdvice { target_mb: r[0], size_factor: r[1], est_cache_hit_pct: r[4], est_overalloc_count: r[5] })
+		.collect()
+}
+
+fn latch_sleep_breakdown(table: ElementRef) -> Vec<LatchSleep> {
+	let row_selector = Selector::parse("tr").unwrap();
+    let column_selector = Selector::parse("td").unwrap();
+	let headers = table_headers(table);
+	let get_idx = column_index(&headers, "get requests").unwrap_or(1);
+	let misses_idx = column_index(&headers, "misses").unwrap_or(2);
+	let sleeps_idx = column_index(&headers, "sleeps").unwrap_or(3);
+	let spin_idx = column_index(&headers, "spin gets").unwrap_or(4);
+	let mut latches: Vec<LatchSleep> = Vec::new();
+	for row in table.select(&row_selector) {
+		let columns = row.select(&column_selector).collect::<Vec<_>>();
+		if columns.len() > get_idx.max(misses_idx).max(sleeps_idx) {
+			let value = |i: usize| columns.get(i).and_then(|c| u64::from_str(&c.text().collect::<String>().trim().replace(",", "")).ok());
+			let latch_name = columns[0].text().collect::<String>().trim().to_string();
+			if let (Some(get_requests), Some(misses), Some(sleeps)) = (value(get_idx), value(misses_idx), value(sleeps_idx)) {
+				latches.push(LatchSleep { latch_name, get_requests, misses, sleeps, spin_gets: value(spin_idx).unwrap_or(0) });
+			}
+		}
+	}
+	latches
+}
+
+fn mutex_sleep_summary(table: ElementRef) -> Vec<MutexSleep> {
+	let row_selector = Selector::parse("tr").unwrap();
+    let column_selector = Selector::parse("td").unwrap();
+	let headers = table_headers(table);
+	let location_idx = column_index(&headers, "location").unwrap_or(1);
+	let sleeps_idx = column_index(&headers, "sleeps").unwrap_or(2);
+	let wait_idx = column_index(&headers, "wait time").unwrap_or(3);
+	let mut mutexes: Vec<MutexSleep> = Vec::new();
+	for row in table.select(&row_selector) {
+		let columns = row.select(&column_selector).collect::<Vec<_>>();
+		if columns.len() > location_idx.max(sleeps_idx).max(wait_idx) {
+			let text = |i: usize| columns[i].text().collect::<String>().trim().replace(",", "");
+			if let (Ok(sleeps), Ok(wait_time_ms)) = (u64::from_str(&text(sleeps_idx)), f64::from_str(&text(wait_idx))) {
+				mutexes.push(MutexSleep { mutex_type: text(0), location: text(location_idx), sleeps, wait_time_ms });
+			}
+		}
+	}
+	mutexes
+}
+
+// Statspack: lines of the section with the given title, up to the next page
+fn section_lines_txt<'a>(awr_lines: &[&'a str], title: &str) -> Vec<&'a str> {
+	let Some(start) = awr_lines.iter().position(|l| l.trim_start_matches(12u8 as char).starts_with(title)) else {
+		return Vec::new();
+	};
+	awr_lines[start + 1..].iter().take_while(|l| !l.starts_with(12u8 as char)).copied().collect()
+}
+
+// Statspack: Latch Name, Get Requests, Misses, Sleeps, Spin Gets - latch name may contain spaces
+fn latch_sleep_breakdown_txt(awr_lines: &[&str]) -> Vec<LatchSleep> {
+	let mut latches: Vec<LatchSleep> = Vec::new();
+	for line in section_lines_txt(awr_lines, "Latch Sleep breakdown") {
+		let tokens: Vec<&str> = line.split_whitespace().collect();
+		if tokens.len() < 5 {
+			continue;
+		}
+		let values: Vec<Option<u64>> = tokens[tokens.len() - 4..].iter().map(|t| u64::from_str(&t.replace(",", "")).ok()).collect();
+		if let [Some(get_requests), Some(misses), Some(sleeps), Some(spin_gets)] = values[..] {
+			latches.push(LatchSleep { latch_name: tokens[..tokens.len() - 4].join(" "), get_requests, misses, sleeps, spin_gets });
+		}
+	}
+	latches
+}
+
+// Statspack: Mutex Type (19 characters), Location, Sleeps, Wait Time (ms)
+fn mutex_sleep_summary_txt(awr_lines: &[&str]) -> Vec<MutexSleep> {
+	let mut mutexes: Vec<MutexSleep> = Vec::new();
+	for line in section_lines_txt(awr_lines, "Mutex Sleep Summary") {
+		let tokens: Vec<&str> = line.split_whitespace().collect();
+		if tokens.len() < 3 || line.len() < 20 || line.starts_with(' ') {
+			continue;
+		}
+		let sleeps = u64::from_str(&tokens[tokens.len() - 2].replace(",", ""));
+		let wait_time_ms = f64::from_str(&tokens[tokens.len() - 1].replace(",", ""));
+		if let (Ok(sleeps), Ok(wait_time_ms)) = (sleeps, wait_time_ms) {
+			let mutex_type = line.get(0..19).unwrap_or("").trim().to_string();
+			let name_part = tokens[..tokens.len() - 2].join(" ");
+			let location = name_part.strip_prefix(mutex_type.as_str()).unwrap_or(&name_part).trim().to_string();
+			mutexes.push(MutexSleep { mutex_type, location, sleeps, wait_time_ms });
+		}
+	}
+	mutexes
+}
+
 fn initialization_parameters_txt(inst_stats_section: Vec<&str>) -> HashMap<String, String> {
     let mut params: HashMap<String, String> = HashMap::new();
 
@@ -768,10 +1001,13 @@ fn dictionary_cache_stats(table: ElementRef) -> Vec<DictionaryCache> {
 			let get_req = columns[1].text().collect::<Vec<_>>();
 			let get_req = u64::from_str(&get_req[0].trim().replace(",","")).unwrap_or(0);
 
+			let get_pct_miss = columns[2].text().collect::<Vec<_>>();
+			let get_pct_miss = get_pct_miss.first().map_or(0.0, |v| f64::from_str(&v.trim().replace(",","")).unwrap_or(0.0));
+
 			let final_usage = columns[6].text().collect::<Vec<_>>();
 			let final_usage = u64::from_str(&final_usage[0].trim().replace(",","")).unwrap_or(0);
 
-			dictionary_cache_stats.push(DictionaryCache {statname: stat_name.to_string(), get_requests: get_req, final_usage: final_usage});
+			dictionary_cache_stats.push(DictionaryCache {statname: stat_name.to_string(), get_requests: get_req, final_usage: final_usage, get_pct_miss: get_pct_miss});
 		}
 	}
 
@@ -785,8 +1021,9 @@ fn dictionary_cache_stats_txt(dictionary_cache_section: Vec<&str>) -> Vec<Dictio
 			let statname = line[0..25].to_string().trim().to_string();
 			let get_requests = u64::from_str(&line[26..38].trim().replace(",",""));
 			let final_usage = u64::from_str(&line[69..79].trim().replace(",",""));
+			let get_pct_miss = line.get(39..45).map_or(0.0, |v| f64::from_str(v.trim()).unwrap_or(0.0));
 			if get_requests.is_ok() && final_usage.is_ok() {
-				dictionary_cache_stats_txt.push(DictionaryCache{statname: statname.to_string(), get_requests: get_requests.unwrap() , final_usage: final_usage.unwrap()});
+				dictionary_cache_stats_txt.push(DictionaryCache{statname: statname.to_string(), get_requests: get_requests.unwrap() , final_usage: final_usage.unwrap(), get_pct_miss: get_pct_miss});
 			}
 		}
 		
@@ -814,7 +1051,16 @@ fn library_cache_stats(table: ElementRef) -> Vec<LibraryCache> {
 			let get_req_pct_miss = columns[2].text().collect::<Vec<_>>();
 			let get_req_pct_miss = f64::from_str(&get_req_pct_miss[0].trim().replace(",","")).unwrap_or(0.0);
 
-			library_cache_stats.push(LibraryCache {statname: stat_name.to_string(), get_requests: get_req, get_pct_miss: get_req_pct_miss, pin_requests: pin_req});
+			let pin_pct_miss = columns[4].text().collect::<Vec<_>>();
+			let pin_pct_miss = pin_pct_miss.first().map_or(0.0, |v| f64::from_str(&v.trim().replace(",","")).unwrap_or(0.0));
+
+			let reloads = columns[5].text().collect::<Vec<_>>();
+			let reloads = reloads.first().map_or(0, |v| u64::from_str(&v.trim().replace(",","")).unwrap_or(0));
+
+			let invalidations = columns[6].text().collect::<Vec<_>>();
+			let invalidations = invalidations.first().map_or(0, |v| u64::from_str(&v.trim().replace(",","")).unwrap_or(0));
+
+			library_cache_stats.push(LibraryCache {statname: stat_name.to_string(), get_requests: get_req, get_pct_miss: get_req_pct_miss, pin_requests: pin_req, pin_pct_miss: pin_pct_miss, reloads: reloads, invalidations: invalidations});
 		}
 	}
 
@@ -885,7 +1131,7 @@ fn is_library_cache_header_line(line: &str) -> bool {
 /// Line 1: NAMESPACE_NAME           get_requests  get_pct_miss  [pin_requests]
 /// Line 2:                         [pin_requests]  [pin_pct_miss]  reloads  invalidations
 ///
-/// We only need: statname, get_requests, get_pct_miss, pin_requests
+/// pin_pct_miss is missing when there were no pin requests, reloads and invalidations are the last two tokens
 fn parse_library_cache_two_lines(first_line: &str, second_line: Option<&str>) -> Option<LibraryCache> {
 	// Find where the namespace name ends and numeric data begins.
 	// The namespace is followed by at least 2 consecutive spaces before the first number.
@@ -920,11 +1166,20 @@ fn parse_library_cache_two_lines(first_line: &str, second_line: Option<&str>) ->
 		0
 	};
 
+	let (pin_pct_miss, reloads, invalidations) = match all_tokens.len() {
+		n if n >= 6 => (parse_lib_f64(all_tokens[3]).unwrap_or(0.0), parse_lib_u64(all_tokens[4]).unwrap_or(0), parse_lib_u64(all_tokens[5]).unwrap_or(0)),
+		5 => (0.0, parse_lib_u64(all_tokens[3]).unwrap_or(0), parse_lib_u64(all_tokens[4]).unwrap_or(0)),
+		_ => (0.0, 0, 0),
+	};
+
 	Some(LibraryCache {
 		statname,
 		get_requests,
 		get_pct_miss,
 		pin_requests,
+		pin_pct_miss,
+		reloads,
+		invalidations,
 	})
 }
 
@@ -2373,10 +2628,9 @@ fn parse_db_instance_information(fname: String) -> DBInstance {
 	db_instance_information
 }
 
-fn parse_awr_report_internal(fname: &str, args: &Args) -> (AWR, HashMap<String, String>, HashMap<String, String>) {
+fn parse_awr_report_internal(fname: &str, args: &Args) -> (AWR, HashMap<String, String>) {
 	let mut awr: AWR = AWR::default();
 	let mut sqls_txt: HashMap<String, String> = HashMap::new();
-	let mut parameters: HashMap<String, String> = HashMap::new();
 	debug_note!("Parsing file: {}", fname);
 	if fname.ends_with("html") {
 
@@ -2429,6 +2683,10 @@ fn parse_awr_report_internal(fname: &str, args: &Args) -> (AWR, HashMap<String,
 				awr.library_cache = library_cache_stats(element);
 			} else if element.value().attr("summary").unwrap() == "This table displays latch statistics. Get requests, % get miss, wait time, noWait requests are displayed for each latch" {
 				awr.latch_activity = latch_activity_stats(element);
+			} else if element.value().attr("summary").unwrap().to_lowercase().contains("sleep") && table_headers(element).iter().any(|h| h.to_lowercase().contains("spin gets")) {
+				awr.latch_sleep_breakdown = latch_sleep_breakdown(element);
+			} else if element.value().attr("summary").unwrap().to_lowercase().contains("sleep") && table_headers(element).iter().any(|h| h.to_lowercase().contains("mutex type")) {
+				awr.mutex_sleep_summary = mutex_sleep_summary(element);
 			} else if element.value().attr("summary").unwrap() == "This table displays top segments by row lock waits. Owner, tablespace name, object type, row lock waits, etc. are displayed for each segment" {
 				let segment = segment_stats(element, "Row Lock Waits", &args);
 				awr.segment_stats.insert("Row Lock Waits".to_string(), segment);
@@ -2464,7 +2722,11 @@ fn parse_awr_report_internal(fname: &str, args: &Args) -> (AWR, HashMap<String,
 			} else if element.value().attr("summary").unwrap().starts_with("This table displays name and value of the modified initialization parameters") 
 			       || element.value().attr("summary").unwrap().starts_with("This table displays name and value of init.ora parameters")
 				   || element.value().attr("summary").unwrap().starts_with("This table displays name and value of the initialization parametersmodified by the current container"){
-				 parameters = initialization_parameters(element);
+				 awr.parameters = initialization_parameters(element);
+			} else if element.value().attr("summary").unwrap().to_lowercase().contains("sga target advisory") {
+				awr.sga_target_advisory = sga_target_advisory(element);
+			} else if element.value().attr("summary").unwrap().to_lowercase().contains("pga memory advisory") {
+				awr.pga_advisory = pga_advisory(element);
 			} else if element.value().attr("summary").unwrap() == "This table displays the Top SQL by Top Wait Events" {
 				awr.top_sql_with_top_events = top_sql_with_top_events(element);
 			} else if element.value().attr("summary").unwrap() == "This table displays total number of waits, and information about total wait time, for each wait event" {
@@ -2536,7 +2798,12 @@ fn parse_awr_report_internal(fname: &str, args: &Args) -> (AWR, HashMap<String,
 		let parameters_section_index = find_section_boundries(awr_lines.clone(), &parameters_section_start, "End of Report",&fname, None);
 		let mut parameters_section: Vec<&str> = Vec::new();
 		parameters_section.extend_from_slice(&awr_lines[parameters_section_index.begin+5..parameters_section_index.end-1]);
-		parameters = initialization_parameters_txt(parameters_section);
+		awr.parameters = initialization_parameters_txt(parameters_section);
+
+		awr.sga_target_advisory = sga_target_advisory_txt(&awr_lines);
+		awr.pga_advisory = pga_advisory_txt(&awr_lines);
+		awr.latch_sleep_breakdown = latch_sleep_breakdown_txt(&awr_lines);
+		awr.mutex_sleep_summary = mutex_sleep_summary_txt(&awr_lines);
 
 		debug_note!("Trying to parse SQL ordered by CPU section of file: {fname}");
 		let sql_cpu_section_start = format!("{}{}", 12u8 as char, "SQL ordered by CPU");
@@ -2720,7 +2987,7 @@ fn parse_awr_report_internal(fname: &str, args: &Args) -> (AWR, HashMap<String,
 	}
 	awr.status = "OK".to_string();
 	awr.file_name = fname.to_string();
-	(awr, sqls_txt, parameters)
+	(awr, sqls_txt)
 }
 
 
@@ -2768,24 +3035,18 @@ pub fn parse_awr_dir(args: Args, events_sqls: &mut HashMap<&str, HashSet<String>
 
 	//This is save HashMap which will be filled with SQLText if appropriate Security Level is being set
 	let sqls_txt = Arc::new(DashMap::<String, String>::new());
-	let parameters = Arc::new(DashMap::<String, String>::new());
 
     let mut awr_vec: Vec<AWR> = file_collection
         .par_iter()
         .map_init( //initialize variables for each thread
-            || (Arc::clone(&counter), Arc::clone(&sqls_txt), Arc::clone(&parameters)), //initializied will be counter as cloned value for each thread
-            |(counter, s, p), f| { //map operator is initialized clone of counter and file name
-                let (result, sqls, params) = parse_awr_report_internal(f, &args); //each thread is processing one file
+            || (Arc::clone(&counter), Arc::clone(&sqls_txt)), //initializied will be counter as cloned value for each thread
+            |(counter, s), f| { //map operator is initialized clone of counter and file name
+                let (result, sqls) = parse_awr_report_internal(f, &args); //each thread is processing one file
 				if !sqls.is_empty() {
 					for (sqlid, sqltxt) in sqls {
 						s.entry(sqlid).or_insert(sqltxt);
 					}
 				}
-				if !params.is_empty() {
-					for (pname, pvalue) in params {
-						p.entry(pname).or_insert(pvalue);
-					}
-				}
 				counter.fetch_add(1, Ordering::Relaxed); //increment counter
                 result
             },
@@ -2829,12 +3090,13 @@ pub fn parse_awr_dir(args: Args, events_sqls: &mut HashMap<&str, HashSet<String>
 		.into_iter()
 		.collect();
 
-	let dash = Arc::try_unwrap(parameters)
-    	.expect("Other Arc clones still exist");
-
-	let parameters_final = dash
-		.into_iter()
-		.collect();
+	/* Parameters are kept per snapshot - the collection gets parameters of the latest snapshot */
+	let parameters_final: HashMap<String, String> = awr_vec
+		.iter()
+		.rev()
+		.find(|a| !a.parameters.is_empty())
+		.map(|a| a.parameters.clone())
+		.unwrap_or_default();
 
 	/* ************************* */
 
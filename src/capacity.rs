@@ -0,0 +1,226 @@
+use crate::awr::{AWR, DBInstance};
+use crate::Args;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, CpuCapacity, CpuPressurePeriod};
+use colored::*;
+use plotly::{Plot, Scatter};
+use plotly::common::{DashType, Line, Marker, MarkerSymbol, Mode};
+use plotly::layout::{Axis, HoverMode, Layout, RangeMode};
+use prettytable::{Table, Row, Cell};
+
+/* Average Active Sessions versus CPU capacity.
+   AAS = DB Time / elapsed seconds of the snapshot, CPU demand = (DB CPU + resmgr:cpu quantum) / elapsed seconds.
+   resmgr:cpu quantum is the time sessions were ready to run but Resource Manager kept them off CPU,
+   so together with DB CPU it shows how much CPU the database wanted, not only how much it got.
+   CPU demand is compared with the number of CPUs (the smaller of host CPUs and CPUs seen by the instance). */
+
+/// Minimal AAS waiting on resmgr:cpu quantum to treat snapshot as throttled by Resource Manager
+const RESMGR_MIN_AAS: f64 = 0.1;
+/// Host CPU busy % treated as saturation regardless of database CPU demand
+const HOST_BUSY_SATURATED_PCT: f64 = 95.0;
+
+struct SnapCapacity {
+    x_val: String,
+    snap_id: u64,
+    snap_date: String,
+    aas: f64,
+    aas_cpu: f64,
+    aas_resmgr: f64,
+    cpu_demand: f64,
+    host_busy_pct: f64,
+    cpus: u32,
+    cores: u32,
+    status: &'static str,
+}
+
+/// Elapsed seconds of the snapshot - from snapshot times, or from DB Time and DB Time per second of Load Profile
+pub fn snapshot_elapsed_s(awr: &AWR) -> Option<f64> {
+    if let (Some(begin), Some(end)) = (parse_snap_time(&awr.snap_info.begin_snap_time), parse_snap_time(&awr.snap_info.end_snap_time)) {
+        let elapsed = (end - begin).num_seconds() as f64;
+        if elapsed > 0.0 {
+            return Some(elapsed);
+        }
+    }
+    let aas = db_time_per_s(awr);
+    (aas > 0.0).then(|| db_time_s(awr) / aas)
+}
+
+fn snapshot_capacity(awr: &AWR, db_instance: &DBInstance, high_pct: f64) -> Option<SnapCapacity> {
+    let db_time = db_time_s(awr);
+    let db_cpu_s = awr.time_model_stats.iter().find(|tm| tm.stat_name.starts_with("DB CPU")).map_or(0.0, |tm| tm.time_s);
+    let resmgr_s: f64 = awr.foreground_wait_events.iter()
+        .filter(|e| e.event.starts_with("resmgr:cpu quantum"))
+        .map(|e| e.total_wait_time_s)
+        .sum();
+    let elapsed = snapshot_elapsed_s(awr)?;
+
+    let host_cpus = if awr.host_cpu.cpus > 0 { awr.host_cpu.cpus } else { db_instance.cpus as u32 };
+    let cpus = if db_instance.cpus > 0 { host_cpus.min(db_instance.cpus as u32) } else { host_cpus };
+    let cores = if awr.host_cpu.cores > 0 { awr.host_cpu.cores } else { db_instance.cores as u32 };
+
+    let aas_cpu = db_cpu_s / elapsed;
+    let aas_resmgr = resmgr_s / elapsed;
+    let cpu_demand = aas_cpu + aas_resmgr;
+    let host_busy_pct = if awr.host_cpu.cpus > 0 { 100.0 - awr.host_cpu.pct_idle } else { 0.0 };
+
+    let status = if cpus == 0 {
+        "UNKNOWN"
+    } else if cpu_demand >= cpus as f64 || host_busy_pct >= HOST_BUSY_SATURATED_PCT {
+        "SATURATED"
+    } else if cpu_demand >= cpus as f64 * high_pct / 100.0 || host_busy_pct >= high_pct {
+        "HIGH"
+    } else {
+        "OK"
+    };
+
+    Some(SnapCapacity {
+        x_val: format!("{} ({})", awr.snap_info.begin_snap_time, awr.snap_info.begin_snap_id),
+        snap_id: awr.snap_info.begin_snap_id,
+        snap_date: awr.snap_info.begin_snap_time.clone(),
+        aas: db_time / elapsed,
+        aas_cpu,
+        aas_resmgr,
+        cpu_demand,
+        host_busy_pct,
+        cpus,
+        cores,
+        status,
+    })
+}
+
+/// Consecutive snapshots with HIGH/SATURATED status or Resource Manager throttling grouped into periods
+fn pressure_periods(snaps: &[SnapCapacity]) -> Vec<CpuPressurePeriod> {
+    let mut periods: Vec<CpuPressurePeriod> = Vec::new();
+    let mut current: Option<CpuPressurePeriod> = None;
+    for s in snaps {
+        let throttled = s.aas_resmgr >= RESMGR_MIN_AAS;
+        if s.status == "HIGH" || s.status == "SATURATED" || throttled {
+            let p = current.get_or_insert_with(|| CpuPressurePeriod {
+                begin_snap_id: s.snap_id,
+                begin_snap_date: s.snap_date.clone(),
+                status: "HIGH".to_string(),
+                ..Default::default()
+            });
+            p.end_snap_id = s.snap_id;
+            p.end_snap_date = s.snap_date.clone();
+            p.snapshots += 1;
+            if s.status == "SATURATED" {
+                p.status = "SATURATED".to_string();
+            }
+            p.resmgr_throttling |= throttled;
+            p.max_aas = p.max_aas.max(s.aas);
+            p.max_cpu_demand = p.max_cpu_demand.max(s.cpu_demand);
+            p.max_resmgr_aas = p.max_resmgr_aas.max(s.aas_resmgr);
+            p.max_host_busy_pct = p.max_host_busy_pct.max(s.host_busy_pct);
+            p.min_headroom_cpus = if p.snapshots == 1 { s.cpus as f64 - s.cpu_demand } else { p.min_headroom_cpus.min(s.cpus as f64 - s.cpu_demand) };
+        } else if let Some(p) = current.take() {
+            periods.push(p);
+        }
+    }
+    if let Some(p) = current.take() {
+        periods.push(p);
+    }
+    periods
+}
+
+/// Computes AAS and CPU demand per snapshot, flags CPU pressure periods, attaches CpuCapacity to ReportForAI
+/// and returns inline HTML plot for the Stats Explorer section of the main report.
+pub fn report_cpu_capacity(awrs: &[AWR], snap_range: &(u64,u64), db_instance: &DBInstance, args: &Args, logfile_name: &str, report_for_ai: &mut ReportForAI) -> String {
+    let snaps: Vec<SnapCapacity> = snaps_in_range(awrs, snap_range)
+        .filter_map(|awr| snapshot_capacity(awr, db_instance, args.cpu_high_pct))
+        .collect();
+
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{} {}%\n", "CPU capacity - Average Active Sessions vs CPUs, HIGH threshold:".yellow(), args.cpu_high_pct);
+
+    if snaps.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tSnapshot duration is unknown - AAS can't be computed\n".green().italic());
+        return String::new();
+    }
+
+    let aas: Vec<f64> = snaps.iter().map(|s| s.aas).collect();
+    let demand: Vec<f64> = snaps.iter().map(|s| s.cpu_demand).collect();
+    let periods = pressure_periods(&snaps);
+    let capacity = CpuCapacity {
+        host_cpus: snaps.iter().map(|s| s.cpus).max().unwrap_or(0).max(snaps_in_range(awrs, snap_range).map(|a| a.host_cpu.cpus).max().unwrap_or(0)),
+        host_cores: snaps.iter().map(|s| s.cores).max().unwrap_or(0),
+        db_instance_cpus: db_instance.cpus as u32,
+        high_threshold_pct: args.cpu_high_pct,
+        aas_mean: aas.iter().sum::<f64>() / aas.len() as f64,
+        aas_p95: percentile(&aas, 95.0),
+        aas_max: aas.iter().cloned().fold(0.0, f64::max),
+        cpu_demand_p95: percentile(&demand, 95.0),
+        cpu_demand_max: demand.iter().cloned().fold(0.0, f64::max),
+        min_headroom_cpus: snaps.iter().map(|s| s.cpus as f64 - s.cpu_demand).fold(f64::INFINITY, f64::min),
+        snapshots_saturated: snaps.iter().filter(|s| s.status == "SATURATED").count(),
+        snapshots_high: snaps.iter().filter(|s| s.status == "HIGH").count(),
+        snapshots_resmgr_throttled: snaps.iter().filter(|s| s.aas_resmgr >= RESMGR_MIN_AAS).count(),
+        pressure_periods: periods,
+    };
+
+    make_notes!(logfile_name, args.quiet, 0, "\tCPUs: {} (cores: {}, instance: {})  AAS mean/P95/max: {:.2}/{:.2}/{:.2}  CPU demand P95/max: {:.2}/{:.2}  Min headroom: {:.2} CPUs\n",
+        capacity.host_cpus, capacity.host_cores, capacity.db_instance_cpus,
+        capacity.aas_mean, capacity.aas_p95, capacity.aas_max,
+        capacity.cpu_demand_p95, capacity.cpu_demand_max, capacity.min_headroom_cpus);
+    make_notes!(logfile_name, args.quiet, 0, "\tSnapshots SATURATED: {}  HIGH: {}  throttled by Resource Manager: {}\n",
+        capacity.snapshots_saturated, capacity.snapshots_high, capacity.snapshots_resmgr_throttled);
+
+    if capacity.pressure_periods.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo CPU pressure periods found\n".green().italic());
+    } else {
+        let mut table = Table::new();
+        table.set_titles(Row::new(vec![
+            Cell::new("Begin"),
+            Cell::new("End"),
+            Cell::new("Snaps"),
+            Cell::new("Status"),
+            Cell::new("Max AAS"),
+            Cell::new("Max CPU demand"),
+            Cell::new("Max resmgr AAS"),
+            Cell::new("Max host busy %"),
+            Cell::new("Min headroom"),
+        ]));
+        for p in &capacity.pressure_periods {
+            table.add_row(Row::new(vec![
+                Cell::new(&format!("{} ({})", p.begin_snap_date, p.begin_snap_id)),
+                Cell::new(&format!("{} ({})", p.end_snap_date, p.end_snap_id)),
+                Cell::new(&format!("{}", p.snapshots)),
+                Cell::new(&format!("{}{}", p.status, if p.resmgr_throttling { " + RESMGR" } else { "" })),
+                Cell::new(&format!("{:.2}", p.max_aas)),
+                Cell::new(&format!("{:.2}", p.max_cpu_demand)),
+                Cell::new(&format!("{:.2}", p.max_resmgr_aas)),
+                Cell::new(&format!("{:.1}", p.max_host_busy_pct)),
+                Cell::new(&format!("{:.2}", p.min_headroom_cpus)),
+            ]));
+        }
+        make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&table));
+    }
+
+    // === Create the capacity plot ===
+    let x_vals: Vec<String> = snaps.iter().map(|s| s.x_val.clone()).collect();
+    let mut plot = Plot::new();
+    plot.add_trace(Scatter::new(x_vals.clone(), aas.clone()).mode(Mode::Lines).name("AAS (DB Time/s)").marker(Marker::new().color("#632e4f")));
+    plot.add_trace(Scatter::new(x_vals.clone(), snaps.iter().map(|s| s.aas_cpu).collect::<Vec<f64>>()).mode(Mode::Lines).name("DB CPU AAS").marker(Marker::new().color("#2ca02c")));
+    plot.add_trace(Scatter::new(x_vals.clone(), demand.clone()).mode(Mode::Lines).name("CPU + resmgr:cpu quantum").marker(Marker::new().color("#ff7f0e")));
+    plot.add_trace(Scatter::new(x_vals.clone(), snaps.iter().map(|s| s.cpus as f64).collect::<Vec<f64>>())
+        .mode(Mode::Lines).name("CPUs").line(Line::new().color("#d62728").dash(DashType::Dash)));
+    plot.add_trace(Scatter::new(x_vals.clone(), snaps.iter().map(|s| s.cores as f64).collect::<Vec<f64>>())
+        .mode(Mode::Lines).name("Cores").line(Line::new().color("#7f7f7f").dash(DashType::Dot)));
+    plot.add_trace(Scatter::new(x_vals.clone(), snaps.iter().map(|s| s.cpus as f64 * args.cpu_high_pct / 100.0).collect::<Vec<f64>>())
+        .mode(Mode::Lines).name(format!("HIGH threshold ({}%)", args.cpu_high_pct)).line(Line::new().color("#bcbd22").dash(DashType::DashDot)));
+    let flagged: Vec<&SnapCapacity> = snaps.iter().filter(|s| s.status == "SATURATED" || s.status == "HIGH").collect();
+    plot.add_trace(Scatter::new(flagged.iter().map(|s| s.x_val.clone()).collect::<Vec<String>>(), flagged.iter().map(|s| s.cpu_demand).collect::<Vec<f64>>())
+        .mode(Mode::Markers)
+        .name("CPU pressure")
+        .text_array(flagged.iter().map(|s| s.status.to_string()).collect::<Vec<String>>())
+        .marker(Marker::new().color("#d62728").size(9).symbol(MarkerSymbol::TriangleUp)));
+    plot.set_layout(Layout::new()
+        .title("Average Active Sessions vs CPU capacity")
+        .height(600)
+        .hover_mode(HoverMode::X)
+        .y_axis(Axis::new().title("Sessions / CPUs").range_mode(RangeMode::ToZero)));
+
+    report_for_ai.cpu_capacity = Some(capacity);
+    plot.to_inline_html(Some("cpu-capacity-plot"))
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+use crate::awr::AWR;
+use crate::Args;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, ChangePoint};
+use colored::*;
+use plotly::{Plot, Scatter};
+use plotly::color::NamedColor;
+use plotly::common::{DashType, Marker, MarkerSymbol, Mode};
+use plotly::layout::{Layout, Shape, ShapeLayer, ShapeLine, ShapeType};
+use prettytable::{Table, Row, Cell};
+use rayon::prelude::*;
+
+/* Change-point detection for DB Time and key metrics.
+   MAD flags single snapshots that stick out, but a lasting level shift ("DB Time doubled after Friday deployment
+   and stayed there") is just a new normal for it. Here every series is split with binary segmentation using
+   the cost of a normal distribution with changing mean and variance:
+        cost(segment) = n * ln(variance of segment)
+   A split is accepted if it lowers the total cost by more than penalty * ln(N). */
+
+/// Prefix sums of x and x^2 - cost of any segment is computed in O(1)
+struct SegmentCost {
+    sum: Vec<f64>,
+    sum_sq: Vec<f64>,
+    var_floor: f64,
+}
+
+impl SegmentCost {
+    fn new(values: &[f64]) -> Self {
+        let mut sum = vec![0.0; values.len() + 1];
+        let mut sum_sq = vec![0.0; values.len() + 1];
+        for (i, v) in values.iter().enumerate() {
+            sum[i + 1] = sum[i] + v;
+            sum_sq[i + 1] = sum_sq[i] + v * v;
+        }
+        //Constant segments would have ln(0) = -inf, so variance is floored relative to the whole series
+        let n = values.len() as f64;
+        let total_var = (sum_sq[values.len()] - sum[values.len()].powi(2) / n) / n;
+        let var_floor = (total_var * 1e-4).max(1e-12);
+        SegmentCost { sum, sum_sq, var_floor }
+    }
+
+    /// Cost of values[a..b]
+    fn cost(&self, a: usize, b: usize) -> f64 {
+        let n = (b - a) as f64;
+        let s = self.sum[b] - self.sum[a];
+        let s2 = self.sum_sq[b] - self.sum_sq[a];
+        let var = ((s2 - s * s / n) / n).max(self.var_floor);
+        n * var.ln()
+    }
+}
+
+/// Binary segmentation over a single series.
+/// Returns sorted indexes where the new segment begins and cost reduction achieved by each split.
+pub fn binary_segmentation(values: &[f64], penalty: f64, min_size: usize) -> Vec<(usize, f64)> {
+    let min_size = min_size.max(2);
+    let n = values.len();
+    if n < 2 * min_size {
+        return Vec::new();
+    }
+
+    let seg_cost = SegmentCost::new(values);
+    let beta = penalty * (n as f64).ln();
+    let mut change_points: Vec<(usize, f64)> = Vec::new();
+    let mut segments: Vec<(usize, usize)> = vec![(0, n)];
+
+    while let Some((a, b)) = segments.pop() {
+        if b - a < 2 * min_size {
+            continue;
+        }
+        let full_cost = seg_cost.cost(a, b);
+        let mut best: Option<(usize, f64)> = None;
+        for k in (a + min_size)..=(b - min_size) {
+            let split_cost = seg_cost.cost(a, k) + seg_cost.cost(k, b);
+            if best.is_none_or(|(_, c)| split_cost < c) {
+                best = Some((k, split_cost));
+            }
+        }
+        if let Some((k, split_cost)) = best {
+            let gain = full_cost - split_cost;
+            if gain > beta {
+                change_points.push((k, gain));
+                segments.push((a, k));
+                segments.push((k, b));
+            }
+        }
+    }
+
+    change_points.sort_by_key(|cp| cp.0);
+    change_points
+}
+
+/// Runs change-point detection over each series (all series have to be aligned with snapshots in snap_range).
+/// Statistics are compared between neighbouring segments, so each change point describes one step.
+pub fn detect_change_points(awrs: &[AWR], snap_range: &(u64,u64), series: &BTreeMap<(String, String), Vec<f64>>, penalty: f64, min_size: usize) -> Vec<ChangePoint> {
+    let snaps: Vec<(u64, String)> = snaps_in_range(awrs, snap_range)
+        .map(|awr| (awr.snap_info.begin_snap_id, awr.snap_info.begin_snap_time.clone()))
+        .collect();
+
+    let mut change_points: Vec<ChangePoint> = series
+        .par_iter() //each thread will process one series
+        .filter(|(_, values)| values.len() == snaps.len() && values.iter().any(|v| *v != 0.0))
+        .flat_map(|((series_type, series_name), values)| {
+            let cps = binary_segmentation(values, penalty, min_size);
+            let mut bounds: Vec<usize> = vec![0];
+            bounds.extend(cps.iter().map(|cp| cp.0));
+            bounds.push(values.len());
+
+            cps.iter().enumerate().map(|(i, (k, gain))| {
+                let before = &values[bounds[i]..*k];
+                let after = &values[*k..bounds[i + 2]];
+                let mean_before = mean(before.to_vec()).unwrap_or(0.0);
+                let mean_after = mean(after.to_vec()).unwrap_or(0.0);
+                let change_pct = if mean_before.abs() > 0.0 {
+                    (mean_after - mean_before) / mean_before.abs() * 100.0
+                } else {
+                    0.0
+                };
+                ChangePoint {
+                    series_type: series_type.clone(),
+                    series_name: series_name.clone(),
+                    change_begin_snap_id: snaps[*k].0,
+                    change_begin_snap_date: snaps[*k].1.clone(),
+                    snaps_before: before.len(),
+                    snaps_after: after.len(),
+                    mean_before,
+                    mean_after,
+                    median_before: median(before),
+                    median_after: median(after),
+                    stddev_before: std_deviation(before.to_vec()).unwrap_or(0.0),
+                    stddev_after: std_deviation(after.to_vec()).unwrap_or(0.0),
+                    change_pct,
+                    cost_reduction: *gain,
+                }
+            }).collect::<Vec<ChangePoint>>()
+        })
+        .collect();
+
+    change_points.sort_by(|a, b| {
+        a.change_begin_snap_id.cmp(&b.change_begin_snap_id)
+            .then(b.change_pct.abs().partial_cmp(&a.change_pct.abs()).unwrap_or(std::cmp::Ordering::Equal))
+    });
+    change_points
+}
+
+/// Change point of the DB Time (s/s) series - the one placed on DB Time charts and used to split DB Time into segments
+pub fn is_db_time_change_point(cp: &ChangePoint) -> bool {
+    cp.series_type == "TM" && cp.series_name == "DB Time (s/s)"
+}
+
+/// Prints detected change points to the log, stores them in ReportForAI and returns rows for the HTML table
+pub fn report_change_points(awrs: &[AWR], snap_range: &(u64,u64), series: &BTreeMap<(String, String), Vec<f64>>, args: &Args, logfile_name: &str, report_for_ai: &mut ReportForAI) -> String {
+    let change_points = detect_change_points(awrs, snap_range, series, args.change_point_penalty, args.change_point_min_size);
+
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{} {} {} {}\n", "Change points - penalty:".yellow(), args.change_point_penalty, "minimal segment:".yellow(), args.change_point_min_size);
+
+    let mut html_rows = String::new();
+    if change_points.is_empty() {
+        let no_change_points_txt = "\tNo lasting level or variance shifts were found\n".to_string();
+        make_notes!(logfile_name, args.quiet, 0, "{}", no_change_points_txt.green().italic());
+        return html_rows;
+    }
+
+    let mut table = Table::new();
+    table.set_titles(Row::new(vec![
+        Cell::new("Type"),
+        Cell::new("Series"),
+        Cell::new("Change began"),
+        Cell::new("Mean before"),
+        Cell::new("Mean after"),
+        Cell::new("Median before"),
+        Cell::new("Median after"),
+        Cell::new("StdDev before"),
+        Cell::new("StdDev after"),
+        Cell::new("Change %"),
+    ]));
+
+    for cp in &change_points {
+        table.add_row(Row::new(vec![
+            Cell::new(&cp.series_type),
+            Cell::new(&cp.series_name),
+            Cell::new(&format!("{} ({})", cp.change_begin_snap_date, cp.change_begin_snap_id)),
+            Cell::new(&format!("{:.3}", cp.mean_before)),
+            Cell::new(&format!("{:.3}", cp.mean_after)),
+            Cell::new(&format!("{:.3}", cp.median_before)),
+            Cell::new(&format!("{:.3}", cp.median_after)),
+            Cell::new(&format!("{:.3}", cp.stddev_before)),
+            Cell::new(&format!("{:.3}", cp.stddev_after)),
+            Cell::new(&format!("{:+.1}", cp.change_pct)),
+        ]));
+
+        html_rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{} ({})</td>
+                <td>{:.3}</td>
+                <td>{:.3}</td>
+                <td>{:.3}</td>
+                <td>{:.3}</td>
+                <td>{:.3}</td>
+                <td>{:.3}</td>
+                <td>{:+.1}</td>
+            </tr>"#,
+            cp.series_type, cp.series_name, cp.change_begin_snap_date, cp.change_begin_snap_id,
+            cp.mean_before, cp.mean_after, cp.median_before, cp.median_after,
+            cp.stddev_before, cp.stddev_after, cp.change_pct
+        ));
+    }
+
+    make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&table));
+
+    report_for_ai.change_points = change_points;
+    html_rows
+}
+
+//Change points grouped by snapshot in the same format as x axis of the main chart
+fn change_points_by_xval(change_points: &[ChangePoint]) -> BTreeMap<(u64, String), Vec<&ChangePoint>> {
+    let mut by_snap: BTreeMap<(u64, String), Vec<&ChangePoint>> = BTreeMap::new();
+    for cp in change_points {
+        by_snap.entry((cp.change_begin_snap_id, format!("{} ({})", cp.change_begin_snap_date, cp.change_begin_snap_id)))
+               .or_default()
+               .push(cp);
+    }
+    by_snap
+}
+
+/// Adds marker trace on DB Time axis - shapes have no tooltips, so the list of shifted series is shown here
+pub fn add_change_point_trace(plot: &mut Plot, change_points: &[ChangePoint], x_vals: &[String], y_vals_dbtime: &[f64]) {
+    let by_snap = change_points_by_xval(change_points);
+    if by_snap.is_empty() {
+        return;
+    }
+
+    let mut x: Vec<String> = Vec::new();
+    let mut y: Vec<f64> = Vec::new();
+    let mut text: Vec<String> = Vec::new();
+    for ((_, xval), cps) in &by_snap {
+        let Some(idx) = x_vals.iter().position(|v| v == xval) else { continue; };
+        x.push(xval.clone());
+        y.push(y_vals_dbtime.get(idx).copied().unwrap_or(0.0));
+        text.push(cps.iter()
+                     .map(|cp| format!("{}: {} {:+.1}%", cp.series_type, cp.series_name, cp.change_pct))
+                     .collect::<Vec<String>>()
+                     .join("<br>"));
+    }
+
+    let trace = Scatter::new(x, y)
+        .mode(Mode::Markers)
+        .name("Change Points")
+        .text_array(text)
+        .hover_template("<b>Change point</b><br>%{x}<br>%{text}<extra></extra>")
+        .marker(Marker::new().symbol(MarkerSymbol::TriangleUp).size(12).color(NamedColor::Red))
+        .x_axis("x1")
+        .y_axis("y1");
+    plot.add_trace(trace);
+}
+
+/// Draws a vertical line through the whole main chart at each snapshot with a change point.
+/// Shifts of DB Time or DB CPU are red, shifts of other series are gray.
+pub fn add_change_point_shapes(layout: &mut Layout, change_points: &[ChangePoint]) {
+    for ((_, xval), cps) in change_points_by_xval(change_points) {
+        let color = if cps.iter().any(|cp| cp.series_type == "TM") {
+            NamedColor::Red
+        } else {
+            NamedColor::Gray
+        };
+        layout.add_shape(
+            Shape::new()
+                .shape_type(ShapeType::Line)
+                .x_ref("x")
+                .y_ref("paper")
+                .x0(xval.clone())
+                .x1(xval)
+                .y0(0.0)
+                .y1(1.0)
+                .layer(ShapeLayer::Below)
+                .line(ShapeLine::new().color(color).width(1.0).dash(DashType::Dash))
+        );
+    }
+}
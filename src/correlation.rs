@@ -0,0 +1,190 @@
+use crate::tools::*;
+use crate::reasonings::CorrelationDetails;
+
+/* Rank-based, lagged and partial correlation.
+   Pearson is dominated by a few huge snapshots and blind to effects which show up one interval later.
+   For each pair (metric, DB Time) we compute additionally:
+        - Spearman rho (Pearson on ranks) and Kendall tau-b - robust to outliers and monotonic non-linear relations
+        - cross-correlation at lags -L..+L with the best lag - positive lag means the metric leads DB Time
+        - partial correlation controlling for overall load (Executes/s)
+   Every method is tested against Bonferroni corrected threshold for number of tested metrics. */
+
+/// Kendall tau equivalent of Pearson r = 0.5 under normality: 2/pi * asin(0.5)
+const KENDALL_FLOOR: f64 = 1.0 / 3.0;
+/// Minimal |r| reported, the same floor which was always used for Pearson
+const CORRELATION_FLOOR: f64 = 0.5;
+
+/// Effective thresholds for each method - Bonferroni corrected, but never below the floor
+#[derive(Debug, Clone, Copy)]
+pub struct CorrelationThresholds {
+    pub pearson: f64,
+    pub kendall: f64,
+    pub lagged: f64,
+    pub partial: f64,
+}
+
+impl CorrelationThresholds {
+    pub fn new(num_tests: usize, sample_size: usize, max_lag: usize) -> Self {
+        let alpha = 0.05;
+        let pearson = bonferroni_significance_threshold(num_tests, alpha, sample_size).max(CORRELATION_FLOOR);
+        //Each lag is one more test and shifted series are shorter
+        let lagged = bonferroni_significance_threshold(num_tests * (2 * max_lag + 1), alpha, sample_size.saturating_sub(max_lag))
+                        .max(CORRELATION_FLOOR);
+        //One degree of freedom is lost for control variable
+        let partial = bonferroni_significance_threshold(num_tests, alpha, sample_size.saturating_sub(1)).max(CORRELATION_FLOOR);
+        //Under H0 tau is approximately normal with variance 2(2n+5) / 9n(n-1)
+        let n = sample_size as f64;
+        let kendall = if num_tests == 0 || sample_size < 4 {
+            KENDALL_FLOOR
+        } else {
+            let z = normal_quantile(1.0 - alpha / num_tests as f64 / 2.0);
+            (z * (2.0 * (2.0 * n + 5.0) / (9.0 * n * (n - 1.0))).sqrt()).max(KENDALL_FLOOR)
+        };
+        CorrelationThresholds { pearson, kendall, lagged, partial }
+    }
+}
+
+//Pearson correlation which returns 0.0 instead of NaN for constant series
+fn pearson(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len().min(y.len());
+    if n < 3 {
+        return 0.0;
+    }
+    let mx = x[..n].iter().sum::<f64>() / n as f64;
+    let my = y[..n].iter().sum::<f64>() / n as f64;
+    let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+    for i in 0..n {
+        let dx = x[i] - mx;
+        let dy = y[i] - my;
+        sxy += dx * dy;
+        sxx += dx * dx;
+        syy += dy * dy;
+    }
+    if sxx == 0.0 || syy == 0.0 {
+        return 0.0;
+    }
+    sxy / (sxx * syy).sqrt()
+}
+
+//Average ranks (ties get the mean of their positions)
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut idx: Vec<usize> = (0..values.len()).collect();
+    idx.sort_by(|a, b| values[*a].partial_cmp(&values[*b]).unwrap_or(std::cmp::Ordering::Equal));
+    let mut r = vec![0.0; values.len()];
+    let mut i = 0;
+    while i < idx.len() {
+        let mut j = i;
+        while j + 1 < idx.len() && values[idx[j + 1]] == values[idx[i]] {
+            j += 1;
+        }
+        let avg_rank = (i + j) as f64 / 2.0 + 1.0;
+        for k in i..=j {
+            r[idx[k]] = avg_rank;
+        }
+        i = j + 1;
+    }
+    r
+}
+
+/// Spearman rank correlation
+pub fn spearman_correlation(x: &[f64], y: &[f64]) -> f64 {
+    pearson(&ranks(x), &ranks(y))
+}
+
+/// Kendall tau-b (with correction for ties)
+pub fn kendall_tau_b(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len().min(y.len());
+    let (mut concordant, mut discordant, mut ties_x, mut ties_y): (f64, f64, f64, f64) = (0.0, 0.0, 0.0, 0.0);
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let dx = x[i] - x[j];
+            let dy = y[i] - y[j];
+            if dx == 0.0 && dy == 0.0 {
+                continue;
+            } else if dx == 0.0 {
+                ties_x += 1.0;
+            } else if dy == 0.0 {
+                ties_y += 1.0;
+            } else if dx * dy > 0.0 {
+                concordant += 1.0;
+            } else {
+                discordant += 1.0;
+            }
+        }
+    }
+    let denominator = ((concordant + discordant + ties_x) * (concordant + discordant + ties_y)).sqrt();
+    if denominator == 0.0 {
+        return 0.0;
+    }
+    (concordant - discordant) / denominator
+}
+
+/// Correlation of x[t] with y[t + lag] - positive lag means that x leads y
+pub fn lagged_correlation(x: &[f64], y: &[f64], lag: i64) -> f64 {
+    let n = x.len().min(y.len());
+    let l = lag.unsigned_abs() as usize;
+    if l >= n {
+        return 0.0;
+    }
+    if lag >= 0 {
+        pearson(&x[..n - l], &y[l..n])
+    } else {
+        pearson(&x[l..n], &y[..n - l])
+    }
+}
+
+/// Lag from -max_lag..=max_lag with the highest absolute correlation, lag 0 wins ties
+pub fn best_lag_correlation(x: &[f64], y: &[f64], max_lag: usize) -> (i64, f64) {
+    let mut best = (0, lagged_correlation(x, y, 0));
+    for l in 1..=max_lag as i64 {
+        for lag in [l, -l] {
+            let r = lagged_correlation(x, y, lag);
+            if r.abs() > best.1.abs() {
+                best = (lag, r);
+            }
+        }
+    }
+    best
+}
+
+/// Partial correlation of x and y controlling for z
+pub fn partial_correlation(x: &[f64], y: &[f64], z: &[f64]) -> f64 {
+    let rxy = pearson(x, y);
+    let rxz = pearson(x, z);
+    let ryz = pearson(y, z);
+    let denominator = ((1.0 - rxz * rxz) * (1.0 - ryz * ryz)).sqrt();
+    if denominator < 1e-12 {
+        return 0.0;
+    }
+    (rxy - rxz * ryz) / denominator
+}
+
+/// Computes all additional correlations of `metric` with `target` and marks which of them are significant.
+/// `control` is the load series used for partial correlation - it is skipped if lengths don't match.
+pub fn correlation_details(metric: &[f64], target: &[f64], control: &[f64], max_lag: usize, thresholds: &CorrelationThresholds) -> (f64, CorrelationDetails) {
+    let r = pearson(metric, target);
+    let spearman = spearman_correlation(metric, target);
+    let kendall_tau = kendall_tau_b(metric, target);
+    let (best_lag, best_lag_correlation) = best_lag_correlation(metric, target, max_lag);
+    let partial = if control.len() == target.len() && control.len() == metric.len() {
+        Some(partial_correlation(metric, target, control))
+    } else {
+        None
+    };
+
+    let mut significant_by: Vec<String> = Vec::new();
+    if r.abs() >= thresholds.pearson { significant_by.push("PEARSON".to_string()); }
+    if spearman.abs() >= thresholds.pearson { significant_by.push("SPEARMAN".to_string()); }
+    if kendall_tau.abs() >= thresholds.kendall { significant_by.push("KENDALL".to_string()); }
+    if best_lag != 0 && best_lag_correlation.abs() >= thresholds.lagged { significant_by.push(format!("LAG {:+}", best_lag)); }
+    if partial.is_some_and(|p| p.abs() >= thresholds.partial) { significant_by.push("PARTIAL".to_string()); }
+
+    (r, CorrelationDetails {
+        spearman,
+        kendall_tau,
+        best_lag,
+        best_lag_correlation,
+        partial_correlation_given_executes: partial,
+        significant_by,
+    })
+}
@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::awr::AWR;
+use crate::Args;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, DbTimeComponentChange};
+use colored::*;
+use plotly::{Plot, Scatter, Bar};
+use plotly::common::{HoverInfo, Line, Marker, Mode, DashType};
+use plotly::layout::{Axis, BarMode, HoverMode, Layout};
+use prettytable::{Table, Row, Cell};
+
+/* DB Time decomposition.
+   DB Time of every snapshot is split into additive components:
+        DB Time = DB CPU + sum(foreground wait classes) + Unaccounted
+   DB CPU and DB Time come from Time Model Statistics, wait classes from Foreground Wait Class table.
+   Unaccounted is the rest - time not instrumented by wait events (e.g. waiting for CPU in run queue)
+   or all the wait time when the report has no wait class section (text AWR, Statspack).
+   For every peak from top_spikes_marked components are compared with their median over all snapshots,
+   so the waterfall goes from the median baseline to the DB Time of the peak. */
+
+const UNACCOUNTED: &str = "Unaccounted";
+
+/// Components of DB Time for all snapshots in snap_range - each series in seconds per snapshot
+pub struct DbTimeDecomposition {
+    pub x_vals: Vec<String>,
+    pub snap_ids: Vec<u64>,
+    pub db_time: Vec<f64>,
+    /// DB CPU first, then wait classes by total time descending, Unaccounted last
+    pub component_names: Vec<String>,
+    pub components: BTreeMap<String, Vec<f64>>,
+}
+
+pub fn compute_db_time_decomposition(awrs: &[AWR], snap_range: &(u64,u64)) -> DbTimeDecomposition {
+    let awrs_in_range: Vec<&AWR> = snaps_in_range(awrs, snap_range)
+        .collect();
+    let n = awrs_in_range.len();
+
+    let mut db_time: Vec<f64> = vec![0.0; n];
+    let mut components: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    components.insert("DB CPU".to_string(), vec![0.0; n]);
+
+    for (i, awr) in awrs_in_range.iter().enumerate() {
+        for tm in &awr.time_model_stats {
+            if tm.stat_name.starts_with("DB time") {
+                db_time[i] = tm.time_s;
+            } else if tm.stat_name.starts_with("DB CPU") {
+                components.get_mut("DB CPU").unwrap()[i] = tm.time_s;
+            }
+        }
+        for wc in &awr.wait_classes {
+            if wc.wait_class == "DB CPU" || wc.wait_class == "Idle" {
+                continue;
+            }
+            components.entry(wc.wait_class.clone()).or_insert(vec![0.0; n])[i] += wc.total_wait_time_s;
+        }
+    }
+
+    let mut wait_classes: Vec<(String, f64)> = components.iter()
+        .filter(|(name, _)| name.as_str() != "DB CPU")
+        .map(|(name, v)| (name.clone(), v.iter().sum::<f64>()))
+        .collect();
+    wait_classes.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    let unaccounted: Vec<f64> = (0..n)
+        .map(|i| db_time[i] - components.values().map(|v| v[i]).sum::<f64>())
+        .collect();
+    components.insert(UNACCOUNTED.to_string(), unaccounted);
+
+    let mut component_names: Vec<String> = vec!["DB CPU".to_string()];
+    component_names.extend(wait_classes.into_iter().map(|(name, _)| name));
+    component_names.push(UNACCOUNTED.to_string());
+
+    DbTimeDecomposition {
+        x_vals: awrs_in_range.iter().map(|awr| format!("{} ({})", awr.snap_info.begin_snap_time, awr.snap_info.begin_snap_id)).collect(),
+        snap_ids: awrs_in_range.iter().map(|awr| awr.snap_info.begin_snap_id).collect(),
+        db_time,
+        component_names,
+        components,
+    }
+}
+
+/// What changed in the snapshot versus median of each component - sorted by delta descending.
+/// Deltas sum up to DB Time of the snapshot minus the sum of component medians (the baseline).
+pub fn changes_vs_median(decomposition: &DbTimeDecomposition, idx: usize) -> Vec<DbTimeComponentChange> {
+    let deltas: Vec<(String, f64, f64)> = decomposition.component_names.iter()
+        .map(|name| {
+            let series = &decomposition.components[name];
+            (name.clone(), series[idx], median(series))
+        })
+        .collect();
+    let total_change: f64 = deltas.iter().map(|(_, peak, med)| peak - med).sum();
+
+    let mut changes: Vec<DbTimeComponentChange> = deltas.into_iter()
+        .map(|(component, peak_value_s, median_value_s)| {
+            let delta_s = peak_value_s - median_value_s;
+            DbTimeComponentChange {
+                component,
+                peak_value_s,
+                median_value_s,
+                delta_s,
+                share_of_change: if total_change.abs() > 1e-9 { delta_s / total_change } else { 0.0 },
+            }
+        })
+        .collect();
+    changes.sort_by(|a, b| b.delta_s.partial_cmp(&a.delta_s).unwrap_or(std::cmp::Ordering::Equal));
+    changes
+}
+
+/// Waterfall emulated with stacked bars: transparent base + increase/decrease bar on top of it
+fn waterfall_plot(title: &str, baseline: f64, changes: &[DbTimeComponentChange], div_id: &str) -> String {
+    let mut x: Vec<String> = vec!["Median baseline".to_string()];
+    let mut base: Vec<f64> = vec![0.0];
+    let mut total: Vec<f64> = vec![baseline];
+    let mut increase: Vec<f64> = vec![0.0];
+    let mut decrease: Vec<f64> = vec![0.0];
+
+    let mut running = baseline;
+    for c in changes {
+        x.push(c.component.clone());
+        total.push(0.0);
+        if c.delta_s >= 0.0 {
+            base.push(running);
+            increase.push(c.delta_s);
+            decrease.push(0.0);
+        } else {
+            base.push(running + c.delta_s);
+            increase.push(0.0);
+            decrease.push(-c.delta_s);
+        }
+        running += c.delta_s;
+    }
+    x.push("Peak DB Time".to_string());
+    base.push(0.0);
+    total.push(running);
+    increase.push(0.0);
+    decrease.push(0.0);
+
+    let mut plot = Plot::new();
+    plot.add_trace(Bar::new(x.clone(), base)
+        .name("")
+        .marker(Marker::new().opacity(0.0))
+        .hover_info(HoverInfo::Skip)
+        .show_legend(false));
+    plot.add_trace(Bar::new(x.clone(), total).name("DB Time (s)").marker(Marker::new().color("#7f7f7f")));
+    plot.add_trace(Bar::new(x.clone(), increase).name("Increase vs median (s)").marker(Marker::new().color("#d62728")));
+    plot.add_trace(Bar::new(x, decrease).name("Decrease vs median (s)").marker(Marker::new().color("#2ca02c")));
+    plot.set_layout(Layout::new()
+        .title(title)
+        .height(450)
+        .bar_mode(BarMode::Stack)
+        .y_axis(Axis::new().title("Seconds")));
+    plot.to_inline_html(Some(div_id))
+}
+
+/// Builds stats/db_time_decomposition.html (stacked area of components + waterfall for every top peak)
+/// and attaches "what changed vs median" attribution to top_spikes_marked.
+pub fn report_db_time_decomposition(awrs: &[AWR], snap_range: &(u64,u64), args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) {
+    let decomposition = compute_db_time_decomposition(awrs, snap_range);
+
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{}\n", "DB Time decomposition - what changed in top peaks vs median".yellow());
+
+    if decomposition.db_time.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo snapshots to decompose\n".green().italic());
+        return;
+    }
+    if decomposition.component_names.len() <= 2 {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo wait class data in the reports - waits are shown as Unaccounted\n".green().italic());
+    }
+
+    // Stacked area of components with DB Time as a control line
+    let mut plot = Plot::new();
+    for name in &decomposition.component_names {
+        plot.add_trace(Scatter::new(decomposition.x_vals.clone(), decomposition.components[name].clone())
+            .mode(Mode::Lines)
+            .name(name)
+            .stack_group("dbtime"));
+    }
+    plot.add_trace(Scatter::new(decomposition.x_vals.clone(), decomposition.db_time.clone())
+        .mode(Mode::Lines)
+        .name("DB Time")
+        .line(Line::new().color("#000000").dash(DashType::Dot)));
+    plot.set_layout(Layout::new()
+        .title("DB Time decomposition (s)")
+        .height(700)
+        .hover_mode(HoverMode::X)
+        .y_axis(Axis::new().title("Seconds")));
+
+    let fname = format!("{}/stats/db_time_decomposition.html", html_dir);
+    plot.write_html(Path::new(&fname));
+
+    // Waterfall and table for every peak
+    let mut peaks_html = String::new();
+    let mut peaks_txt = String::new();
+    for peak in report_for_ai.top_spikes_marked.iter_mut() {
+        let Some(idx) = decomposition.snap_ids.iter().position(|s| *s == peak.snap_id) else {
+            continue;
+        };
+        let changes = changes_vs_median(&decomposition, idx);
+        let baseline: f64 = changes.iter().map(|c| c.median_value_s).sum();
+
+        let mut table = Table::new();
+        table.set_titles(Row::new(vec![
+            Cell::new("Component"),
+            Cell::new("Peak (s)"),
+            Cell::new("Median (s)"),
+            Cell::new("Delta (s)"),
+            Cell::new("Share of change"),
+        ]));
+        for c in &changes {
+            table.add_row(Row::new(vec![
+                Cell::new(&c.component),
+                Cell::new(&format!("{:.2}", c.peak_value_s)),
+                Cell::new(&format!("{:.2}", c.median_value_s)),
+                Cell::new(&format!("{:+.2}", c.delta_s)),
+                Cell::new(&format!("{:.1}%", c.share_of_change * 100.0)),
+            ]));
+        }
+        peaks_txt.push_str(&format!("\t{} ({}) - DB Time {:.2}s, median baseline {:.2}s\n", peak.report_date, peak.snap_id, decomposition.db_time[idx], baseline));
+        peaks_txt.push_str(&indented_table(&table));
+
+        let title = format!("{} ({}) vs median", peak.report_date, peak.snap_id);
+        peaks_html.push_str(&waterfall_plot(&title, baseline, &changes, &format!("waterfall-{}", peak.snap_id)));
+        peaks_html.push_str(&table_to_html_string(&table, &title, &["Component", "Peak (s)", "Median (s)", "Delta (s)", "Share of change"]));
+
+        peak.db_time_vs_median = changes;
+    }
+    make_notes!(logfile_name, args.quiet, 0, "{}", peaks_txt);
+
+    // Waterfalls are injected below the stacked area - plotly.js is already loaded by write_html
+    if let Err(e) = append_html_before_body_end(&fname, &format!("<h2>Top peaks - what changed vs median</h2>\n{}", peaks_html)) {
+        eprintln!("Error writing file {}: {}", fname, e);
+    }
+}
@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use chrono::{Duration, NaiveDateTime};
+use rayon::prelude::*;
+use crate::analyze::{TrackedStat, TrackedStatKey, raw_values_of};
+use crate::awr::AWR;
+use crate::Args;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, CapacityForecast};
+use colored::*;
+use plotly::{Plot, Scatter};
+use plotly::common::{DashType, Fill, Line, Mode};
+use plotly::layout::{Axis, HoverMode, Layout, RangeMode};
+use prettytable::{Table, Row, Cell};
+
+/* Workload capacity forecasting.
+   Additive Holt-Winters with trend and up to two seasonal components (Taylor's double seasonal method):
+        level    l_t = a(y_t - s1_{t-m1} - s2_{t-m2}) + (1-a)(l_{t-1} + b_{t-1})
+        trend    b_t = b(l_t - l_{t-1}) + (1-b)b_{t-1}
+        seasonal s1_t = g(y_t - l_t - s2_{t-m2}) + (1-g)s1_{t-m1}     (s2 alike)
+        forecast y_{t+h} = l_t + h*b_t + s1_{t+h-m1} + s2_{t+h-m2}
+   m1 is one day and m2 one week of snapshots - a season is used only when the history covers at least two of them.
+   Smoothing parameters are chosen by grid search minimizing one-step-ahead squared error,
+   prediction intervals follow the ETS(A,A,A) variance: sigma_h^2 = sigma^2 * (1 + sum_{j<h} c_j^2),
+   c_j = a(1 + j*b) + g * (number of seasons with j mod m == 0). */
+
+const ALPHA_GRID: [f64; 6] = [0.05, 0.1, 0.2, 0.3, 0.5, 0.8];
+const BETA_GRID: [f64; 5] = [0.0, 0.01, 0.05, 0.1, 0.2];
+const GAMMA_GRID: [f64; 4] = [0.05, 0.1, 0.2, 0.4];
+/// z-value of two-sided 95% prediction interval
+const Z_95: f64 = 1.96;
+/// Minimal number of snapshots to fit a forecast at all
+const FORECAST_MIN_SNAPS: usize = 12;
+
+/// Series which can be forecasted and their keys for --forecast-thresholds
+const FORECAST_SERIES: [(&str, &str, &str); 5] = [
+    ("DB_TIME",       "DB Time",             "s/snap"),
+    ("AAS",           "Average Active Sessions", "sessions"),
+    ("PHYS_READ_MB",  "Physical Read MB/s",  "MB/s"),
+    ("PHYS_WRITE_MB", "Physical Write MB/s", "MB/s"),
+    ("REDO_MB",       "Redo MB/s",           "MB/s"),
+];
+
+/// Forecast mean with lower and upper bound of the 95% prediction interval
+type ForecastPath = (Vec<f64>, Vec<f64>, Vec<f64>);
+
+struct HoltWinters {
+    alpha: f64,
+    beta: f64,
+    gamma: f64,
+    seasons: Vec<usize>,
+    level: f64,
+    trend: f64,
+    seasonal: Vec<Vec<f64>>,
+    n: usize,
+    sigma: f64,
+    sse: f64,
+}
+
+impl HoltWinters {
+    fn fit(y: &[f64], seasons: &[usize], alpha: f64, beta: f64, gamma: f64) -> Self {
+        let n = y.len();
+        // Initial level and trend from first (and second) longest season, or from first quarter without seasons
+        let init_len = seasons.iter().copied().max().unwrap_or((n / 4).max(1)).min(n);
+        let first_mean = y[..init_len].iter().sum::<f64>() / init_len as f64;
+        let mut trend = if n >= 2 * init_len {
+            let second_mean = y[init_len..2 * init_len].iter().sum::<f64>() / init_len as f64;
+            (second_mean - first_mean) / init_len as f64
+        } else {
+            0.0
+        };
+        if !trend.is_finite() { trend = 0.0; }
+        let mut level = first_mean;
+
+        // Seasonal indices - shorter seasons first, each one on what is left after the previous ones
+        let mut seasonal: Vec<Vec<f64>> = Vec::new();
+        for &m in seasons {
+            let mut sums = vec![0.0; m];
+            let mut counts = vec![0usize; m];
+            for t in 0..init_len {
+                let other: f64 = seasons.iter().zip(seasonal.iter()).map(|(mk, s)| s[t % mk]).sum();
+                sums[t % m] += y[t] - first_mean - other;
+                counts[t % m] += 1;
+            }
+            seasonal.push(sums.iter().zip(counts.iter()).map(|(s, c)| if *c > 0 { s / *c as f64 } else { 0.0 }).collect());
+        }
+
+        let warm_up = init_len.min(n / 2);
+        let mut sse = 0.0;
+        let mut count = 0usize;
+        for t in 0..n {
+            let s_sum: f64 = seasons.iter().zip(seasonal.iter()).map(|(m, s)| s[t % m]).sum();
+            let err = y[t] - (level + trend + s_sum);
+            if t >= warm_up {
+                sse += err * err;
+                count += 1;
+            }
+            let new_level = alpha * (y[t] - s_sum) + (1.0 - alpha) * (level + trend);
+            trend = beta * (new_level - level) + (1.0 - beta) * trend;
+            for (m, s) in seasons.iter().zip(seasonal.iter_mut()) {
+                let other = s_sum - s[t % m];
+                s[t % m] = gamma * (y[t] - new_level - other) + (1.0 - gamma) * s[t % m];
+            }
+            level = new_level;
+        }
+
+        HoltWinters {
+            alpha, beta, gamma,
+            seasons: seasons.to_vec(),
+            level, trend, seasonal, n,
+            sigma: if count > 0 { (sse / count as f64).sqrt() } else { 0.0 },
+            sse,
+        }
+    }
+
+    /// Grid search of smoothing parameters by one-step-ahead SSE
+    fn fit_best(y: &[f64], seasons: &[usize]) -> Self {
+        let gammas: &[f64] = if seasons.is_empty() { &[0.0] } else { &GAMMA_GRID };
+        let mut best: Option<HoltWinters> = None;
+        for &a in &ALPHA_GRID {
+            for &b in &BETA_GRID {
+                for &g in gammas {
+                    let fit = HoltWinters::fit(y, seasons, a, b, g);
+                    if fit.sse.is_finite() && best.as_ref().is_none_or(|bf| fit.sse < bf.sse) {
+                        best = Some(fit);
+                    }
+                }
+            }
+        }
+        best.unwrap_or_else(|| HoltWinters::fit(y, seasons, 0.2, 0.0, 0.0))
+    }
+
+    /// Point forecast and 95% prediction interval for h = 1..=horizon (lower bound clipped at 0)
+    fn forecast(&self, horizon: usize) -> ForecastPath {
+        let mut mean = Vec::with_capacity(horizon);
+        let mut lower = Vec::with_capacity(horizon);
+        let mut upper = Vec::with_capacity(horizon);
+        let mut var_sum = 0.0;
+        for h in 1..=horizon {
+            let t = self.n - 1 + h;
+            let s_sum: f64 = self.seasons.iter().zip(self.seasonal.iter()).map(|(m, s)| s[t % m]).sum();
+            let yhat = self.level + h as f64 * self.trend + s_sum;
+            if h > 1 {
+                let j = h - 1;
+                let seasonal_hits = self.seasons.iter().filter(|m| j % **m == 0).count() as f64;
+                let c = self.alpha * (1.0 + j as f64 * self.beta) + self.gamma * seasonal_hits;
+                var_sum += c * c;
+            }
+            let half_width = Z_95 * self.sigma * (1.0 + var_sum).sqrt();
+            mean.push(yhat);
+            lower.push((yhat - half_width).max(0.0));
+            upper.push(yhat + half_width);
+        }
+        (mean, lower, upper)
+    }
+}
+
+/// Parses --forecast-thresholds "AAS=16,REDO_MB=50" into key -> threshold
+fn parse_thresholds(spec: &str) -> HashMap<String, f64> {
+    let mut thresholds = HashMap::new();
+    for item in spec.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let Some((key, value)) = item.split_once('=') else {
+            eprintln!("Wrong forecast threshold '{}' - expected KEY=VALUE", item);
+            continue;
+        };
+        let key = key.trim().to_uppercase();
+        match (FORECAST_SERIES.iter().any(|(k, _, _)| *k == key), value.trim().parse::<f64>()) {
+            (true, Ok(v)) => { thresholds.insert(key, v); }
+            (false, _) => eprintln!("Unknown forecast threshold key '{}' - use one of DB_TIME, AAS, PHYS_READ_MB, PHYS_WRITE_MB, REDO_MB", key),
+            (_, Err(e)) => eprintln!("Wrong forecast threshold value '{}': {}", value, e),
+        }
+    }
+    thresholds
+}
+
+fn format_time(t: &NaiveDateTime) -> String {
+    t.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Fits Holt-Winters to DB Time, AAS, physical read/write MB/s and redo MB/s, projects them --forecast-horizon days ahead,
+/// writes stats/capacity_forecast.html and attaches threshold crossing dates to ReportForAI.
+pub fn report_capacity_forecast(awrs: &[AWR], snap_range: &(u64,u64), tracked_stats: &HashMap<TrackedStatKey, TrackedStat>, args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) {
+    if args.forecast_horizon == 0 {
+        return;
+    }
+    let snaps: Vec<&AWR> = snaps_in_range(awrs, snap_range)
+        .collect();
+
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{} {} {}\n", "Capacity forecast - Holt-Winters, horizon:".yellow(), args.forecast_horizon, "days".yellow());
+
+    let times: Option<Vec<NaiveDateTime>> = snaps.iter().map(|awr| parse_snap_time(&awr.snap_info.begin_snap_time)).collect();
+    let Some(times) = times else {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tSnapshot times can't be parsed - forecast skipped\n".green().italic());
+        return;
+    };
+    if times.len() < FORECAST_MIN_SNAPS {
+        make_notes!(logfile_name, args.quiet, 0, "{}", format!("\tAt least {} snapshots are needed for a forecast\n", FORECAST_MIN_SNAPS).green().italic());
+        return;
+    }
+
+    // Snapshot interval - median of differences between consecutive snapshots
+    let diffs: Vec<f64> = times.windows(2).map(|w| (w[1] - w[0]).num_seconds() as f64).filter(|d| *d > 0.0).collect();
+    let interval_s = percentile(&diffs, 50.0);
+    if !interval_s.is_finite() || interval_s <= 0.0 {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tSnapshot interval can't be determined - forecast skipped\n".green().italic());
+        return;
+    }
+    let snaps_per_day = (86400.0 / interval_s).round() as usize;
+    let horizon = ((args.forecast_horizon as f64 * 86400.0 / interval_s).round() as usize).max(1);
+    let mut seasons: Vec<usize> = Vec::new();
+    for m in [snaps_per_day, 7 * snaps_per_day] {
+        if m >= 2 && times.len() >= 2 * m {
+            seasons.push(m);
+        }
+    }
+    let seasonality = match seasons.len() {
+        0 => "none",
+        1 => "daily",
+        _ => "daily + weekly",
+    };
+
+    // Input series - DB Time and AAS from Time Model / Load Profile, the rest from the tracked stats registry
+    let db_time: Vec<f64> = snaps.iter().map(|awr| db_time_s(awr)).collect();
+    let aas: Vec<f64> = snaps.iter().map(|awr| db_time_per_s(awr)).collect();
+    let mut inputs: Vec<(&str, &str, &str, Vec<f64>)> = Vec::new();
+    let mut skipped = String::new();
+    for (key, name, unit) in FORECAST_SERIES {
+        let values = match key {
+            "DB_TIME" => db_time.clone(),
+            "AAS" => aas.clone(),
+            "PHYS_READ_MB" => raw_values_of(tracked_stats, TrackedStatKey::PhysReadMbPerSec),
+            "PHYS_WRITE_MB" => raw_values_of(tracked_stats, TrackedStatKey::PhysWriteMbPerSec),
+            _ => raw_values_of(tracked_stats, TrackedStatKey::RedoMbPerSec),
+        };
+        if values.len() != times.len() {
+            skipped.push_str(&format!("\t{} has {} values for {} snapshots - skipped\n", name, values.len(), times.len()));
+            continue;
+        }
+        inputs.push((key, name, unit, values));
+    }
+    if !skipped.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", skipped);
+    }
+
+    // AAS threshold defaults to number of CPUs from CPU capacity analysis
+    let mut thresholds = parse_thresholds(&args.forecast_thresholds);
+    if !thresholds.contains_key("AAS") {
+        if let Some(cpus) = report_for_ai.cpu_capacity.as_ref().map(|c| c.host_cpus).filter(|c| *c > 0) {
+            thresholds.insert("AAS".to_string(), cpus as f64);
+        }
+    }
+
+    let last_time = *times.last().unwrap();
+    let future_times: Vec<NaiveDateTime> = (1..=horizon)
+        .map(|h| last_time + Duration::seconds((h as f64 * interval_s).round() as i64))
+        .collect();
+    let fits: Vec<(HoltWinters, ForecastPath)> = inputs.par_iter()
+        .map(|(_, _, _, values)| {
+            let fit = HoltWinters::fit_best(values, &seasons);
+            let fc = fit.forecast(horizon);
+            (fit, fc)
+        })
+        .collect();
+
+    let history_x: Vec<String> = times.iter().map(format_time).collect();
+    let future_x: Vec<String> = future_times.iter().map(format_time).collect();
+    let mut forecasts: Vec<CapacityForecast> = Vec::new();
+    let mut plots_html = String::new();
+    let fname = format!("{}/stats/capacity_forecast.html", html_dir);
+
+    for ((key, name, unit, values), (fit, (mean, lower, upper))) in inputs.iter().zip(fits.iter()) {
+        let threshold = thresholds.get(*key).copied();
+        let last_day = &values[values.len().saturating_sub(snaps_per_day.max(1))..];
+        let last_day_mean = last_day.iter().sum::<f64>() / last_day.len() as f64;
+        let crossing = |series: &Vec<f64>| threshold.and_then(|th| series.iter().position(|v| *v >= th)).map(|h| format_time(&future_times[h]));
+        let crossing_date = crossing(mean);
+        let crossing_date_upper95 = crossing(upper);
+        let status = match threshold {
+            None => "NO_THRESHOLD",
+            Some(th) if last_day.iter().any(|v| *v >= th) => "EXCEEDED",
+            Some(_) if crossing_date.is_some() => "CROSSES",
+            Some(_) if crossing_date_upper95.is_some() => "MAY_CROSS",
+            Some(_) => "BELOW",
+        };
+
+        forecasts.push(CapacityForecast {
+            series: name.to_string(),
+            unit: unit.to_string(),
+            seasonality: seasonality.to_string(),
+            alpha: fit.alpha,
+            beta: fit.beta,
+            gamma: fit.gamma,
+            residual_stddev: fit.sigma,
+            last_day_mean,
+            trend_per_day: fit.trend * snaps_per_day as f64,
+            horizon_days: args.forecast_horizon,
+            horizon_end_mean: *mean.last().unwrap(),
+            horizon_end_lower95: *lower.last().unwrap(),
+            horizon_end_upper95: *upper.last().unwrap(),
+            threshold,
+            status: status.to_string(),
+            crossing_date,
+            crossing_date_upper95,
+        });
+
+        let mut plot = Plot::new();
+        plot.add_trace(Scatter::new(history_x.clone(), values.clone()).mode(Mode::Lines).name("History").line(Line::new().color("#632e4f")));
+        plot.add_trace(Scatter::new(future_x.clone(), upper.clone()).mode(Mode::Lines).name("Upper 95%").line(Line::new().color("rgba(255,127,14,0.3)")));
+        plot.add_trace(Scatter::new(future_x.clone(), lower.clone()).mode(Mode::Lines).name("Lower 95%")
+            .line(Line::new().color("rgba(255,127,14,0.3)"))
+            .fill(Fill::ToNextY)
+            .fill_color("rgba(255,127,14,0.2)"));
+        plot.add_trace(Scatter::new(future_x.clone(), mean.clone()).mode(Mode::Lines).name("Forecast").line(Line::new().color("#ff7f0e")));
+        if let Some(th) = threshold {
+            plot.add_trace(Scatter::new(vec![history_x[0].clone(), future_x[future_x.len() - 1].clone()], vec![th, th])
+                .mode(Mode::Lines)
+                .name(format!("Threshold {}", th))
+                .line(Line::new().color("#d62728").dash(DashType::Dash)));
+        }
+        plot.set_layout(Layout::new()
+            .title(format!("{} - forecast {} days ({} seasonality)", name, args.forecast_horizon, seasonality))
+            .height(500)
+            .hover_mode(HoverMode::X)
+            .y_axis(Axis::new().title(*unit).range_mode(RangeMode::ToZero)));
+
+        if forecasts.len() == 1 {
+            plot.write_html(Path::new(&fname));
+        } else {
+            plots_html.push_str(&plot.to_inline_html(Some(&format!("forecast-{}", key.to_lowercase()))));
+        }
+    }
+
+    let headers = ["Series", "Seasonality", "Last day mean", "Trend/day", "Horizon end (95% PI)", "Threshold", "Status", "Crossing", "Crossing (upper 95%)"];
+    let mut table = Table::new();
+    table.set_titles(Row::new(headers.iter().map(|h| Cell::new(h)).collect()));
+    for f in &forecasts {
+        table.add_row(Row::new(vec![
+            Cell::new(&f.series),
+            Cell::new(&f.seasonality),
+            Cell::new(&format!("{:.2}", f.last_day_mean)),
+            Cell::new(&format!("{:+.3}", f.trend_per_day)),
+            Cell::new(&format!("{:.2} ({:.2} - {:.2})", f.horizon_end_mean, f.horizon_end_lower95, f.horizon_end_upper95)),
+            Cell::new(&f.threshold.map_or("-".to_string(), |t| format!("{}", t))),
+            Cell::new(&f.status),
+            Cell::new(f.crossing_date.as_deref().unwrap_or("-")),
+            Cell::new(f.crossing_date_upper95.as_deref().unwrap_or("-")),
+        ]));
+    }
+    make_notes!(logfile_name, args.quiet, 0, "\tSnapshot interval: {:.0}s, snapshots: {}, seasonality: {}\n", interval_s, times.len(), seasonality);
+    make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&table));
+
+    if !forecasts.is_empty() {
+        let table_html = table_to_html_string(&table, "Capacity forecast - threshold crossing dates", &headers);
+        if let Err(e) = append_html_before_body_end(&fname, &format!("{}\n{}", plots_html, table_html)) {
+            eprintln!("Error writing file {}: {}", fname, e);
+        }
+    }
+
+    report_for_ai.capacity_forecast = forecasts;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holt_winters_follows_linear_trend() {
+        let y: Vec<f64> = (0..48).map(|t| 10.0 + 2.0 * t as f64).collect();
+        let (mean, _, _) = HoltWinters::fit_best(&y, &[]).forecast(5);
+        for (h, m) in mean.iter().enumerate() {
+            let expected = 10.0 + 2.0 * (48 + h) as f64;
+            assert!((m - expected).abs() < 0.5, "h={} forecast={} expected={}", h + 1, m, expected);
+        }
+    }
+
+    #[test]
+    fn holt_winters_repeats_season() {
+        let season = |t: usize| 50.0 + 10.0 * (2.0 * std::f64::consts::PI * (t % 24) as f64 / 24.0).sin();
+        let y: Vec<f64> = (0..24 * 6).map(season).collect();
+        let (mean, _, _) = HoltWinters::fit_best(&y, &[24]).forecast(24);
+        for (h, m) in mean.iter().enumerate() {
+            let expected = season(y.len() + h);
+            assert!((m - expected).abs() < 1.5, "h={} forecast={} expected={}", h + 1, m, expected);
+        }
+    }
+
+    #[test]
+    fn holt_winters_interval_widens() {
+        let y: Vec<f64> = (0..60).map(|t| 20.0 + ((t * 7) % 5) as f64).collect();
+        let (mean, lower, upper) = HoltWinters::fit_best(&y, &[]).forecast(10);
+        for h in 0..10 {
+            assert!(lower[h] >= 0.0 && lower[h] <= mean[h] && mean[h] <= upper[h]);
+        }
+        for h in 1..10 {
+            assert!(upper[h] - mean[h] >= upper[h - 1] - mean[h - 1] - 1e-9);
+        }
+    }
+
+    #[test]
+    fn parse_thresholds_skips_unknown_keys_and_values() {
+        let thresholds = parse_thresholds("aas=16, REDO_MB = 50, FOO=1, DB_TIME=x, broken");
+        assert_eq!(thresholds.len(), 2);
+        assert_eq!(thresholds.get("AAS"), Some(&16.0));
+        assert_eq!(thresholds.get("REDO_MB"), Some(&50.0));
+    }
+}
@@ -26,7 +26,10 @@ use crate::reasonings::{StatisticsDescription,
                         CrossModelClassification,
                         DbTimeGradientSection,
                         VifDiagnostic,
-                        CollinearGroupImpact};
+                        CollinearGroupImpact,
+                        OlsFitSummary,
+                        RollingGradientWindow,
+                        WorkloadRegime};
 
 use prettytable::{Table, Row, Cell, format, Attr};
 use colored::*;
@@ -91,6 +94,13 @@ pub struct DbTimeGradientResult {
     //Quantile 95 impact ranking
     pub quantile95_ranking: Vec<EventImpact>,
 
+    /// OLS coefficients on the VIF-pruned predictor set with Newey-West inference
+    pub ols_gradient_by_event: EventScalarMap,
+    pub ols_ranking: Vec<EventImpact>,
+    pub ols_inference_by_event: BTreeMap<String, OlsInference>,
+    /// None when OLS could not be fitted (too few snapshots or singular X'X)
+    pub ols_fit: Option<OlsFitSummary>,
+
     /// Δ(wait event/statistic value/sqlid exec time) standardization stats
     pub event_delta_mean_by_event: EventScalarMap,
     pub event_delta_std_by_event: EventScalarMap,
@@ -98,11 +108,109 @@ pub struct DbTimeGradientResult {
     /// MAD(raw Δ(wait event/statistic value/sqlid exec time))
     pub event_delta_mad_by_event: EventScalarMap,
 
+    /// Regularization used by the models
+    pub regularization: RegularizationChoice,
+
+    /// Block bootstrap intervals for each model (empty when bootstrap is disabled)
+    pub ridge_bootstrap: BootstrapByEvent,
+    pub elastic_net_bootstrap: BootstrapByEvent,
+    pub huber_bootstrap: BootstrapByEvent,
+    pub quantile95_bootstrap: BootstrapByEvent,
+
     /// Variance Inflation Factor for each predictor
     pub vif_by_event: EventScalarMap,
     /// Grouped impacts for collinear predictor clusters
     /// Each entry: (group_member_names, group_impact, group_coef)
     pub collinear_groups: Vec<(Vec<String>, f64, f64)>,
+
+    /// Lagged and interaction features kept in the model / dropped by VIF pruning
+    pub expanded_features: Vec<String>,
+    pub pruned_features: Vec<String>,
+}
+
+/// Regularization actually used by the models - manual values or the ones selected by tuning
+#[derive(Debug, Clone)]
+pub struct RegularizationChoice {
+    /// none, cv or gcv (with a note when tuning was not possible)
+    pub method: String,
+    pub ridge_lambda: f64,
+    pub elastic_net_lambda: f64,
+    pub elastic_net_alpha: f64,
+    /// Validation MSE (cv) or GCV score of the selected value
+    pub ridge_validation_error: Option<f64>,
+    pub elastic_net_validation_error: Option<f64>,
+}
+
+/// Regularization and solver settings of the models - manual values from command line or the ones selected by tuning
+#[derive(Debug, Clone, Copy)]
+pub struct ModelSettings {
+    pub ridge_lambda: f64,
+    pub elastic_net_lambda: f64,
+    pub elastic_net_alpha: f64,
+    pub elastic_net_max_iter: usize,
+    pub elastic_net_tol: f64,
+}
+
+/// Block bootstrap settings - resamples of block_size consecutive Δ-snapshots (0 - n^(1/3)), models refitted on each of them.
+/// Resample b draws its blocks from SplitMix64 seeded with seed + b + 1, so results are reproducible.
+#[derive(Debug, Clone, Copy)]
+struct BootstrapSettings {
+    resamples: usize,
+    block_size: usize,
+    seed: u64,
+    models: ModelSettings,
+}
+
+/// Block bootstrap summary of one predictor in one model
+#[derive(Debug, Clone)]
+pub struct CoefBootstrap {
+    /// 95% percentile interval of the coefficient
+    pub coef_ci: (f64, f64),
+    /// 95% percentile interval of active impact (|coef| * P90(|Δx|))
+    pub impact_active_ci: (f64, f64),
+    /// Fraction of resamples with non-zero coefficient - stability selection for Elastic Net
+    pub selection_frequency: f64,
+}
+
+/// Statistical inference for one OLS coefficient
+#[derive(Debug, Clone, Default)]
+pub struct OlsInference {
+    /// Newey-West (HAC) standard error
+    pub std_error: f64,
+    pub t_stat: f64,
+    /// Two-sided p-value from Student's t with n - p - 1 degrees of freedom
+    pub p_value: f64,
+}
+
+/// Named bootstrap summaries: event_name/stat_name/sqlid -> CoefBootstrap
+pub type BootstrapByEvent = BTreeMap<String, CoefBootstrap>;
+
+/// Optional predictors added to the same-snapshot deltas of each event/statistic/sqlid
+#[derive(Debug, Clone, Default)]
+pub struct FeatureExpansion {
+    /// Lagged deltas Δx[t-1]..Δx[t-lags] of the top predictors (0 - disabled)
+    pub lags: usize,
+    /// Pairwise products of standardized deltas of the top predictors
+    pub interactions: bool,
+    /// Number of predictors (by |corr(Δx, ΔDB Time)|) that get lags and interactions
+    pub top_k: usize,
+    /// Expanded features are dropped (highest VIF first) until all of them have VIF below this value
+    pub vif_max: f64,
+}
+
+impl FeatureExpansion {
+    pub fn from_args(args: &Args) -> Self {
+        FeatureExpansion {
+            lags: args.gradient_lags,
+            interactions: args.gradient_interactions,
+            top_k: args.gradient_expand_top,
+            vif_max: args.gradient_vif_max,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        (self.lags > 0 || self.interactions) && self.top_k > 0
+    }
 }
 
 //This will be usable for task collection to make parallel threads with Rayon
@@ -131,6 +239,10 @@ pub fn compute_db_time_gradient(
     elastic_net_alpha: f64,
     elastic_net_max_iter: usize,
     elastic_net_tol: f64,
+    bootstrap_resamples: usize,
+    bootstrap_block_size: usize,
+    tuning: &str,
+    expansion: &FeatureExpansion,
 ) -> Result<DbTimeGradientResult, String> {
     if db_time_series.len() < 3 {
         return Err("DB Time series must have at least 3 samples.".into());
@@ -162,6 +274,12 @@ pub fn compute_db_time_gradient(
     let y_mean = db_time_delta_raw.iter().sum::<f64>() / db_time_delta_raw.len() as f64;
     let db_time_delta: Vec<f64> = db_time_delta_raw.iter().map(|&y| y - y_mean).collect();
     let event_delta_by_event = compute_event_deltas(event_series)?;
+    let (event_delta_by_event, expanded_features, pruned_features) = if expansion.is_enabled() {
+        println!("  -> Adding lagged/interaction features (top {}, lags {}, interactions {})", expansion.top_k, expansion.lags, expansion.interactions);
+        expand_features(event_delta_by_event, &db_time_delta, expansion)
+    } else {
+        (event_delta_by_event, Vec::new(), Vec::new())
+    };
     let event_delta_mean_by_event = compute_mean_by_event(&event_delta_by_event);
     let event_delta_std_by_event = compute_std_by_event(&event_delta_by_event, &event_delta_mean_by_event);
     let event_delta_standardized_by_event =
@@ -171,6 +289,13 @@ pub fn compute_db_time_gradient(
     let event_delta_p99_by_event = compute_abs_percentile_by_event(&event_delta_by_event, 0.99);
 
  
+    // Manual regularization can be replaced by values selected with cross-validation or GCV
+    let manual = ModelSettings { ridge_lambda, elastic_net_lambda, elastic_net_alpha, elastic_net_max_iter, elastic_net_tol };
+    let regularization = tune_regularization(&event_delta_standardized_by_event, &db_time_delta, tuning, &manual);
+    let ridge_lambda = regularization.ridge_lambda;
+    let elastic_net_lambda = regularization.elastic_net_lambda;
+    let elastic_net_alpha = regularization.elastic_net_alpha;
+
     let tasks: Vec<u8> = vec![0, 1, 2, 3]; //4 tasks - 4 models
 
     // Compute Huber delta from median residuals (intercept-only model)
@@ -179,6 +304,11 @@ pub fn compute_db_time_gradient(
     let median_residuals: Vec<f64> = db_time_delta.iter().map(|&y| y - y_median_val).collect();
     let huber_delta = (1.345 * mad(&median_residuals)).max(1e-6);
 
+    println!("  -> Building Ridge regression");
+    println!("  -> Building Elastic Net regression");
+    println!("  -> Building Huber robust regression");
+    println!("  -> Building Quantile regression tau={}", 0.95);
+
     let start = Instant::now(); //for counting duration of models computation
     //parallel regression calculation
     let results: Vec<RegressionResult> = tasks.par_iter().map(|&task_id| {
@@ -242,13 +372,40 @@ pub fn compute_db_time_gradient(
     let huber_ranking = build_ranking(&huber_gradient_by_event, &event_delta_mad_by_event, &event_delta_p90_by_event, &event_delta_p99_by_event);
     let quantile95_ranking = build_ranking(&quantile95_gradient_by_event, &event_delta_mad_by_event, &event_delta_p90_by_event, &event_delta_p99_by_event);
 
+    // Block bootstrap over snapshots - confidence intervals and stability selection
+    let [ridge_bootstrap, elastic_net_bootstrap, huber_bootstrap, quantile95_bootstrap] = if bootstrap_resamples > 0 {
+        println!("  -> Block bootstrap with {} resamples", bootstrap_resamples);
+        let settings = BootstrapSettings {
+            resamples: bootstrap_resamples,
+            block_size: bootstrap_block_size,
+            seed: 0,
+            models: ModelSettings { ridge_lambda, elastic_net_lambda, elastic_net_alpha, elastic_net_max_iter, elastic_net_tol },
+        };
+        bootstrap_gradient(&event_delta_standardized_by_event, &db_time_delta, &event_delta_p90_by_event, &settings)
+    } else {
+        Default::default()
+    };
+
     // VIF diagnostics
+    println!("  -> Computing VIF");
     let vif_by_event = compute_vif(&event_delta_standardized_by_event);
     for (event, vif) in &vif_by_event {
         if *vif > 10.0 {
             println!("  ⚠️  VIF({}) = {:.1} — severe multicollinearity", event, vif);
         }
     }
+    // OLS with HAC standard errors - the only model with p-values, so it runs on a VIF-pruned predictor set
+    println!("  -> Building OLS regression with Newey-West standard errors");
+    let (ols_gradient_by_event, ols_inference_by_event, ols_fit) =
+        match ols_hac_regression(&event_delta_standardized_by_event, &db_time_delta) {
+            Ok((coefs, inference, fit)) => (coefs, inference, Some(fit)),
+            Err(e) => {
+                println!("  ⚠️  OLS skipped: {}", e);
+                (BTreeMap::new(), BTreeMap::new(), None)
+            }
+        };
+    let ols_ranking = build_ranking(&ols_gradient_by_event, &event_delta_mad_by_event, &event_delta_p90_by_event, &event_delta_p99_by_event);
+
     let end = Instant::now(); //for counting duration of models computation
     let duration = end.duration_since(start);
 
@@ -273,14 +430,527 @@ pub fn compute_db_time_gradient(
         elastic_net_ranking,
         huber_ranking,
         quantile95_ranking,
+        ols_gradient_by_event,
+        ols_ranking,
+        ols_inference_by_event,
+        ols_fit,
         event_delta_mean_by_event,
         event_delta_std_by_event,
         event_delta_mad_by_event,
+        regularization,
+        ridge_bootstrap,
+        elastic_net_bootstrap,
+        huber_bootstrap,
+        quantile95_bootstrap,
         vif_by_event,
         collinear_groups,
+        expanded_features,
+        pruned_features,
     })
 }
 
+/* =========================================================================================
+   OLS with Newey-West (HAC) standard errors
+   ========================================================================================= */
+
+/// Predictors with VIF above this value are removed before OLS
+const OLS_VIF_MAX: f64 = 10.0;
+/// Minimal number of differenced snapshots per OLS predictor
+const OLS_MIN_OBS_PER_PREDICTOR: usize = 5;
+
+/// Predictor set for OLS: the strongest predictors by |corr(Δx, Δy)| (at most n/5 of them),
+/// then the one with the highest VIF is dropped until all are below OLS_VIF_MAX.
+/// Returns (kept predictors, predictors dropped by VIF).
+fn ols_predictor_set(x_by_event: &EventSeriesMap, y: &[f64]) -> (Vec<String>, Vec<String>) {
+    let n = y.len();
+    let y_vec = y.to_vec();
+    let mut by_corr: Vec<(String, f64)> = x_by_event.iter()
+        .map(|(name, x)| {
+            let r = pearson_correlation_2v(x, &y_vec);
+            (name.clone(), if r.is_finite() { r.abs() } else { 0.0 })
+        })
+        .filter(|(_, r)| *r > 0.0)
+        .collect();
+    by_corr.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0)));
+    let max_predictors = (n / OLS_MIN_OBS_PER_PREDICTOR).max(1);
+    let mut kept: Vec<String> = by_corr.into_iter().take(max_predictors).map(|(name, _)| name).collect();
+
+    let mut dropped: Vec<String> = Vec::new();
+    while kept.len() > 1 {
+        let subset: EventSeriesMap = kept.iter().map(|k| (k.clone(), x_by_event[k].clone())).collect();
+        let vif_by_event = compute_vif(&subset);
+        let worst = vif_by_event.iter()
+            .filter(|(_, vif)| **vif > OLS_VIF_MAX)
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(name, _)| name.clone());
+        match worst {
+            Some(name) => {
+                kept.retain(|k| k != &name);
+                dropped.push(name);
+            }
+            None => break,
+        }
+    }
+    (kept, dropped)
+}
+
+/// OLS on standardized deltas with centered target (implicit intercept).
+/// Predictors are selected on the first half of Δ-snapshots and OLS is fitted on the second half only -
+/// p-values computed on the same data that picked the predictors would be too optimistic.
+/// Covariance is the Newey-West sandwich (X'X)^-1 S (X'X)^-1 with Bartlett weights 1 - l/(L+1),
+/// L = floor(4 (n/100)^(2/9)), and small-sample factor n / (n - p - 1) - snapshots are autocorrelated,
+/// so classic OLS errors would be too optimistic.
+fn ols_hac_regression(
+    x_by_event: &EventSeriesMap,
+    y: &[f64],
+) -> Result<(EventScalarMap, BTreeMap<String, OlsInference>, OlsFitSummary), String> {
+    let selection_snapshots = y.len() / 2;
+    if selection_snapshots < OLS_MIN_OBS_PER_PREDICTOR {
+        return Err(format!("not enough snapshots ({}) to select predictors on a held-out half", y.len()));
+    }
+    let (x_selection, y_selection) = select_rows(x_by_event, y, 0..selection_snapshots);
+    let (kept, dropped_by_vif) = ols_predictor_set(&x_selection, &y_selection);
+    let p = kept.len();
+    if p == 0 {
+        return Err("no predictor correlated with the target".into());
+    }
+
+    // Inference half is centered again - it has its own means
+    let center = |v: &[f64]| -> Vec<f64> {
+        let m = v.iter().sum::<f64>() / v.len().max(1) as f64;
+        v.iter().map(|x| x - m).collect()
+    };
+    let y = center(&y[selection_snapshots..]);
+    let n = y.len();
+    if n < p + 3 {
+        return Err(format!("not enough snapshots ({n}) for {p} predictors"));
+    }
+    let df = n - p - 1;
+
+    let columns: Vec<Vec<f64>> = kept.iter().map(|k| center(&x_by_event[k][selection_snapshots..])).collect();
+    let x = nalgebra::DMatrix::from_fn(n, p, |t, j| columns[j][t]);
+    let yv = nalgebra::DVector::from_column_slice(&y);
+    let xtx_inv = (x.transpose() * &x).try_inverse().ok_or("X'X is singular")?;
+    let beta = &xtx_inv * (x.transpose() * &yv);
+    let residuals = &yv - &x * &beta;
+
+    // Scores u_t = x_t * e_t
+    let mut u = x.clone();
+    for t in 0..n {
+        let e = residuals[t];
+        u.row_mut(t).scale_mut(e);
+    }
+    let hac_lags = (4.0 * (n as f64 / 100.0).powf(2.0 / 9.0)).floor() as usize;
+    let mut s = u.transpose() * &u;
+    for l in 1..=hac_lags.min(n - 1) {
+        let w = 1.0 - l as f64 / (hac_lags as f64 + 1.0);
+        let gamma = u.rows(l, n - l).transpose() * u.rows(0, n - l);
+        s += (&gamma + gamma.transpose()) * w;
+    }
+    let cov = &xtx_inv * s * &xtx_inv * (n as f64 / df as f64);
+
+    let rss: f64 = residuals.iter().map(|e| e * e).sum();
+    let tss: f64 = y.iter().map(|v| v * v).sum();
+    let r_squared = if tss > 1e-15 { 1.0 - rss / tss } else { 0.0 };
+    let adj_r_squared = 1.0 - (1.0 - r_squared) * (n as f64 - 1.0) / df as f64;
+
+    let mut coefs = EventScalarMap::new();
+    let mut inference = BTreeMap::new();
+    for (j, name) in kept.iter().enumerate() {
+        let std_error = cov[(j, j)].max(0.0).sqrt();
+        let t_stat = if std_error > 0.0 { beta[j] / std_error } else { 0.0 };
+        coefs.insert(name.clone(), beta[j]);
+        inference.insert(name.clone(), OlsInference {
+            std_error,
+            t_stat,
+            p_value: student_t_two_sided_p_value(t_stat, df as f64),
+        });
+    }
+
+    Ok((coefs, inference, OlsFitSummary {
+        r_squared,
+        adj_r_squared,
+        hac_lags,
+        num_predictors: p,
+        degrees_of_freedom: df,
+        dropped_by_vif,
+        selection_snapshots,
+    }))
+}
+
+/* =========================================================================================
+   Lagged and interaction features
+   ========================================================================================= */
+
+/// Name of a lagged delta feature as shown in rankings
+pub fn lag_feature_name(event_name: &str, lag: usize) -> String {
+    format!("{event_name} (lag {lag})")
+}
+
+/// Name of an interaction feature as shown in rankings
+pub fn interaction_feature_name(event_a: &str, event_b: &str) -> String {
+    format!("{event_a} × {event_b}")
+}
+
+/// Adds lagged deltas and pairwise interactions of the top-k predictors to the delta map.
+/// Lagged delta at t is Δx[t-lag] (0 before the first snapshot) - queuing effects that show up one snapshot later.
+/// Interaction is a product of standardized deltas - both predictors moving together (e.g. commits AND log writes).
+/// Expanded features with VIF above `vif_max` are dropped in a single pass, base predictors are always kept.
+/// Returns (expanded delta map, kept expanded features, pruned expanded features).
+fn expand_features(
+    event_delta_by_event: EventSeriesMap,
+    target_delta: &[f64],
+    expansion: &FeatureExpansion,
+) -> (EventSeriesMap, Vec<String>, Vec<String>) {
+    let target_vec = target_delta.to_vec();
+    let mut by_corr: Vec<(String, f64)> = event_delta_by_event.iter()
+        .map(|(name, deltas)| {
+            let r = pearson_correlation_2v(deltas, &target_vec);
+            (name.clone(), if r.is_finite() { r.abs() } else { 0.0 })
+        })
+        .filter(|(_, r)| *r > 0.0)
+        .collect();
+    by_corr.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0)));
+    let top: Vec<String> = by_corr.into_iter().take(expansion.top_k).map(|(name, _)| name).collect();
+
+    let mut expanded = event_delta_by_event.clone();
+    let mut added: Vec<String> = Vec::new();
+
+    for lag in 1..=expansion.lags {
+        for name in &top {
+            let deltas = &event_delta_by_event[name];
+            let lagged: Vec<f64> = (0..deltas.len())
+                .map(|t| if t >= lag { deltas[t - lag] } else { 0.0 })
+                .collect();
+            let feature = lag_feature_name(name, lag);
+            expanded.insert(feature.clone(), lagged);
+            added.push(feature);
+        }
+    }
+
+    if expansion.interactions && top.len() > 1 {
+        let top_series: EventSeriesMap = top.iter().map(|n| (n.clone(), event_delta_by_event[n].clone())).collect();
+        let top_mean = compute_mean_by_event(&top_series);
+        let top_std = compute_std_by_event(&top_series, &top_mean);
+        let top_z = standardize_by_event(&top_series, &top_mean, &top_std);
+        for a in 0..top.len() {
+            for b in (a + 1)..top.len() {
+                let product: Vec<f64> = top_z[&top[a]].iter().zip(top_z[&top[b]].iter()).map(|(x, y)| x * y).collect();
+                let feature = interaction_feature_name(&top[a], &top[b]);
+                expanded.insert(feature.clone(), product);
+                added.push(feature);
+            }
+        }
+    }
+
+    // VIF pruning of expanded features only - keeps the design matrix well-conditioned.
+    // Only the worst feature is dropped in each step, its collinear partner may fall below vif_max once it is gone.
+    let mut pruned: Vec<String> = Vec::new();
+    if !added.is_empty() {
+        println!("  -> Pruning expanded features with VIF > {}", expansion.vif_max);
+        let mean = compute_mean_by_event(&expanded);
+        let std = compute_std_by_event(&expanded, &mean);
+        let mut standardized = standardize_by_event(&expanded, &mean, &std);
+        while !added.is_empty() {
+            let vif_by_event = compute_vif_of(&standardized, &added);
+            let worst = vif_by_event.iter()
+                .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+                .filter(|(_, vif)| **vif > expansion.vif_max)
+                .map(|(name, _)| name.clone());
+            let Some(worst) = worst else { break; };
+            standardized.remove(&worst);
+            expanded.remove(&worst);
+            added.retain(|n| *n != worst);
+            pruned.push(worst);
+        }
+    }
+
+    (expanded, added, pruned)
+}
+
+/* =========================================================================================
+   Regularization tuning (rolling-origin cross-validation / GCV)
+   ========================================================================================= */
+
+/// Elastic Net mixing values searched by tuning
+const TUNING_EN_ALPHAS: [f64; 6] = [0.1, 0.333, 0.5, 0.666, 0.9, 1.0];
+/// Number of validation blocks in rolling-origin cross-validation
+const TUNING_CV_FOLDS: usize = 5;
+/// Below this number of Δ-snapshots tuning is skipped and manual values are used
+const TUNING_MIN_SAMPLES: usize = 20;
+
+fn log_grid(from: f64, to: f64, points: usize) -> Vec<f64> {
+    let (lf, lt) = (from.ln(), to.ln());
+    (0..points)
+        .map(|i| (lf + (lt - lf) * i as f64 / (points - 1).max(1) as f64).exp())
+        .collect()
+}
+
+/// Ridge lambda grid - Ridge penalty is added to X'X, so it is relative to number of samples
+fn ridge_lambda_grid(n: usize) -> Vec<f64> {
+    log_grid(1e-3 * n as f64, 1e2 * n as f64, 26)
+}
+
+/// Elastic Net lambda grid for given alpha - from lambda_max (all coefficients zero) down to 0.001 * lambda_max
+fn elastic_net_lambda_grid(x_by_event: &EventSeriesMap, y: &[f64], alpha: f64) -> Vec<f64> {
+    let n = y.len().max(1) as f64;
+    let lambda_max = x_by_event
+        .values()
+        .map(|x| (x.iter().zip(y).map(|(a, b)| a * b).sum::<f64>() / n).abs())
+        .fold(0.0, f64::max) / alpha.max(1e-3);
+    if lambda_max <= 0.0 || !lambda_max.is_finite() {
+        return Vec::new();
+    }
+    log_grid(lambda_max, lambda_max * 1e-3, 15)
+}
+
+fn predict(coef_by_event: &EventScalarMap, x_by_event: &EventSeriesMap, t: usize) -> f64 {
+    coef_by_event
+        .iter()
+        .map(|(name, c)| c * x_by_event.get(name).map_or(0.0, |x| x[t]))
+        .sum()
+}
+
+fn select_rows(x_by_event: &EventSeriesMap, y: &[f64], rows: std::ops::Range<usize>) -> (EventSeriesMap, Vec<f64>) {
+    let x_rows = x_by_event
+        .iter()
+        .map(|(name, x)| (name.clone(), x[rows.clone()].to_vec()))
+        .collect();
+    (x_rows, y[rows].to_vec())
+}
+
+/// Rolling-origin folds: the second half of the series is split into consecutive validation blocks,
+/// each model is trained only on snapshots before its validation block (no look-ahead).
+fn rolling_origin_folds(n: usize) -> Vec<(usize, usize)> {
+    let first_validation = n / 2;
+    let block = ((n - first_validation) / TUNING_CV_FOLDS).max(1);
+    (0..TUNING_CV_FOLDS)
+        .map(|k| (first_validation + k * block, (first_validation + (k + 1) * block).min(n)))
+        .filter(|(start, end)| start < end)
+        .collect()
+}
+
+/// Mean squared error over all validation blocks for a model fitted by `fit` on the preceding rows
+fn rolling_origin_mse<F>(x_by_event: &EventSeriesMap, y: &[f64], folds: &[(usize, usize)], fit: F) -> f64
+where
+    F: Fn(&EventSeriesMap, &[f64]) -> Option<EventScalarMap>,
+{
+    let mut sse = 0.0;
+    let mut count = 0usize;
+    for (start, end) in folds {
+        let (x_train, y_train) = select_rows(x_by_event, y, 0..*start);
+        let Some(coef_by_event) = fit(&x_train, &y_train) else { return f64::INFINITY; };
+        for (t, yt) in y.iter().enumerate().take(*end).skip(*start) {
+            let r = yt - predict(&coef_by_event, x_by_event, t);
+            sse += r * r;
+            count += 1;
+        }
+    }
+    if count == 0 { f64::INFINITY } else { sse / count as f64 }
+}
+
+/// Generalized cross-validation score: (RSS / n) / (1 - df / n)^2
+fn gcv_score(rss: f64, df: f64, n: usize) -> f64 {
+    let n = n as f64;
+    if df >= n {
+        return f64::INFINITY;
+    }
+    (rss / n) / (1.0 - df / n).powi(2)
+}
+
+/// GCV for Ridge computed for the whole grid from one SVD of X:
+/// RSS(lambda) = sum((lambda / (d_i^2 + lambda) * u_i'y)^2) + ||y - UU'y||^2, df(lambda) = sum(d_i^2 / (d_i^2 + lambda))
+fn ridge_gcv(x_by_event: &EventSeriesMap, y: &[f64], grid: &[f64]) -> Vec<(f64, f64)> {
+    let n = y.len();
+    let columns: Vec<&Vec<f64>> = x_by_event.values().collect();
+    let x = nalgebra::DMatrix::from_fn(n, columns.len(), |t, j| columns[j][t]);
+    let yv = nalgebra::DVector::from_column_slice(y);
+    let svd = x.svd(true, false);
+    let Some(u) = svd.u else { return Vec::new(); };
+    let uty = u.transpose() * &yv;
+    let residual_outside = (yv.norm_squared() - uty.norm_squared()).max(0.0);
+
+    grid.iter().map(|&lambda| {
+        let mut rss = residual_outside;
+        let mut df = 0.0;
+        for (i, d) in svd.singular_values.iter().enumerate() {
+            let d2 = d * d;
+            let shrink = lambda / (d2 + lambda);
+            rss += (shrink * uty[i]).powi(2);
+            df += d2 / (d2 + lambda);
+        }
+        (lambda, gcv_score(rss, df, n))
+    }).collect()
+}
+
+/// Selects Ridge lambda and Elastic Net lambda/alpha.
+///     none - manual values from command line
+///     cv   - rolling-origin cross-validation (validation MSE)
+///     gcv  - generalized cross-validation on the whole sample; Elastic Net df is the number of non-zero coefficients
+/// Grid points are evaluated in parallel with rayon.
+pub fn tune_regularization(x_by_event: &EventSeriesMap, y: &[f64], tuning: &str, manual: &ModelSettings) -> RegularizationChoice {
+    let (elastic_net_max_iter, elastic_net_tol) = (manual.elastic_net_max_iter, manual.elastic_net_tol);
+    let mut choice = RegularizationChoice {
+        method: "none".to_string(),
+        ridge_lambda: manual.ridge_lambda,
+        elastic_net_lambda: manual.elastic_net_lambda,
+        elastic_net_alpha: manual.elastic_net_alpha,
+        ridge_validation_error: None,
+        elastic_net_validation_error: None,
+    };
+    match tuning {
+        "none" => return choice,
+        "cv" | "gcv" => {},
+        other => {
+            println!("{} {}", "  ⚠️  Unknown gradient tuning method, expected none, cv or gcv - using manual values:".red(), other);
+            return choice;
+        }
+    }
+    let n = y.len();
+    if n < TUNING_MIN_SAMPLES {
+        choice.method = format!("none ({} skipped - only {} samples)", tuning, n);
+        return choice;
+    }
+
+    let best = |scores: Vec<(f64, f64, f64)>| -> Option<(f64, f64, f64)> {
+        scores.into_iter()
+              .filter(|s| s.2.is_finite())
+              .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal))
+    };
+    let folds = rolling_origin_folds(n);
+    let en_grid: Vec<(f64, f64)> = TUNING_EN_ALPHAS
+        .iter()
+        .flat_map(|&alpha| elastic_net_lambda_grid(x_by_event, y, alpha).into_iter().map(move |lambda| (alpha, lambda)))
+        .collect();
+
+    println!("  -> Tuning regularization ({}): {} Ridge and {} Elastic Net candidates", tuning, ridge_lambda_grid(n).len(), en_grid.len());
+
+    let ridge_scores: Vec<(f64, f64, f64)> = if tuning == "gcv" {
+        ridge_gcv(x_by_event, y, &ridge_lambda_grid(n)).into_iter().map(|(l, score)| (l, 0.0, score)).collect()
+    } else {
+        ridge_lambda_grid(n).par_iter().map(|&lambda| {
+            //Ridge penalty is added to X'X, so it is rescaled to the size of the training set
+            let mse = rolling_origin_mse(x_by_event, y, &folds, |x, yt| {
+                ridge_regression_map(x, yt, lambda * yt.len() as f64 / n as f64).ok()
+            });
+            (lambda, 0.0, mse)
+        }).collect()
+    };
+
+    let en_scores: Vec<(f64, f64, f64)> = en_grid.par_iter().map(|&(alpha, lambda)| {
+        let score = if tuning == "gcv" {
+            let coef_by_event = elastic_net_coordinate_descent_map(x_by_event, y, lambda, alpha, elastic_net_max_iter, elastic_net_tol);
+            let rss: f64 = (0..n).map(|t| (y[t] - predict(&coef_by_event, x_by_event, t)).powi(2)).sum();
+            let df = coef_by_event.values().filter(|c| **c != 0.0).count() as f64;
+            gcv_score(rss, df, n)
+        } else {
+            rolling_origin_mse(x_by_event, y, &folds, |x, yt| {
+                Some(elastic_net_coordinate_descent_map(x, yt, lambda, alpha, elastic_net_max_iter, elastic_net_tol))
+            })
+        };
+        (lambda, alpha, score)
+    }).collect();
+
+    choice.method = tuning.to_string();
+    if let Some((lambda, _, score)) = best(ridge_scores) {
+        choice.ridge_lambda = lambda;
+        choice.ridge_validation_error = Some(score);
+    }
+    if let Some((lambda, alpha, score)) = best(en_scores) {
+        choice.elastic_net_lambda = lambda;
+        choice.elastic_net_alpha = alpha;
+        choice.elastic_net_validation_error = Some(score);
+    }
+    println!("  -> Selected ridge_lambda={:.4} elastic_net_lambda={:.4} elastic_net_alpha={}",
+             choice.ridge_lambda, choice.elastic_net_lambda, choice.elastic_net_alpha);
+    choice
+}
+
+/* =========================================================================================
+   Block bootstrap (confidence intervals + Elastic Net stability selection)
+   ========================================================================================= */
+
+/// Default block length for moving block bootstrap: n^(1/3), at least 2
+pub fn default_bootstrap_block_size(n: usize) -> usize {
+    ((n as f64).cbrt().ceil() as usize).max(2)
+}
+
+/// Moving block bootstrap: random blocks of consecutive Δ-snapshots are glued together until n rows are drawn.
+/// Blocks keep short-range autocorrelation of the series which plain row resampling would destroy.
+fn block_bootstrap_indices(n: usize, block_size: usize, rng: &mut SplitMix64) -> Vec<usize> {
+    let block_size = block_size.clamp(1, n.max(1));
+    let mut indices: Vec<usize> = Vec::with_capacity(n);
+    while indices.len() < n {
+        let start = rng.next_index(n - block_size + 1);
+        for i in start..(start + block_size) {
+            if indices.len() == n {
+                break;
+            }
+            indices.push(i);
+        }
+    }
+    indices
+}
+
+/// Fits all 4 models on one resample. Columns and target are centered again because the resample has its own means.
+fn fit_models_on_resample(x_by_event: &EventSeriesMap, y: &[f64], indices: &[usize], models: &ModelSettings) -> Option<[EventScalarMap; 4]> {
+    let ModelSettings { ridge_lambda, elastic_net_lambda, elastic_net_alpha, elastic_net_max_iter, elastic_net_tol } = *models;
+    let center = |v: Vec<f64>| -> Vec<f64> {
+        let m = v.iter().sum::<f64>() / v.len().max(1) as f64;
+        v.into_iter().map(|x| x - m).collect()
+    };
+    let y_res = center(indices.iter().map(|&t| y[t]).collect());
+    let x_res: EventSeriesMap = x_by_event
+        .iter()
+        .map(|(name, series)| (name.clone(), center(indices.iter().map(|&t| series[t]).collect())))
+        .collect();
+
+    let y_median_val = median(&y_res);
+    let median_residuals: Vec<f64> = y_res.iter().map(|&v| v - y_median_val).collect();
+    let huber_delta = (1.345 * mad(&median_residuals)).max(1e-6);
+
+    let ridge = ridge_regression_map(&x_res, &y_res, ridge_lambda).ok()?;
+    let en = elastic_net_coordinate_descent_map(&x_res, &y_res, elastic_net_lambda, elastic_net_alpha, elastic_net_max_iter, elastic_net_tol);
+    let huber = huber_regression_map(&x_res, &y_res, huber_delta, 100, elastic_net_tol, ridge_lambda);
+    let q95 = quantile_regression_irls_map(&x_res, &y_res, 0.95, 200, elastic_net_tol, ridge_lambda);
+    Some([ridge, en, huber, q95])
+}
+
+/// Runs block bootstrap in parallel (one resample per task) and summarizes coefficients of each model
+/// as 95% percentile intervals. Returned array is in order: Ridge, Elastic Net, Huber, Quantile95.
+fn bootstrap_gradient(x_by_event: &EventSeriesMap, y: &[f64], p90_by_event: &EventScalarMap, settings: &BootstrapSettings) -> [BootstrapByEvent; 4] {
+    let n = y.len();
+    let block_size = if settings.block_size == 0 { default_bootstrap_block_size(n) } else { settings.block_size };
+
+    let fits: Vec<[EventScalarMap; 4]> = (0..settings.resamples)
+        .into_par_iter()
+        .filter_map(|b| {
+            let mut rng = SplitMix64::new(settings.seed + b as u64 + 1);
+            let indices = block_bootstrap_indices(n, block_size, &mut rng);
+            fit_models_on_resample(x_by_event, y, &indices, &settings.models)
+        })
+        .collect();
+
+    let mut summaries: [BootstrapByEvent; 4] = Default::default();
+    if fits.is_empty() {
+        return summaries;
+    }
+    for (model, summary) in summaries.iter_mut().enumerate() {
+        for event_name in x_by_event.keys() {
+            let coefs: Vec<f64> = fits.iter().map(|f| *f[model].get(event_name).unwrap_or(&0.0)).collect();
+            let p90 = *p90_by_event.get(event_name).unwrap_or(&0.0);
+            let impacts: Vec<f64> = coefs.iter().map(|c| c.abs() * p90).collect();
+            let selected = coefs.iter().filter(|c| **c != 0.0).count();
+            summary.insert(event_name.clone(), CoefBootstrap {
+                coef_ci: (percentile(&coefs, 2.5), percentile(&coefs, 97.5)),
+                impact_active_ci: (percentile(&impacts, 2.5), percentile(&impacts, 97.5)),
+                selection_frequency: selected as f64 / coefs.len() as f64,
+            });
+        }
+    }
+    summaries
+}
+
 /* =========================================================================================
    Core computations
    ========================================================================================= */
@@ -378,8 +1048,6 @@ fn ridge_regression_map (
     lambda: f64,
 ) -> Result<EventScalarMap, String> {
 
-    println!("  -> Building Ridge regression");
-
     let n = db_time_delta.len();
     let event_names: Vec<String> = standardized_event_deltas.keys().cloned().collect();
     let p = event_names.len();
@@ -442,8 +1110,6 @@ fn elastic_net_coordinate_descent_map(
     tol: f64,
 ) -> EventScalarMap {
 
-    println!("  -> Building Elastic Net regression");
-
     let sample_count = db_time_delta.len();
     let mut coef_by_event: EventScalarMap = standardized_event_deltas
         .keys().map(|k| (k.clone(), 0.0)).collect();
@@ -504,8 +1170,6 @@ fn huber_regression_map(
     tol: f64,
     ridge_penalty: f64,
 ) -> EventScalarMap {
-    println!("  -> Building Huber robust regression");
-
     let n = y.len();
     let event_names: Vec<String> = x_by_event.keys().cloned().collect();
     let p = event_names.len();
@@ -584,8 +1248,6 @@ fn quantile_regression_irls_map(
     tol: f64,
     ridge_penalty: f64,
 ) -> EventScalarMap {
-    println!("  -> Building Quantile regression tau={}", tau);
-
     let n = y.len();
     let event_names: Vec<String> = x_by_event.keys().cloned().collect();
     let p = event_names.len();
@@ -772,7 +1434,12 @@ fn solve_dense_linear_system(a: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
 /// VIF_j = 1 / (1 - R²_j), where R²_j is from regressing x_j on all other x's.
 /// VIF > 10 indicates severe multicollinearity.
 pub fn compute_vif(x_by_event: &EventSeriesMap) -> EventScalarMap {
-    println!("  -> Computing VIF");
+    let event_names: Vec<String> = x_by_event.keys().cloned().collect();
+    compute_vif_of(x_by_event, &event_names)
+}
+
+/// VIF of selected predictors only - each of them is still regressed on all other x's.
+fn compute_vif_of(x_by_event: &EventSeriesMap, targets: &[String]) -> EventScalarMap {
     let event_names: Vec<String> = x_by_event.keys().cloned().collect();
     let p = event_names.len();
     let n = x_by_event
@@ -782,7 +1449,7 @@ pub fn compute_vif(x_by_event: &EventSeriesMap) -> EventScalarMap {
         .unwrap_or(0);
 
     if p <= 1 || n < p + 1 {
-        return event_names.iter().map(|e| (e.clone(), 1.0)).collect();
+        return targets.iter().map(|e| (e.clone(), 1.0)).collect();
     }
 
     // Pre-materialize all columns ONCE — eliminates BTreeMap lookups in hot loops.
@@ -792,7 +1459,9 @@ pub fn compute_vif(x_by_event: &EventSeriesMap) -> EventScalarMap {
         .map(|name| x_by_event[name].as_slice())
         .collect();
 
-    let vif_entries: Vec<(String, f64)> = (0..p).into_par_iter().map(|target_idx| {
+    let target_indices: Vec<usize> = (0..p).filter(|i| targets.contains(&event_names[*i])).collect();
+
+    let vif_entries: Vec<(String, f64)> = target_indices.into_par_iter().map(|target_idx| {
         let target_name = &event_names[target_idx];
         let y_j: &[f64] = all_columns[target_idx];
 
@@ -1043,7 +1712,11 @@ fn build_ranking(
    Cross-model triangulation / classification
    ========================================================================================= */
 
+/// HAC p-value below which OLS confirms a predictor
+const OLS_ALPHA: f64 = 0.05;
+
 /// Classify events/stats/SQLs by cross-referencing all 4 model rankings.
+/// Top classes additionally require that OLS did not reject the predictor (HAC p-value < OLS_ALPHA).
 /// Returns a list of classifications sorted by confidence (confirmed bottlenecks first).
 pub fn cross_model_classify(
     section: &DbTimeGradientSection,
@@ -1073,10 +1746,18 @@ pub fn cross_model_classify(
         .map(|i| i.event_name.clone())
         .collect();
 
+    //OLS counts only when the coefficient is significant - HAC p-value below 5%
+    let ols_set: HashSet<String> = section.ols_top.iter()
+        .take(top_n)
+        .filter(|i| i.impact >= 0.0 && i.gradient_coef > 0.0 && i.p_value.is_some_and(|p| p < OLS_ALPHA))
+        .map(|i| i.event_name.clone())
+        .collect();
+
     let all_events: HashSet<String> = ridge_set.iter()
         .chain(en_set.iter())
         .chain(huber_set.iter())
         .chain(q95_set.iter())
+        .chain(ols_set.iter())
         .cloned()
         .collect();
 
@@ -1087,8 +1768,14 @@ pub fn cross_model_classify(
         let in_en = en_set.contains(event);
         let in_huber = huber_set.contains(event);
         let in_q95 = q95_set.contains(event);
-
-        let model_count = [in_ridge, in_en, in_huber, in_q95].iter().filter(|&&b| b).count();
+        let in_ols = ols_set.contains(event);
+        let ols_p_value = section.ols_top.iter()
+                                               .find_map(|r| (&r.event_name == event).then(|| r.p_value))
+                                               .flatten();
+        //OLS estimated the predictor and could not confirm it - top tiers are downgraded, no estimate means no downgrade
+        let ols_not_significant = ols_p_value.is_some_and(|p| p >= OLS_ALPHA);
+
+        let model_count = [in_ridge, in_en, in_huber, in_q95, in_ols].iter().filter(|&&b| b).count();
         let ridge_impact = section.ridge_top.iter() 
                                                .find_map(|r| (&r.event_name == event).then(|| r.impact_active))
                                                .unwrap_or(0.0);
@@ -1101,7 +1788,10 @@ pub fn cross_model_classify(
         let q95_impact = section.quantile95_top.iter() 
                                                .find_map(|r| (&r.event_name == event).then(|| r.impact_active))
                                                .unwrap_or(0.0);
-        let combined_impact = ridge_impact + en_impact + huber_impact + q95_impact;
+        let ols_impact = section.ols_top.iter() 
+                                               .find_map(|r| (&r.event_name == event).then(|| r.impact_active))
+                                               .unwrap_or(0.0);
+        let combined_impact = ridge_impact + en_impact + huber_impact + q95_impact + ols_impact;
 
         let ridge_peak_impact = section.ridge_top.iter() 
                                                .find_map(|r| (&r.event_name == event).then(|| r.impact_peak))
@@ -1116,19 +1806,52 @@ pub fn cross_model_classify(
                                                .find_map(|r| (&r.event_name == event).then(|| r.impact_peak))
                                                .unwrap_or(0.0);
 
-        let combined_peak_impact = ridge_peak_impact + en_peak_impact + huber_peak_impact + q95_peak_impact;
+        let ols_peak_impact = section.ols_top.iter() 
+                                               .find_map(|r| (&r.event_name == event).then(|| r.impact_peak))
+                                               .unwrap_or(0.0);
+
+        let combined_peak_impact = ridge_peak_impact + en_peak_impact + huber_peak_impact + q95_peak_impact + ols_peak_impact;
+
+        //Models in which bootstrap interval of the coefficient contains zero - the sign is not certain
+        let ci_crosses_zero_in: Vec<String> = [
+            ("RIDGE", in_ridge, &section.ridge_top),
+            ("ELASTIC_NET", in_en, &section.elastic_net_top),
+            ("HUBER", in_huber, &section.huber_top),
+            ("QUANTILE95", in_q95, &section.quantile95_top),
+        ].iter()
+            .filter(|(_, in_model, _)| *in_model)
+            .filter_map(|(model, _, top)| {
+                let item = top.iter().find(|r| &r.event_name == event)?;
+                match (item.coef_ci_low, item.coef_ci_high) {
+                    (Some(low), Some(high)) if low <= 0.0 && high >= 0.0 => Some(model.to_string()),
+                    _ => None,
+                }
+            })
+            .collect();
 
         let (classification, priority) = if in_ridge && in_en && in_huber && in_q95 {
             // All 4 models agree
-            ("CONFIRMED_BOTTLENECK", 0)
+            if ols_not_significant {
+                ("CONFIRMED_BOTTLENECK_NOT_SIGNIFICANT", 2)
+            } else {
+                ("CONFIRMED_BOTTLENECK", 0)
+            }
         } else if in_ridge && in_huber && in_q95 && !in_en {
             // Ridge + Huber + Q95 but NOT EN → EN zeroed it due to collinearity with another 
             // dominant event. Still very high confidence since 3 independent models agree 
             // including the tail-risk model.
-            ("CONFIRMED_BOTTLENECK_EN_COLLINEAR", 1)
+            if ols_not_significant {
+                ("CONFIRMED_BOTTLENECK_EN_COLLINEAR_NOT_SIGNIFICANT", 2)
+            } else {
+                ("CONFIRMED_BOTTLENECK_EN_COLLINEAR", 1)
+            }
         } else if in_ridge && in_en && in_huber && !in_q95 {
             // Strong across average behavior, not dominant in tail
-            ("STRONG_CONTRIBUTOR", 2)
+            if ols_not_significant {
+                ("STRONG_CONTRIBUTOR_NOT_SIGNIFICANT", 3)
+            } else {
+                ("STRONG_CONTRIBUTOR", 2)
+            }
         } else if in_ridge && in_huber && !in_en && !in_q95 {
             // Stable systematic contributor, but EN dropped it (collinear) 
             // and not a tail risk
@@ -1149,6 +1872,9 @@ pub fn cross_model_classify(
         } else if in_huber && !in_ridge && !in_en {
             // Only visible when outliers are removed
             ("ROBUST_ONLY", 7)
+        } else if in_ols && model_count == 1 {
+            // Significant in OLS but not in the Top-N of any regularized/robust model
+            ("OLS_SIGNIFICANT_ONLY", 8)
         } else if model_count >= 2 {
             // At least 2 models, but doesn't match any specific pattern above
             ("MULTI_MODEL_MINOR", 8)
@@ -1168,6 +1894,13 @@ pub fn cross_model_classify(
             "STRONG_CONTRIBUTOR" => 
                 "Present in Ridge, ElasticNet, and Huber but not Q95. Reliable systematic \
                  contributor to DB Time, but not especially dominant in tail/worst-case scenarios.",
+            "CONFIRMED_BOTTLENECK_NOT_SIGNIFICANT" | "CONFIRMED_BOTTLENECK_EN_COLLINEAR_NOT_SIGNIFICANT" => 
+                "Ranked high by Ridge, Huber and Q95 but its OLS coefficient is not significant \
+                 (HAC p-value >= 0.05). Strong ranking signal without statistical confirmation — \
+                 likely shares its effect with collinear predictors or rests on few snapshots.",
+            "STRONG_CONTRIBUTOR_NOT_SIGNIFICANT" => 
+                "Present in Ridge, ElasticNet, and Huber but its OLS coefficient is not significant \
+                 (HAC p-value >= 0.05). Systematic contributor in rankings only — verify before acting.",
             "STABLE_CONTRIBUTOR" => 
                 "Present in Ridge and Huber (both agree = robust finding) but absent from \
                  ElasticNet (collinearity) and Q95 (not a tail driver). A steady, moderate \
@@ -1191,6 +1924,9 @@ pub fn cross_model_classify(
             "ROBUST_ONLY" => 
                 "Present only in Huber. Stable background contributor visible only when \
                  outliers are downweighted. Low priority but worth monitoring.",
+            "OLS_SIGNIFICANT_ONLY" => 
+                "Statistically significant in OLS (HAC p-value < 0.05) but not ranked high by \
+                 the other models. Real but small effect — informational, check its size before acting.",
             "MULTI_MODEL_MINOR" => 
                 "Appeared in at least 2 models but with no clear dominant pattern. Minor \
                  contributor worth noting.",
@@ -1206,9 +1942,12 @@ pub fn cross_model_classify(
             in_elastic_net: in_en,
             in_huber,
             in_quantile95: in_q95,
+            in_ols,
+            ols_p_value,
             priority: priority as u8,
             combined_impact: combined_impact,
             combined_peak_impact: combined_peak_impact,
+            ci_crosses_zero_in,
         });
     }
 
@@ -1234,6 +1973,7 @@ pub fn print_cross_model_table(
         Cell::new("EN").with_style(Attr::Bold),
         Cell::new("Huber").with_style(Attr::Bold),
         Cell::new("Q95").with_style(Attr::Bold),
+        Cell::new("OLS").with_style(Attr::Bold),
         Cell::new("Combined Impact").with_style(Attr::Bold),
         Cell::new("Combined Peak Impact").with_style(Attr::Bold),
         Cell::new("Description").with_style(Attr::Bold),
@@ -1249,6 +1989,7 @@ pub fn print_cross_model_table(
             Cell::new(yn(c.in_elastic_net)),
             Cell::new(yn(c.in_huber)),
             Cell::new(yn(c.in_quantile95)),
+            Cell::new(yn(c.in_ols)),
             Cell::new(&format!("{:.2}",c.combined_impact)),
             Cell::new(&format!("{:.2}",c.combined_peak_impact)),
             Cell::new(&desc),
@@ -1258,12 +1999,12 @@ pub fn print_cross_model_table(
     }
 
 
-    let mut html = table_to_html_string(&table, &format!("Cross-Model Triangulation: {}", section_label), &["Event/Stat/SQL", "Classification", "Ridge", "EN", "Huber", "Q95", "Combined Impact","Combined Peak Impact","Description"]);
+    let mut html = table_to_html_string(&table, &format!("Cross-Model Triangulation: {}", section_label), &["Event/Stat/SQL", "Classification", "Ridge", "EN", "Huber", "Q95", "OLS", "Combined Impact","Combined Peak Impact","Description"]);
     html = format!(r#"<div>{html}</div>"#);
 
     /* Removing description before printing on screen, because it doesn't look good */
     for row in table.row_iter_mut() {
-        row.remove_cell(6);
+        row.remove_cell(9);
     }
 
     for table_line in table.to_string().lines() {
@@ -1286,6 +2027,10 @@ pub fn build_db_time_gradient_section(
     elastic_net_alpha: f64,
     elastic_net_max_iter: usize,
     elastic_net_tol: f64,
+    bootstrap_resamples: usize,
+    bootstrap_block_size: usize,
+    tuning: &str,
+    expansion: &FeatureExpansion,
     units_desc: &str,
 ) -> Result<DbTimeGradientSection, String> {
     println!("\n\nBuilding gradient for {units_desc} - {} stats", event_series.len());
@@ -1309,35 +2054,68 @@ pub fn build_db_time_gradient_section(
         db_time_series, event_series,
         ridge_lambda, elastic_net_lambda, elastic_net_alpha,
         elastic_net_max_iter, elastic_net_tol,
+        bootstrap_resamples, bootstrap_block_size,
+        tuning, expansion,
     )?;
 
-    let make_top = |ranking: &[EventImpact], filter_zero: bool| -> Vec<GradientTopItem> {
+    //Selection frequency is reported only for Elastic Net - other models never zero coefficients
+    let make_top = |ranking: &[EventImpact], bootstrap: &BootstrapByEvent, filter_zero: bool, with_selection: bool| -> Vec<GradientTopItem> {
         ranking.iter()
             .filter(|x| !filter_zero || x.gradient_coef != 0.0)
             .take(50)
-            .map(|x| GradientTopItem {
-                event_name: x.event_name.clone(),
-                gradient_coef: x.gradient_coef,
-                impact: x.impact,
-                impact_active: x.impact_active,
-                impact_peak: x.impact_peak,
-                impact_share: x.impact_share,
+            .map(|x| {
+                let b = bootstrap.get(&x.event_name);
+                GradientTopItem {
+                    event_name: x.event_name.clone(),
+                    gradient_coef: x.gradient_coef,
+                    impact: x.impact,
+                    impact_active: x.impact_active,
+                    impact_peak: x.impact_peak,
+                    impact_share: x.impact_share,
+                    coef_ci_low: b.map(|b| b.coef_ci.0),
+                    coef_ci_high: b.map(|b| b.coef_ci.1),
+                    impact_active_ci_low: b.map(|b| b.impact_active_ci.0),
+                    impact_active_ci_high: b.map(|b| b.impact_active_ci.1),
+                    selection_frequency: b.filter(|_| with_selection).map(|b| b.selection_frequency),
+                    ..Default::default()
+                }
             })
             .collect()
     };
 
-    let ridge_top = make_top(&gradient_result.ridge_ranking, false);
-    let elastic_net_top = make_top(&gradient_result.elastic_net_ranking, true);
-    let huber_top = make_top(&gradient_result.huber_ranking, false);
-    let quantile95_top = make_top(&gradient_result.quantile95_ranking, false);
+    let ridge_top = make_top(&gradient_result.ridge_ranking, &gradient_result.ridge_bootstrap, false, false);
+    let elastic_net_top = make_top(&gradient_result.elastic_net_ranking, &gradient_result.elastic_net_bootstrap, true, true);
+    let huber_top = make_top(&gradient_result.huber_ranking, &gradient_result.huber_bootstrap, false, false);
+    let quantile95_top = make_top(&gradient_result.quantile95_ranking, &gradient_result.quantile95_bootstrap, false, false);
+    let mut ols_top = make_top(&gradient_result.ols_ranking, &BootstrapByEvent::new(), false, false);
+    for item in ols_top.iter_mut() {
+        if let Some(inf) = gradient_result.ols_inference_by_event.get(&item.event_name) {
+            item.std_error = Some(inf.std_error);
+            item.t_stat = Some(inf.t_stat);
+            item.p_value = Some(inf.p_value);
+        }
+    }
 
     let mut section = DbTimeGradientSection {
         settings: GradientSettings {
-            ridge_lambda,
-            elastic_net_lambda,
-            elastic_net_alpha,
+            ridge_lambda: gradient_result.regularization.ridge_lambda,
+            elastic_net_lambda: gradient_result.regularization.elastic_net_lambda,
+            elastic_net_alpha: gradient_result.regularization.elastic_net_alpha,
+            regularization_tuning: gradient_result.regularization.method.clone(),
+            ridge_validation_error: gradient_result.regularization.ridge_validation_error,
+            elastic_net_validation_error: gradient_result.regularization.elastic_net_validation_error,
             elastic_net_max_iter,
             elastic_net_tol,
+            bootstrap_resamples,
+            bootstrap_block_size: if bootstrap_resamples > 0 && bootstrap_block_size == 0 {
+                default_bootstrap_block_size(expected_len - 1)
+            } else {
+                bootstrap_block_size
+            },
+            feature_lags: if expansion.is_enabled() { expansion.lags } else { 0 },
+            feature_interactions: expansion.is_enabled() && expansion.interactions,
+            expanded_features: gradient_result.expanded_features.clone(),
+            pruned_features: gradient_result.pruned_features.clone(),
             input_wait_event_unit: units_desc.to_string(),
             input_db_time_unit: "db_time_per_second".to_string(),
         },
@@ -1345,6 +2123,8 @@ pub fn build_db_time_gradient_section(
         elastic_net_top,
         huber_top,
         quantile95_top,
+        ols_top,
+        ols_fit: gradient_result.ols_fit.clone(),
         cross_model_classifications: Vec::new(),
         vif_diagnostics: Vec::new(),
         collinear_group_impacts: Vec::new(),
@@ -1387,6 +2167,236 @@ pub fn build_db_time_gradient_section(
     Ok(section)
 }
 
+/* =========================================================================================
+   Rolling gradient (sliding windows / change-point segments)
+   ========================================================================================= */
+
+/// Minimal number of snapshots in a rolling gradient window (3 deltas)
+const ROLLING_MIN_WINDOW: usize = 4;
+/// Number of predictors stored for each window
+const ROLLING_TOP_N: usize = 10;
+
+/// Sliding windows [begin, end) over n snapshots - the last window is aligned to the end of the series
+pub fn sliding_windows(n: usize, size: usize, step: usize) -> Vec<(usize, usize)> {
+    let size = size.max(ROLLING_MIN_WINDOW);
+    if n < size {
+        return vec![(0, n)];
+    }
+    let step = if step == 0 { (size / 2).max(1) } else { step };
+    let mut windows: Vec<(usize, usize)> = (0..=(n - size)).step_by(step).map(|b| (b, b + size)).collect();
+    if windows.last().is_none_or(|w| w.1 < n) {
+        windows.push((n - size, n));
+    }
+    windows
+}
+
+/// Segments between change points (indexes where the new segment begins)
+pub fn segment_windows(n: usize, change_points: &[usize]) -> Vec<(usize, usize)> {
+    let mut bounds: Vec<usize> = vec![0];
+    bounds.extend(change_points.iter().copied().filter(|cp| *cp > 0 && *cp < n));
+    bounds.push(n);
+    bounds.dedup();
+    bounds.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+/// Ridge gradient fitted separately in each window. Standardization, MAD/P90/P99 are computed inside the window,
+/// so impacts show how much each predictor mattered in that period. Ridge penalty is scaled to the window size
+/// to keep the same strength relative to X'X as for the whole range. Windows are processed in parallel.
+pub fn compute_rolling_gradient(
+    target_series: &[f64],
+    event_series: &EventSeriesMap,
+    windows: &[(usize, usize)],
+    snaps: &[(u64, String)],
+    ridge_lambda: f64,
+) -> Vec<RollingGradientWindow> {
+    let n = target_series.len();
+    windows.par_iter()
+        .filter(|(b, e)| e - b >= ROLLING_MIN_WINDOW && *e <= n && *e <= snaps.len())
+        .filter_map(|&(b, e)| {
+            let y_delta = compute_time_deltas(&target_series[b..e]);
+            let window_series: EventSeriesMap = event_series.iter()
+                .filter(|(_, v)| v.len() == n)
+                .map(|(k, v)| (k.clone(), v[b..e].to_vec()))
+                .collect();
+            let x_delta = compute_event_deltas(&window_series).ok()?;
+            let lambda = ridge_lambda * (e - b) as f64 / n.max(1) as f64;
+
+            Some(RollingGradientWindow {
+                begin_snap_id: snaps[b].0,
+                begin_snap_date: snaps[b].1.clone(),
+                end_snap_id: snaps[e - 1].0,
+                end_snap_date: snaps[e - 1].1.clone(),
+                top_items: ridge_top_items(&x_delta, &y_delta, lambda)?,
+            })
+        })
+        .collect()
+}
+
+/// Ridge fit on a subset of deltas - standardization and MAD/P90/P99 are computed on this subset only.
+/// Returns predictors with positive gradient, strongest first.
+fn ridge_top_items(x_delta: &EventSeriesMap, y_delta_raw: &[f64], lambda: f64) -> Option<Vec<GradientTopItem>> {
+    let y_mean = y_delta_raw.iter().sum::<f64>() / y_delta_raw.len() as f64;
+    let y_delta: Vec<f64> = y_delta_raw.iter().map(|v| v - y_mean).collect();
+    let x_mean = compute_mean_by_event(x_delta);
+    let x_std = compute_std_by_event(x_delta, &x_mean);
+    let x_standardized = standardize_by_event(x_delta, &x_mean, &x_std);
+
+    let coef_by_event = ridge_regression_map(&x_standardized, &y_delta, lambda).ok()?;
+    let ranking = build_ranking(
+        &coef_by_event,
+        &compute_mad_by_event(x_delta),
+        &compute_abs_percentile_by_event(x_delta, 0.90),
+        &compute_abs_percentile_by_event(x_delta, 0.99),
+    );
+    Some(ranking.iter()
+        .filter(|x| x.gradient_coef > 0.0)
+        .take(ROLLING_TOP_N)
+        .map(|x| GradientTopItem {
+            event_name: x.event_name.clone(),
+            gradient_coef: x.gradient_coef,
+            impact: x.impact,
+            impact_active: x.impact_active,
+            impact_peak: x.impact_peak,
+            impact_share: x.impact_share,
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// Ridge gradient fitted separately for each workload regime. Regime snapshots are usually not consecutive,
+/// so deltas are computed over the whole range and only deltas with both snapshots in the regime are kept.
+/// `labels` holds the regime of each snapshot (None when unknown); returns top items for regimes 0..num_regimes.
+pub fn compute_regime_gradient(
+    target_series: &[f64],
+    event_series: &EventSeriesMap,
+    labels: &[Option<usize>],
+    num_regimes: usize,
+    ridge_lambda: f64,
+) -> Vec<Vec<GradientTopItem>> {
+    let n = target_series.len();
+    if n < 2 || labels.len() != n {
+        return vec![Vec::new(); num_regimes];
+    }
+    let y_delta_all = compute_time_deltas(target_series);
+    let full_series: EventSeriesMap = event_series.iter()
+        .filter(|(_, v)| v.len() == n)
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    let Ok(x_delta_all) = compute_event_deltas(&full_series) else {
+        return vec![Vec::new(); num_regimes];
+    };
+
+    (0..num_regimes).into_par_iter().map(|r| {
+        let rows: Vec<usize> = (0..n - 1).filter(|t| labels[*t] == Some(r) && labels[t + 1] == Some(r)).collect();
+        if rows.len() + 1 < ROLLING_MIN_WINDOW {
+            return Vec::new();
+        }
+        let y_delta: Vec<f64> = rows.iter().map(|t| y_delta_all[*t]).collect();
+        let x_delta: EventSeriesMap = x_delta_all.iter()
+            .map(|(k, v)| (k.clone(), rows.iter().map(|t| v[*t]).collect()))
+            .collect();
+        let lambda = ridge_lambda * rows.len() as f64 / n.max(1) as f64;
+        ridge_top_items(&x_delta, &y_delta, lambda).unwrap_or_default()
+    }).collect()
+}
+
+/// Prints top drivers of each workload regime and returns HTML heatmap: predictors x regimes, share of active impact
+pub fn print_regime_gradient_heatmap(regimes: &[WorkloadRegime], logfile_name: &str, args: &Args) -> String {
+    let columns: Vec<(String, String, &[GradientTopItem])> = regimes.iter()
+        .filter(|r| !r.db_time_gradient_top.is_empty())
+        .map(|r| (r.label.clone(), format!("{} snapshots, hours {}", r.snapshots, r.dominant_hours), r.db_time_gradient_top.as_slice()))
+        .collect();
+    if columns.is_empty() {
+        return String::new();
+    }
+    make_notes!(logfile_name, args.quiet, 0, "{}", "\n-- Ridge gradient per workload regime (top 3 drivers) --\n".bold().bright_white());
+    let mut table = Table::new();
+    table.set_titles(Row::new(vec![
+        Cell::new("Regime").with_style(Attr::Bold),
+        Cell::new("Top drivers (share of active impact)").with_style(Attr::Bold),
+    ]));
+    for (label, _, items) in &columns {
+        let drivers: Vec<String> = items.iter()
+            .take(3)
+            .map(|i| format!("{} ({:.1}%)", i.event_name, i.impact_share * 100.0))
+            .collect();
+        table.add_row(Row::new(vec![Cell::new(label), Cell::new(&drivers.join(", "))]));
+    }
+    make_notes!(logfile_name, args.quiet, 0, "{}", table);
+    impact_share_heatmap("Ridge gradient per workload regime - share of active impact", &columns)
+}
+
+/// Prints the strongest drivers of each window and returns HTML heatmap: predictors x windows, share of active impact.
+pub fn print_rolling_gradient_heatmap(windows: &[RollingGradientWindow], logfile_name: &str, args: &Args) -> String {
+    if windows.is_empty() {
+        return String::new();
+    }
+    make_notes!(logfile_name, args.quiet, 0, "{}", "\n-- Rolling Ridge gradient (top 3 drivers in each window) --\n".bold().bright_white());
+    let mut table = Table::new();
+    table.set_titles(Row::new(vec![
+        Cell::new("Window").with_style(Attr::Bold),
+        Cell::new("Top drivers (share of active impact)").with_style(Attr::Bold),
+    ]));
+    for w in windows {
+        let drivers: Vec<String> = w.top_items.iter()
+            .take(3)
+            .map(|i| format!("{} ({:.1}%)", i.event_name, i.impact_share * 100.0))
+            .collect();
+        table.add_row(Row::new(vec![
+            Cell::new(&format!("{} - {}", w.begin_snap_id, w.end_snap_id)),
+            Cell::new(&drivers.join(", ")),
+        ]));
+    }
+    make_notes!(logfile_name, args.quiet, 0, "{}", table);
+
+    let columns: Vec<(String, String, &[GradientTopItem])> = windows.iter()
+        .map(|w| (format!("{}<br>{}", w.begin_snap_id, w.end_snap_id), format!("{} - {}", w.begin_snap_date, w.end_snap_date), w.top_items.as_slice()))
+        .collect();
+    impact_share_heatmap("Rolling Ridge gradient - share of active impact in each window", &columns)
+}
+
+/// HTML heatmap of share of active impact: predictors x columns (header, header tooltip, top items).
+/// Predictors are ordered by the number of columns in which they are among the drivers.
+fn impact_share_heatmap(title: &str, columns: &[(String, String, &[GradientTopItem])]) -> String {
+    let mut occurrences: BTreeMap<String, usize> = BTreeMap::new();
+    for (_, _, items) in columns {
+        for item in items.iter() {
+            *occurrences.entry(item.event_name.clone()).or_insert(0) += 1;
+        }
+    }
+    let mut predictors: Vec<(String, usize)> = occurrences.into_iter().collect();
+    predictors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    predictors.truncate(25);
+
+    let header: String = columns.iter()
+        .map(|(head, tooltip, _)| format!(r#"<th title="{}">{}</th>"#, tooltip, head))
+        .collect();
+    let rows: String = predictors.iter().map(|(name, _)| {
+        let cells: String = columns.iter().map(|(_, _, items)| {
+            match items.iter().find(|i| &i.event_name == name) {
+                Some(i) => format!(
+                    r#"<td style="background-color: rgba(99, 46, 79, {:.2}); color: {};" title="coef {:+.6}, active impact {:.6}">{:.1}%</td>"#,
+                    i.impact_share.clamp(0.0, 1.0),
+                    if i.impact_share > 0.5 { "white" } else { "black" },
+                    i.gradient_coef, i.impact_active, i.impact_share * 100.0
+                ),
+                None => "<td>-</td>".to_string(),
+            }
+        }).collect();
+        format!("<tr><td>{}</td>{}</tr>\n", name, cells)
+    }).collect();
+
+    format!(
+        r#"<div class="cross-model"><p>{}</p>
+        <table>
+            <thead><tr><th>Wait Event/Statistic</th>{}</tr></thead>
+            <tbody>
+{}            </tbody>
+        </table></div>"#,
+        title, header, rows
+    )
+}
+
 /* =========================================================================================
    Print functions
    ========================================================================================= */
@@ -1403,8 +2413,22 @@ pub fn print_db_time_gradient_tables(section: &DbTimeGradientSection, print_sett
         settings_table.add_row(Row::new(vec![Cell::new("ridge_lambda"), Cell::new(&format!("{:.6}", section.settings.ridge_lambda))]));
         settings_table.add_row(Row::new(vec![Cell::new("elastic_net_lambda"), Cell::new(&format!("{:.6}", section.settings.elastic_net_lambda))]));
         settings_table.add_row(Row::new(vec![Cell::new("elastic_net_alpha"), Cell::new(&format!("{:.6}", section.settings.elastic_net_alpha))]));
+        settings_table.add_row(Row::new(vec![Cell::new("regularization_tuning"), Cell::new(&section.settings.regularization_tuning)]));
+        if let Some(err) = section.settings.ridge_validation_error {
+            settings_table.add_row(Row::new(vec![Cell::new("ridge_validation_error"), Cell::new(&format!("{:.6e}", err))]));
+        }
+        if let Some(err) = section.settings.elastic_net_validation_error {
+            settings_table.add_row(Row::new(vec![Cell::new("elastic_net_validation_error"), Cell::new(&format!("{:.6e}", err))]));
+        }
         settings_table.add_row(Row::new(vec![Cell::new("elastic_net_max_iter"), Cell::new(&format!("{}", section.settings.elastic_net_max_iter))]));
         settings_table.add_row(Row::new(vec![Cell::new("elastic_net_tol"), Cell::new(&format!("{:.6e}", section.settings.elastic_net_tol))]));
+        settings_table.add_row(Row::new(vec![Cell::new("bootstrap_resamples"), Cell::new(&format!("{}", section.settings.bootstrap_resamples))]));
+        settings_table.add_row(Row::new(vec![Cell::new("bootstrap_block_size"), Cell::new(&format!("{}", section.settings.bootstrap_block_size))]));
+        if section.settings.feature_lags > 0 || section.settings.feature_interactions {
+            settings_table.add_row(Row::new(vec![Cell::new("feature_lags"), Cell::new(&format!("{}", section.settings.feature_lags))]));
+            settings_table.add_row(Row::new(vec![Cell::new("feature_interactions"), Cell::new(&format!("{}", section.settings.feature_interactions))]));
+            settings_table.add_row(Row::new(vec![Cell::new("expanded_features"), Cell::new(&format!("{} kept, {} pruned by VIF", section.settings.expanded_features.len(), section.settings.pruned_features.len()))]));
+        }
         settings_table.add_row(Row::new(vec![Cell::new("input_event_unit"), Cell::new(&section.settings.input_wait_event_unit)]));
         settings_table.add_row(Row::new(vec![Cell::new("input_db_time_unit"), Cell::new(&section.settings.input_db_time_unit)]));
         make_notes!(logfile_name, args.quiet, 0, "{}", "\n-- Settings --".bold().bright_white());
@@ -1440,6 +2464,26 @@ pub fn print_db_time_gradient_tables(section: &DbTimeGradientSection, print_sett
     let q95_html = print_top_items_table("Quantile95", &section.quantile95_top, logfile_name, args);
     gradient_html += &format!(r#"<div>{}</div>"#, q95_html);
 
+    //OLS with HAC standard errors
+    make_notes!(logfile_name, args.quiet, 0, "{}", "\n-- OLS TOP (Newey-West standard errors) --\n".bold().bright_white());
+    match &section.ols_fit {
+        Some(fit) => {
+            make_notes!(logfile_name, args.quiet, 0, "{}",
+                format!("R² = {:.4}, adjusted R² = {:.4}, predictors = {} (dropped by VIF: {}), df = {}, HAC lags = {}\n\
+                         Predictors selected on the first {} Δ-snapshots, OLS and p-values from the remaining ones only\n",
+                    fit.r_squared, fit.adj_r_squared, fit.num_predictors, fit.dropped_by_vif.len(), fit.degrees_of_freedom, fit.hac_lags,
+                    fit.selection_snapshots).bright_black());
+            let ols_html = print_top_items_table(
+                &format!("OLS (adj. R² = {:.3})", fit.adj_r_squared),
+                &section.ols_top, logfile_name, args,
+            );
+            gradient_html += &format!(r#"<div>{}</div>"#, ols_html);
+        }
+        None => {
+            make_notes!(logfile_name, args.quiet, 0, "{}", "OLS could not be fitted for this section.\n".yellow());
+        }
+    }
+
     gradient_html.push_str("</div>");
     //Cross-model triangulation
     if !section.cross_model_classifications.is_empty() {
@@ -1517,18 +2561,35 @@ pub fn print_top_items_table(
     logfile_name: &str,
     args: &Args,
 ) -> String {
+    let with_ci = items.iter().any(|i| i.coef_ci_low.is_some());
+    let with_selection = items.iter().any(|i| i.selection_frequency.is_some());
+    let with_inference = items.iter().any(|i| i.std_error.is_some());
+    let fmt_ci = |low: Option<f64>, high: Option<f64>| -> String {
+        match (low, high) {
+            (Some(l), Some(h)) => format!("[{:+.6}, {:+.6}]", l, h),
+            _ => "-".to_string(),
+        }
+    };
+
+    let mut headers: Vec<&str> = vec!["#", "Wait Event/Statistic", "Coef", "Active Impact (P90)",
+                                      "Peak Impact (P99)", "Share %", "Typical Impact (MAD)"];
+    if with_ci {
+        headers.push("Coef 95% CI");
+        headers.push("Active Impact 95% CI");
+    }
+    if with_selection {
+        headers.push("Selected %");
+    }
+    if with_inference {
+        headers.push("Std Err (HAC)");
+        headers.push("t");
+        headers.push("p-value");
+    }
+
     let mut table = Table::new();
-    table.set_titles(Row::new(vec![
-        Cell::new("#").with_style(Attr::Bold),
-        Cell::new("Wait Event/Statistic").with_style(Attr::Bold),
-        Cell::new("Coef").with_style(Attr::Bold),
-        Cell::new("Active Impact (P90)").with_style(Attr::Bold),
-        Cell::new("Peak Impact (P99)").with_style(Attr::Bold),
-        Cell::new("Share %").with_style(Attr::Bold),
-        Cell::new("Typical Impact (MAD)").with_style(Attr::Bold),
-    ]));
+    table.set_titles(Row::new(headers.iter().map(|h| Cell::new(h).with_style(Attr::Bold)).collect()));
     for (idx, item) in items.iter().enumerate() {
-        table.add_row(Row::new(vec![
+        let mut cells = vec![
             Cell::new(&format!("{}", idx + 1)),
             Cell::new(&item.event_name),
             Cell::new(&format!("{} {:+.6}",
@@ -1539,7 +2600,20 @@ pub fn print_top_items_table(
             Cell::new(&format!("{:.6}", item.impact_peak)),
             Cell::new(&format!("{:.1}%", item.impact_share * 100.0)),
             Cell::new(&format!("{:.6}", item.impact)),
-        ]));
+        ];
+        if with_ci {
+            cells.push(Cell::new(&fmt_ci(item.coef_ci_low, item.coef_ci_high)));
+            cells.push(Cell::new(&fmt_ci(item.impact_active_ci_low, item.impact_active_ci_high)));
+        }
+        if with_selection {
+            cells.push(Cell::new(&item.selection_frequency.map_or("-".to_string(), |f| format!("{:.1}%", f * 100.0))));
+        }
+        if with_inference {
+            cells.push(Cell::new(&item.std_error.map_or("-".to_string(), |v| format!("{:.6}", v))));
+            cells.push(Cell::new(&item.t_stat.map_or("-".to_string(), |v| format!("{:+.2}", v))));
+            cells.push(Cell::new(&item.p_value.map_or("-".to_string(), |v| format!("{:.4}", v))));
+        }
+        table.add_row(Row::new(cells));
     }
     make_notes!(logfile_name, args.quiet, 0, "{}",
         format!("{} table (Top {})\n", title, items.len()).bright_black());
@@ -1549,8 +2623,7 @@ pub fn print_top_items_table(
     let mut html = table_to_html_string(
         &table,
         title,
-        &["#", "Wait Event/Statistic", "Coef", "Active Impact (P90)",
-          "Peak Impact (P99)", "Share %", "Typical Impact (MAD)"],
+        &headers,
     );
     html = format!(r#"<div>{html}</div>"#);
     html
@@ -1588,6 +2661,10 @@ pub fn run_gradient_section(
         elastic_net_alpha,
         elastic_net_max_iter,
         elastic_net_tol,
+        args.gradient_bootstrap,
+        args.bootstrap_block_size,
+        &args.gradient_tuning,
+        &FeatureExpansion::from_args(args),
         &spec.label,
     ) {
         Ok(section) => {
@@ -1830,4 +2907,26 @@ pub fn build_gradient_html(
 </html>
 "#
     )
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vif_pruning_drops_one_of_two_collinear_interactions() {
+        let n = 60;
+        let a: Vec<f64> = (0..n).map(|t| ((t * 7) % 11) as f64 - 5.0).collect();
+        let b: Vec<f64> = (0..n).map(|t| ((t * 5) % 13) as f64 - 6.0).collect();
+        let c: Vec<f64> = b.iter().enumerate().map(|(t, v)| v + if t % 2 == 0 { 0.01 } else { -0.01 }).collect();
+        let y: Vec<f64> = (0..n).map(|t| a[t] + b[t] + c[t]).collect();
+        let x: EventSeriesMap = [("A", a), ("B", b), ("C", c)].into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+        let expansion = FeatureExpansion { lags: 0, interactions: true, top_k: 3, vif_max: 10.0 };
+
+        let (expanded, added, pruned) = expand_features(x, &y, &expansion);
+        let pair = |x: &str, y: &str| [interaction_feature_name(x, y), interaction_feature_name(y, x)];
+        let with_a: Vec<String> = pair("A", "B").into_iter().chain(pair("A", "C")).collect();
+        assert_eq!(added.iter().filter(|f| with_a.contains(f)).count(), 1, "added: {:?} pruned: {:?}", added, pruned);
+        assert_eq!(pruned.iter().filter(|f| with_a.contains(f)).count(), 1);
+        assert!(expanded.contains_key("B") && expanded.contains_key("C"));
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use crate::analyze::{TrackedStat, TrackedStatKey, tracked_stats_specs, metric_series};
+use crate::awr::AWR;
+use crate::Args;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, TimeOfDayProfile};
+use colored::*;
+use plotly::{HeatMap, Plot};
+use plotly::common::{ColorScale, ColorScalePalette};
+use plotly::layout::{Axis, Layout};
+use prettytable::{Table, Row, Cell};
+
+/* Calendar heatmaps.
+   Every snapshot is placed in the hour of its begin time, so hour of day x day of week shows the weekly rhythm of a metric
+   (mean of all snapshots in the cell) and date x hour shows every day separately. Metrics are DB Time, AAS, the top
+   foreground wait events and SQL_IDs (--heatmaps-top) and any metric given with --heatmaps as FAMILY:name. */
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Series of a heatmap metric aligned with snapshots in snap_range. DB_TIME, AAS and TRACKED:<display name> are resolved
+/// here, the other families (EVENT, BGEVENT, STAT, LP, TM, IO, SQL) like gradient targets.
+fn heatmap_series(awrs: &[AWR], snaps: &[&AWR], snap_range: &(u64,u64), tracked_stats: &HashMap<TrackedStatKey, TrackedStat>, metric: &str) -> Result<Vec<f64>, String> {
+    let (family, name) = metric.split_once(':').map_or((metric.trim().to_uppercase(), ""), |(f, n)| (f.trim().to_uppercase(), n.trim()));
+    match family.as_str() {
+        "DB_TIME" => Ok(snaps.iter()
+            .map(|awr| db_time_s(awr))
+            .collect()),
+        "AAS" => Ok(snaps.iter()
+            .map(|awr| db_time_per_s(awr))
+            .collect()),
+        "TRACKED" => {
+            let spec = tracked_stats_specs().iter().find(|s| s.display_name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("unknown tracked stat '{}'", name))?;
+            let values = tracked_stats.get(&spec.key).map(|ts| ts.values.clone()).unwrap_or_default();
+            if values.len() != snaps.len() {
+                return Err(format!("tracked stat '{}' has {} values for {} snapshots", name, values.len(), snaps.len()));
+            }
+            Ok(values)
+        }
+        _ => metric_series(awrs, snap_range, metric),
+    }
+}
+
+/// Metrics plotted automatically - DB Time, AAS, top foreground wait events and top SQL_IDs by elapsed time
+fn top_metrics(report_for_ai: &ReportForAI, top: usize) -> Vec<String> {
+    if top == 0 {
+        return Vec::new();
+    }
+    let mut metrics = vec!["DB_TIME".to_string(), "AAS".to_string()];
+    metrics.extend(report_for_ai.top_foreground_wait_events.iter().take(top).map(|e| format!("EVENT:{}", e.event_name)));
+    metrics.extend(report_for_ai.top_sqls_by_elapsed_time.iter().take(top).map(|s| format!("SQL:{}", s.sql_id)));
+    metrics
+}
+
+/// Hour of day x day of week and date x hour heatmaps of DB Time, AAS, top wait events, top SQL_IDs and metrics from --heatmaps.
+/// Writes stats/heatmaps.html and attaches time of day profiles to ReportForAI. Returns true if the page was written.
+pub fn report_heatmaps(awrs: &[AWR], snap_range: &(u64,u64), tracked_stats: &HashMap<TrackedStatKey, TrackedStat>, args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) -> bool {
+    let mut metrics = top_metrics(report_for_ai, args.heatmaps_top);
+    for m in args.heatmaps.split(',').map(|m| m.trim()).filter(|m| !m.is_empty()) {
+        if !metrics.iter().any(|x| x.eq_ignore_ascii_case(m)) {
+            metrics.push(m.to_string());
+        }
+    }
+    if metrics.is_empty() {
+        return false;
+    }
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{}\n", "Time of day and day of week profiles".yellow());
+
+    let snaps: Vec<&AWR> = snaps_in_range(awrs, snap_range)
+        .collect();
+    let times: Vec<Option<NaiveDateTime>> = snaps.iter().map(|awr| parse_snap_time(&awr.snap_info.begin_snap_time)).collect();
+    if times.iter().all(|t| t.is_none()) {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tSnapshot times can't be parsed - heatmaps skipped\n".green().italic());
+        return false;
+    }
+    let mut days: Vec<String> = times.iter().flatten().map(|t| t.format("%Y-%m-%d (%a)").to_string()).collect();
+    days.sort();
+    days.dedup();
+    days.reverse(); // first day on top
+    let hours: Vec<String> = (0..24).map(|h| format!("{:02}:00", h)).collect();
+
+    let headers = ["Metric", "Peak Cell", "Peak Value", "Busiest Hour", "Quietest Hour", "Busiest Weekday", "Peak / Median"];
+    let mut table = Table::new();
+    table.set_titles(Row::new(headers.iter().map(|h| Cell::new(h)).collect()));
+    let mut profiles: Vec<TimeOfDayProfile> = Vec::new();
+    let mut plots: Vec<Plot> = Vec::new();
+    let mut skipped = String::new();
+    for metric in &metrics {
+        let values = match heatmap_series(awrs, &snaps, snap_range, tracked_stats, metric) {
+            Ok(v) => v,
+            Err(e) => {
+                skipped.push_str(&format!("\t{} - skipped: {}\n", metric, e));
+                continue;
+            }
+        };
+
+        // (weekday, hour) and (day, hour) cells - mean of snapshots starting in the cell, rows from Sunday so Monday is on top
+        let mut week_cells: BTreeMap<(usize, usize), Vec<f64>> = BTreeMap::new();
+        let mut day_cells: BTreeMap<(String, usize), Vec<f64>> = BTreeMap::new();
+        for (t, v) in times.iter().zip(values.iter()) {
+            let Some(t) = t else { continue; };
+            let hour = t.hour() as usize;
+            week_cells.entry((t.weekday().num_days_from_monday() as usize, hour)).or_default().push(*v);
+            day_cells.entry((t.format("%Y-%m-%d (%a)").to_string(), hour)).or_default().push(*v);
+        }
+        let cell_mean = |v: &Vec<f64>| v.iter().sum::<f64>() / v.len() as f64;
+        let week_z: Vec<Vec<Option<f64>>> = (0..7).rev()
+            .map(|d| (0..24).map(|h| week_cells.get(&(d, h)).map(cell_mean)).collect())
+            .collect();
+        let day_z: Vec<Vec<Option<f64>>> = days.iter()
+            .map(|d| (0..24).map(|h| day_cells.get(&(d.clone(), h)).map(cell_mean)).collect())
+            .collect();
+
+        let means: Vec<((usize, usize), f64)> = week_cells.iter().map(|(k, v)| (*k, cell_mean(v))).collect();
+        let Some(((peak_day, peak_hour), peak_value)) = means.iter().cloned().max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal)) else { continue; };
+        let by_hour: Vec<(usize, f64)> = (0..24)
+            .filter_map(|h| {
+                let v: Vec<f64> = week_cells.iter().filter(|((_, ch), _)| *ch == h).flat_map(|(_, v)| v.iter().cloned()).collect();
+                if v.is_empty() { None } else { Some((h, cell_mean(&v))) }
+            })
+            .collect();
+        let by_weekday: Vec<(usize, f64)> = (0..7)
+            .filter_map(|d| {
+                let v: Vec<f64> = week_cells.iter().filter(|((cd, _), _)| *cd == d).flat_map(|(_, v)| v.iter().cloned()).collect();
+                if v.is_empty() { None } else { Some((d, cell_mean(&v))) }
+            })
+            .collect();
+        let cmp = |a: &&(usize, f64), b: &&(usize, f64)| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal);
+        let busiest_hour = by_hour.iter().max_by(cmp).map_or(0, |(h, _)| *h);
+        let quietest_hour = by_hour.iter().min_by(cmp).map_or(0, |(h, _)| *h);
+        let busiest_weekday = by_weekday.iter().max_by(cmp).map_or(0, |(d, _)| *d);
+        let med = median(&means.iter().map(|(_, v)| *v).collect::<Vec<f64>>());
+        let profile = TimeOfDayProfile {
+            metric: metric.clone(),
+            peak_weekday: WEEKDAYS[peak_day].to_string(),
+            peak_hour,
+            peak_value,
+            busiest_hour,
+            quietest_hour,
+            busiest_weekday: WEEKDAYS[busiest_weekday].to_string(),
+            peak_to_median: if med > 0.0 { Some(peak_value / med) } else { None },
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(&profile.metric),
+            Cell::new(&format!("{} {:02}:00", profile.peak_weekday, profile.peak_hour)),
+            Cell::new(&format!("{:.2}", profile.peak_value)),
+            Cell::new(&format!("{:02}:00", profile.busiest_hour)),
+            Cell::new(&format!("{:02}:00", profile.quietest_hour)),
+            Cell::new(&profile.busiest_weekday),
+            Cell::new(&profile.peak_to_median.map_or("-".to_string(), |r| format!("{:.2}", r))),
+        ]));
+        profiles.push(profile);
+
+        let mut week_plot = Plot::new();
+        week_plot.add_trace(HeatMap::new(hours.clone(), WEEKDAYS.iter().rev().map(|d| d.to_string()).collect::<Vec<String>>(), week_z)
+            .hover_on_gaps(false)
+            .color_scale(ColorScale::Palette(ColorScalePalette::YlOrRd))
+            .reverse_scale(true)
+            .name(metric.as_str()));
+        week_plot.set_layout(Layout::new()
+            .title(format!("{} - hour of day x day of week (mean per snapshot)", metric))
+            .height(450)
+            .x_axis(Axis::new().title("Hour of day")));
+        plots.push(week_plot);
+
+        let mut day_plot = Plot::new();
+        day_plot.add_trace(HeatMap::new(hours.clone(), days.clone(), day_z)
+            .hover_on_gaps(false)
+            .color_scale(ColorScale::Palette(ColorScalePalette::YlOrRd))
+            .reverse_scale(true)
+            .name(metric.as_str()));
+        day_plot.set_layout(Layout::new()
+            .title(format!("{} - date x hour", metric))
+            .height((250 + 22 * days.len()).min(1600))
+            .x_axis(Axis::new().title("Hour of day")));
+        plots.push(day_plot);
+    }
+    if !skipped.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", skipped);
+    }
+    if plots.is_empty() {
+        return false;
+    }
+    make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&table));
+
+    let fname = format!("{}/stats/heatmaps.html", html_dir);
+    plots[0].write_html(Path::new(&fname));
+    let mut page_html = String::new();
+    for (i, plot) in plots.iter().enumerate().skip(1) {
+        page_html.push_str(&plot.to_inline_html(Some(&format!("heatmap-{}", i))));
+    }
+    page_html.push_str(&table_to_html_string(&table, "Time of day and day of week profiles", &headers));
+    if let Err(e) = append_html_before_body_end(&fname, &page_html) {
+        eprintln!("Error writing file {}: {}", fname, e);
+    }
+
+    report_for_ai.time_of_day_profiles = profiles;
+    true
+}
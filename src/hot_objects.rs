@@ -0,0 +1,216 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use crate::awr::AWR;
+use crate::Args;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, HotObject};
+use colored::*;
+use plotly::{Bar, Plot, Scatter};
+use plotly::common::{AxisSide, Marker, MarkerSymbol, Mode};
+use plotly::layout::{Axis, HoverMode, Layout, RangeMode};
+use prettytable::{Table, Row, Cell};
+
+/* Hot objects over time.
+   Segment Statistics sections of AWR list only top segments, so an object has a value only in snapshots
+   where it made it to the top list - for correlation a missing value counts as 0 (below the top list).
+   Objects are ranked by persistence (% of snapshots with the object in any top list) and by the highest
+   Pearson correlation of one of their segment statistics with DB Time. With SQL text (security level 2)
+   tables referenced by top SQLs are matched with object names, so every hot object lists SQL_IDs touching it.
+   Indexes are not matched - SQL text does not name them. */
+
+/// Number of objects taken from each ranking
+const HOT_OBJECTS_MAX: usize = 20;
+/// Object has to be in top list of a statistic in this many snapshots to correlate the statistic with DB Time
+const MIN_SNAPSHOTS_FOR_CORRELATION: usize = 3;
+/// Number of hot objects plotted for each segment statistic
+const PLOT_OBJECTS_PER_STATISTIC: usize = 10;
+
+type ObjectKey = (u64, u64, String, String);
+
+struct ObjectSeries {
+    label: String,
+    present: Vec<bool>,
+    values: BTreeMap<String, Vec<f64>>, // segment statistic -> value per snapshot, NaN when not in top list
+}
+
+fn object_label(key: &ObjectKey) -> String {
+    let (obj, objd, name, object_type) = key;
+    if name != "#" {
+        format!("{} ({})", name, object_type)
+    } else {
+        format!("{} {}/{}", object_type, obj, objd)
+    }
+}
+
+/// Top SQLs by elapsed time referencing a table, key is upper case table name without schema
+fn sqls_by_table(report_for_ai: &ReportForAI, sql_text: &HashMap<String, String>) -> HashMap<String, Vec<String>> {
+    let mut by_table: HashMap<String, Vec<String>> = HashMap::new();
+    for sql in &report_for_ai.top_sqls_by_elapsed_time {
+        let Some(text) = sql_text.get(&sql.sql_id) else { continue; };
+        for table in extract_tables_from_sql(text) {
+            let base = table.rsplit('.').next().unwrap_or(&table).to_string();
+            let sql_ids = by_table.entry(base).or_default();
+            if !sql_ids.contains(&sql.sql_id) {
+                sql_ids.push(sql.sql_id.clone());
+            }
+        }
+    }
+    by_table
+}
+
+/// Per-object time series of segment statistics ranked by persistence and correlation with DB Time.
+/// Writes stats/hot_objects.html and attaches hot objects to ReportForAI. Returns true if the page was written.
+pub fn report_hot_objects(awrs: &[AWR], snap_range: &(u64,u64), sql_text: &HashMap<String, String>, args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) -> bool {
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{}\n", "Hot objects - persistence in Segment Statistics and correlation with DB Time".yellow());
+
+    let snaps: Vec<&AWR> = snaps_in_range(awrs, snap_range)
+        .collect();
+    let n = snaps.len();
+
+    let mut objects: BTreeMap<ObjectKey, ObjectSeries> = BTreeMap::new();
+    for (i, awr) in snaps.iter().enumerate() {
+        for (section, segments) in &awr.segment_stats {
+            for seg in segments {
+                let key: ObjectKey = (seg.obj, seg.objd, seg.object_name.clone(), seg.object_type.clone());
+                let series = objects.entry(key.clone()).or_insert_with(|| ObjectSeries {
+                    label: object_label(&key),
+                    present: vec![false; n],
+                    values: BTreeMap::new(),
+                });
+                series.present[i] = true;
+                let values = series.values.entry(section.clone()).or_insert_with(|| vec![f64::NAN; n]);
+                values[i] = if values[i].is_nan() { seg.stat_vlalue } else { values[i] + seg.stat_vlalue };
+            }
+        }
+    }
+    if objects.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo Segment Statistics found in reports\n".green().italic());
+        return false;
+    }
+
+    let db_time: Vec<f64> = snaps.iter()
+        .map(|awr| db_time_s(awr))
+        .collect();
+
+    // Persistence and the segment statistic with the highest correlation with DB Time
+    let mut hot: Vec<(ObjectKey, HotObject)> = objects.iter()
+        .map(|(key, series)| {
+            let snapshots_in_top = series.present.iter().filter(|p| **p).count();
+            let mut best: Option<(String, f64)> = None;
+            for (stat, values) in &series.values {
+                if n < 3 || values.iter().filter(|v| !v.is_nan()).count() < MIN_SNAPSHOTS_FOR_CORRELATION {
+                    continue;
+                }
+                let filled: Vec<f64> = values.iter().map(|v| if v.is_nan() { 0.0 } else { *v }).collect();
+                let r = pearson_correlation_2v(&filled, &db_time);
+                if r.is_finite() && best.as_ref().is_none_or(|(_, b)| r > *b) {
+                    best = Some((stat.clone(), r));
+                }
+            }
+            (key.clone(), HotObject {
+                object_name: key.2.clone(),
+                object_type: key.3.clone(),
+                object_id: key.0,
+                data_object_id: key.1,
+                snapshots_in_top,
+                persistence_pct: snapshots_in_top as f64 / n as f64 * 100.0,
+                statistics: series.values.keys().cloned().collect(),
+                most_correlated_statistic: best.as_ref().map(|(s, _)| s.clone()),
+                db_time_correlation: best.map(|(_, r)| r),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    hot.sort_by(|a, b| b.1.snapshots_in_top.cmp(&a.1.snapshots_in_top).then(a.0.cmp(&b.0)));
+    for (rank, (_, h)) in hot.iter_mut().enumerate() {
+        h.persistence_rank = rank + 1;
+    }
+    let mut by_corr: Vec<usize> = (0..hot.len()).filter(|i| hot[*i].1.db_time_correlation.is_some()).collect();
+    by_corr.sort_by(|a, b| hot[*b].1.db_time_correlation.partial_cmp(&hot[*a].1.db_time_correlation).unwrap_or(std::cmp::Ordering::Equal));
+    for (rank, i) in by_corr.iter().enumerate() {
+        hot[*i].1.correlation_rank = Some(rank + 1);
+    }
+    hot.retain(|(_, h)| h.persistence_rank <= HOT_OBJECTS_MAX || h.correlation_rank.is_some_and(|r| r <= HOT_OBJECTS_MAX));
+
+    // SQL linkage - only with SQL text gathered at security level 2
+    if args.security_level >= 2 && !sql_text.is_empty() {
+        let by_table = sqls_by_table(report_for_ai, sql_text);
+        for (_, h) in hot.iter_mut() {
+            if let Some(sql_ids) = by_table.get(&h.object_name.to_uppercase()) {
+                h.top_sql_ids = sql_ids.clone();
+            }
+        }
+    }
+
+    let headers = ["Object", "Obj#", "Dataobj#", "Snaps in Top", "Persistence %", "Persistence Rank", "Most Correlated Statistic", "DB Time r", "Correlation Rank", "Statistics", "Top SQL_IDs"];
+    let mut table = Table::new();
+    table.set_titles(Row::new(headers.iter().map(|h| Cell::new(h)).collect()));
+    for (key, h) in &hot {
+        table.add_row(Row::new(vec![
+            Cell::new(&objects[key].label),
+            Cell::new(&format!("{}", h.object_id)),
+            Cell::new(&format!("{}", h.data_object_id)),
+            Cell::new(&format!("{}", h.snapshots_in_top)),
+            Cell::new(&format!("{:.1}", h.persistence_pct)),
+            Cell::new(&format!("{}", h.persistence_rank)),
+            Cell::new(h.most_correlated_statistic.as_deref().unwrap_or("-")),
+            Cell::new(&h.db_time_correlation.map_or("-".to_string(), |r| format!("{:.3}", r))),
+            Cell::new(&h.correlation_rank.map_or("-".to_string(), |r| format!("{}", r))),
+            Cell::new(&h.statistics.join(", ")),
+            Cell::new(&h.top_sql_ids.join(", ")),
+        ]));
+    }
+    make_notes!(logfile_name, args.quiet, 0, "\tObjects in Segment Statistics: {}  hot objects (top {} by persistence or DB Time correlation): {}\n", objects.len(), HOT_OBJECTS_MAX, hot.len());
+    make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&table));
+
+    // Hot objects page - persistence and correlation, then time series of every segment statistic
+    let labels: Vec<String> = hot.iter().map(|(key, _)| objects[key].label.clone()).collect();
+    let mut plot = Plot::new();
+    plot.add_trace(Bar::new(labels.clone(), hot.iter().map(|(_, h)| h.persistence_pct).collect::<Vec<f64>>())
+        .name("Persistence %")
+        .marker(Marker::new().color("#1f77b4")));
+    plot.add_trace(Scatter::new(labels.clone(), hot.iter().map(|(_, h)| h.db_time_correlation.unwrap_or(f64::NAN)).collect::<Vec<f64>>())
+        .mode(Mode::Markers)
+        .name("DB Time correlation")
+        .text_array(hot.iter().map(|(_, h)| h.most_correlated_statistic.clone().unwrap_or_default()).collect::<Vec<String>>())
+        .marker(Marker::new().color("#d62728").size(10).symbol(MarkerSymbol::Diamond))
+        .y_axis("y2"));
+    plot.set_layout(Layout::new()
+        .title("Hot objects - persistence in top lists and correlation with DB Time")
+        .height(600)
+        .hover_mode(HoverMode::X)
+        .y_axis(Axis::new().title("% of snapshots in top lists").range_mode(RangeMode::ToZero))
+        .y_axis2(Axis::new().title("Pearson r with DB Time").overlaying("y").side(AxisSide::Right).range(vec![-1.0, 1.0])));
+
+    let fname = format!("{}/stats/hot_objects.html", html_dir);
+    plot.write_html(Path::new(&fname));
+
+    let x_vals: Vec<String> = snaps.iter().map(|awr| format!("{} ({})", awr.snap_info.begin_snap_time, awr.snap_info.begin_snap_id)).collect();
+    let statistics: BTreeSet<String> = hot.iter().flat_map(|(_, h)| h.statistics.iter().cloned()).collect();
+    let mut page_html = String::new();
+    for (si, stat) in statistics.iter().enumerate() {
+        let mut stat_plot = Plot::new();
+        for (key, _) in hot.iter().filter(|(_, h)| h.statistics.contains(stat)).take(PLOT_OBJECTS_PER_STATISTIC) {
+            stat_plot.add_trace(Scatter::new(x_vals.clone(), objects[key].values[stat].clone())
+                .mode(Mode::LinesMarkers)
+                .name(&objects[key].label));
+        }
+        stat_plot.set_layout(Layout::new()
+            .title(format!("Hot objects - {}", stat))
+            .height(450)
+            .hover_mode(HoverMode::Closest)
+            .y_axis(Axis::new().title(stat.as_str()).range_mode(RangeMode::ToZero)));
+        page_html.push_str(&stat_plot.to_inline_html(Some(&format!("hot-objects-{}", si))));
+    }
+    page_html.push_str(&table_to_html_string(&table, "Hot objects", &headers));
+    if let Err(e) = append_html_before_body_end(&fname, &page_html) {
+        eprintln!("Error writing file {}: {}", fname, e);
+    }
+
+    report_for_ai.hot_objects = hot.into_iter().map(|(_, h)| h).collect();
+    true
+}
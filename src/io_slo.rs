@@ -0,0 +1,276 @@
+use std::fs;
+use std::path::Path;
+use crate::awr::AWR;
+use crate::Args;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, IoSloCompliance, IoSloViolationPeriod};
+use colored::*;
+use plotly::{Plot, Scatter};
+use plotly::common::{DashType, Line, Mode};
+use plotly::layout::{Axis, HoverMode, Layout, RangeMode};
+use prettytable::{Table, Row, Cell};
+
+/* IO latency SLOs.
+   Every SLO from --io-slo is a latency limit in ms for an IO function from IO Stats by Function (LGWR, Buffer Cache Reads, ...)
+   or for a wait event (db file sequential read, log file parallel write, ...) - a target is an IO function when
+   any snapshot has an IO function of this name. A snapshot violates the SLO when its average latency is above the limit,
+   snapshots with less than MIN_WAITS waits are not evaluated. Wait event histograms tell how many waits were in buckets
+   entirely below the limit - an average above the limit with most waits below it points to outliers, not slow IO. */
+
+/// Snapshots with fewer waits are not evaluated
+const MIN_WAITS: u64 = 100;
+
+struct SloSnap {
+    x_val: String,
+    snap_id: u64,
+    snap_date: String,
+    latency_ms: Option<f64>,
+    waits_within_slo_pct: Option<f64>,
+}
+
+/// Parses --io-slo "db file sequential read=5,LGWR=2" into target -> limit in ms, keeping the order
+fn parse_slos(spec: &str) -> Vec<(String, f64)> {
+    let mut slos: Vec<(String, f64)> = Vec::new();
+    for item in spec.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let Some((target, value)) = item.rsplit_once('=') else {
+            eprintln!("Wrong IO SLO '{}' - expected TARGET=MS", item);
+            continue;
+        };
+        match value.trim().parse::<f64>() {
+            Ok(v) if v > 0.0 => slos.push((target.trim().to_string(), v)),
+            Ok(_) => eprintln!("Wrong IO SLO value '{}' - latency limit has to be above 0", value),
+            Err(e) => eprintln!("Wrong IO SLO value '{}': {}", value, e),
+        }
+    }
+    slos
+}
+
+/// Upper bound in ms of a wait event histogram bucket like "2: <4ms", None for the open bucket (>1s)
+fn bucket_upper_ms(bucket: &str) -> Option<f64> {
+    let label = bucket.split_once(':').map_or(bucket, |(_, l)| l).trim();
+    if label.starts_with('>') {
+        return None;
+    }
+    let label = label.trim_start_matches("<=").trim_start_matches('<');
+    if let Some(v) = label.strip_suffix("ms") {
+        v.trim().parse::<f64>().ok()
+    } else if let Some(v) = label.strip_suffix("us") {
+        v.trim().parse::<f64>().ok().map(|v| v / 1000.0)
+    } else if let Some(v) = label.strip_suffix('s') {
+        v.trim().parse::<f64>().ok().map(|v| v * 1000.0)
+    } else {
+        None
+    }
+}
+
+/// Average latency of a wait event (foreground first, then background) and % of waits in histogram buckets below the limit
+fn event_latency(awr: &AWR, event: &str, slo_ms: f64) -> (Option<f64>, Option<f64>) {
+    let Some(ev) = awr.foreground_wait_events.iter().chain(awr.background_wait_events.iter())
+        .find(|e| e.event.eq_ignore_ascii_case(event) && e.waits > 0) else {
+        return (None, None);
+    };
+    if ev.waits < MIN_WAITS {
+        return (None, None);
+    }
+    let within = if ev.waitevent_histogram_ms.is_empty() {
+        None
+    } else {
+        Some(ev.waitevent_histogram_ms.iter()
+            .filter(|(bucket, _)| bucket_upper_ms(bucket).is_some_and(|upper| upper <= slo_ms))
+            .map(|(_, pct)| *pct as f64)
+            .sum::<f64>())
+    };
+    (Some(ev.total_wait_time_s * 1000.0 / ev.waits as f64), within)
+}
+
+/// Longest run of consecutive violating snapshots, the one with higher average latency wins a tie
+fn worst_period(snaps: &[SloSnap], slo_ms: f64) -> Option<IoSloViolationPeriod> {
+    let mut best: Option<IoSloViolationPeriod> = None;
+    let mut i = 0;
+    while i < snaps.len() {
+        if !snaps[i].latency_ms.is_some_and(|l| l > slo_ms) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < snaps.len() && snaps[i].latency_ms.is_some_and(|l| l > slo_ms) {
+            i += 1;
+        }
+        let run: Vec<f64> = snaps[start..i].iter().filter_map(|s| s.latency_ms).collect();
+        let period = IoSloViolationPeriod {
+            begin_snap_id: snaps[start].snap_id,
+            end_snap_id: snaps[i - 1].snap_id,
+            begin_snap_date: snaps[start].snap_date.clone(),
+            snapshots: run.len(),
+            avg_latency_ms: run.iter().sum::<f64>() / run.len() as f64,
+            max_latency_ms: run.iter().cloned().fold(f64::MIN, f64::max),
+        };
+        if best.as_ref().is_none_or(|b| period.snapshots > b.snapshots || (period.snapshots == b.snapshots && period.avg_latency_ms > b.avg_latency_ms)) {
+            best = Some(period);
+        }
+    }
+    best
+}
+
+/// Evaluates IO latency SLOs (--io-slo) in every snapshot. Writes stats/io_slo.html and attaches
+/// the SLO compliance to ReportForAI. Returns true if the page was written.
+pub fn report_io_slo(awrs: &[AWR], snap_range: &(u64,u64), args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) -> bool {
+    let slos = parse_slos(&args.io_slo);
+    if slos.is_empty() {
+        return false;
+    }
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{}\n", "IO latency SLO compliance - IO functions and wait events".yellow());
+
+    let snaps: Vec<&AWR> = snaps_in_range(awrs, snap_range)
+        .collect();
+
+    let mut compliance: Vec<IoSloCompliance> = Vec::new();
+    let mut series: Vec<Vec<SloSnap>> = Vec::new(); // per evaluated SLO, same order as compliance
+    let mut not_evaluated = String::new();
+    for (target, slo_ms) in &slos {
+        let function = snaps.iter().flat_map(|awr| awr.io_stats_byfunc.keys()).find(|f| f.eq_ignore_ascii_case(target)).cloned();
+        let slo_snaps: Vec<SloSnap> = snaps.iter()
+            .map(|awr| {
+                let (latency_ms, waits_within_slo_pct) = match &function {
+                    Some(f) => (awr.io_stats_byfunc.get(f).filter(|io| io.waits_count >= MIN_WAITS).and_then(|io| io.avg_time), None),
+                    None => event_latency(awr, target, *slo_ms),
+                };
+                SloSnap {
+                    x_val: format!("{} ({})", awr.snap_info.begin_snap_time, awr.snap_info.begin_snap_id),
+                    snap_id: awr.snap_info.begin_snap_id,
+                    snap_date: awr.snap_info.begin_snap_time.clone(),
+                    latency_ms,
+                    waits_within_slo_pct,
+                }
+            })
+            .collect();
+
+        let latencies: Vec<f64> = slo_snaps.iter().filter_map(|s| s.latency_ms).collect();
+        if latencies.is_empty() {
+            not_evaluated.push_str(&format!("\t{} - no snapshot with at least {} waits, SLO not evaluated\n", target, MIN_WAITS).green().italic().to_string());
+            continue;
+        }
+        let violations = latencies.iter().filter(|l| **l > *slo_ms).count();
+        let worst = slo_snaps.iter()
+            .filter(|s| s.latency_ms.is_some())
+            .max_by(|a, b| a.latency_ms.partial_cmp(&b.latency_ms).unwrap_or(std::cmp::Ordering::Equal))
+            .unwrap();
+        let within: Vec<f64> = slo_snaps.iter().filter_map(|s| s.waits_within_slo_pct).collect();
+        compliance.push(IoSloCompliance {
+            target: function.clone().unwrap_or_else(|| target.clone()),
+            source: if function.is_some() { "IO_FUNCTION".to_string() } else { "WAIT_EVENT".to_string() },
+            slo_ms: *slo_ms,
+            snapshots_evaluated: latencies.len(),
+            violations,
+            compliance_pct: (latencies.len() - violations) as f64 / latencies.len() as f64 * 100.0,
+            median_latency_ms: median(&latencies),
+            p95_latency_ms: percentile(&latencies, 95.0),
+            worst_latency_ms: worst.latency_ms.unwrap_or(0.0),
+            worst_snap_id: worst.snap_id,
+            worst_snap_date: worst.snap_date.clone(),
+            worst_period: worst_period(&slo_snaps, *slo_ms),
+            waits_within_slo_pct: if within.is_empty() { None } else { Some(median(&within)) },
+        });
+        series.push(slo_snaps);
+    }
+    if !not_evaluated.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", not_evaluated);
+    }
+    if compliance.is_empty() {
+        return false;
+    }
+
+    let headers = ["Target", "Source", "SLO (ms)", "Snaps Evaluated", "Violations", "Compliance %", "Median (ms)", "P95 (ms)", "Worst (ms)", "Worst Snap", "Worst Period", "Waits within SLO %"];
+    let mut table = Table::new();
+    table.set_titles(Row::new(headers.iter().map(|h| Cell::new(h)).collect()));
+    for c in &compliance {
+        table.add_row(Row::new(vec![
+            Cell::new(&c.target),
+            Cell::new(&c.source),
+            Cell::new(&format!("{:.1}", c.slo_ms)),
+            Cell::new(&format!("{}", c.snapshots_evaluated)),
+            Cell::new(&format!("{}", c.violations)),
+            Cell::new(&format!("{:.1}", c.compliance_pct)),
+            Cell::new(&format!("{:.2}", c.median_latency_ms)),
+            Cell::new(&format!("{:.2}", c.p95_latency_ms)),
+            Cell::new(&format!("{:.2}", c.worst_latency_ms)),
+            Cell::new(&format!("{} ({})", c.worst_snap_id, c.worst_snap_date)),
+            Cell::new(&c.worst_period.as_ref().map_or("-".to_string(), |p| format!("{}-{}: {} snaps, avg {:.2} ms", p.begin_snap_id, p.end_snap_id, p.snapshots, p.avg_latency_ms))),
+            Cell::new(&c.waits_within_slo_pct.map_or("-".to_string(), |w| format!("{:.1}", w))),
+        ]));
+    }
+    make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&table));
+    let violated: String = compliance.iter().filter(|c| c.violations > 0)
+        .map(|c| format!("\t{} above {} ms in {} of {} snapshots, worst {:.2} ms at snap {}\n", c.target, c.slo_ms, c.violations, c.snapshots_evaluated, c.worst_latency_ms, c.worst_snap_id).red().to_string())
+        .collect();
+    if !violated.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", violated);
+    }
+
+    // IO SLO page - latency as % of its SLO (100% = limit), then % of waits within SLO from histograms
+    let mut plot = Plot::new();
+    let x_vals: Vec<String> = series[0].iter().map(|s| s.x_val.clone()).collect();
+    for (c, slo_snaps) in compliance.iter().zip(series.iter()) {
+        plot.add_trace(Scatter::new(x_vals.clone(), slo_snaps.iter().map(|s| s.latency_ms.map_or(f64::NAN, |l| l / c.slo_ms * 100.0)).collect::<Vec<f64>>())
+            .mode(Mode::LinesMarkers)
+            .name(format!("{} (SLO {} ms)", c.target, c.slo_ms))
+            .text_array(slo_snaps.iter().map(|s| s.latency_ms.map_or(String::new(), |l| format!("{:.2} ms", l))).collect::<Vec<String>>()));
+    }
+    plot.add_trace(Scatter::new(x_vals.clone(), vec![100.0; x_vals.len()])
+        .mode(Mode::Lines)
+        .name("SLO limit")
+        .line(Line::new().color("#d62728").dash(DashType::Dash)));
+    plot.set_layout(Layout::new()
+        .title("IO latency as % of SLO (above 100% = violation)")
+        .height(600)
+        .hover_mode(HoverMode::X)
+        .y_axis(Axis::new().title("% of SLO").range_mode(RangeMode::ToZero)));
+
+    let fname = format!("{}/stats/io_slo.html", html_dir);
+    plot.write_html(Path::new(&fname));
+
+    let mut page_html = String::new();
+    if compliance.iter().any(|c| c.waits_within_slo_pct.is_some()) {
+        let mut hist_plot = Plot::new();
+        for (c, slo_snaps) in compliance.iter().zip(series.iter()).filter(|(c, _)| c.waits_within_slo_pct.is_some()) {
+            hist_plot.add_trace(Scatter::new(x_vals.clone(), slo_snaps.iter().map(|s| s.waits_within_slo_pct.unwrap_or(f64::NAN)).collect::<Vec<f64>>())
+                .mode(Mode::LinesMarkers)
+                .name(&c.target));
+        }
+        hist_plot.set_layout(Layout::new()
+            .title("% of waits in histogram buckets below SLO")
+            .height(450)
+            .hover_mode(HoverMode::X)
+            .y_axis(Axis::new().title("% of waits").range(vec![0.0, 100.0])));
+        page_html.push_str(&hist_plot.to_inline_html(Some("io-slo-histogram")));
+    }
+    page_html.push_str(&table_to_html_string(&table, "IO latency SLO compliance", &headers));
+    if let Err(e) = append_html_before_body_end(&fname, &page_html) {
+        eprintln!("Error writing file {}: {}", fname, e);
+    }
+
+    report_for_ai.io_slo_compliance = compliance;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_slos_keeps_valid_targets() {
+        let slos = parse_slos("LGWR=5, log file sync = 2.5, DBWR, Buffer Cache Reads=0, Direct Reads=x,,");
+        assert_eq!(slos, vec![("LGWR".to_string(), 5.0), ("log file sync".to_string(), 2.5)]);
+    }
+
+    #[test]
+    fn bucket_upper_ms_units() {
+        assert_eq!(bucket_upper_ms("2: <4ms"), Some(4.0));
+        assert_eq!(bucket_upper_ms("<=512us"), Some(0.512));
+        assert_eq!(bucket_upper_ms("<1s"), Some(1000.0));
+        assert_eq!(bucket_upper_ms("12: >1s"), None);
+        assert_eq!(bucket_upper_ms("bucket"), None);
+    }
+}
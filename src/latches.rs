@@ -0,0 +1,271 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use crate::awr::AWR;
+use crate::Args;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, LatchContention, MutexContention};
+use colored::*;
+use plotly::{Bar, Plot, Scatter};
+use plotly::common::{AxisSide, Marker, MarkerSymbol, Mode};
+use plotly::layout::{Axis, HoverMode, Layout, RangeMode};
+use prettytable::{Table, Row, Cell};
+
+/* Latch and mutex drill-down.
+   Latch Activity tells how often a latch is missed, but a latch costs DB Time only through its wait event.
+   Latches with their own event (latch: cache buffers chains, latch: shared pool, ...) get its time, the generic
+   latch free event is split between latches without own event by their sleeps (Latch Sleep Breakdown).
+   Latches are ranked by % of DB Time, so a latch with high get % miss and no waits is not reported as a problem.
+   Mutex Sleep Summary gives wait time per mutex type and code location, mutex wait events are split between
+   mutex types of the same family by their wait time. */
+
+/// Number of latches reported
+const LATCHES_MAX: usize = 20;
+/// Number of latches plotted over time
+const PLOT_LATCHES: usize = 5;
+/// Number of code locations reported for each mutex type
+const MUTEX_LOCATIONS: usize = 3;
+
+/// Family of mutex wait events for a mutex type from Mutex Sleep Summary
+fn mutex_family(mutex_type: &str) -> Option<&'static str> {
+    match mutex_type.to_lowercase().as_str() {
+        "library cache" => Some("library cache:"),
+        "cursor pin" => Some("cursor: pin"),
+        "cursor parent" | "cursor stat" => Some("cursor: mutex"),
+        "row cache" => Some("row cache mutex"),
+        _ => None,
+    }
+}
+
+fn is_mutex_event(event: &str, family: &str) -> bool {
+    event.starts_with(family) && (family != "library cache:" || event.contains("mutex"))
+}
+
+/// Latch and mutex contention ranked by contribution to DB Time. Writes stats/latches_mutexes.html and attaches
+/// latch_contention and mutex_contention to ReportForAI. Returns true if the page was written.
+pub fn report_latch_mutex_contention(awrs: &[AWR], snap_range: &(u64,u64), args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) -> bool {
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{}\n", "Latch and mutex contention - contribution to DB Time".yellow());
+
+    let snaps: Vec<&AWR> = snaps_in_range(awrs, snap_range)
+        .collect();
+    if snaps.iter().all(|awr| awr.latch_activity.is_empty() && awr.mutex_sleep_summary.is_empty()) {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo Latch Activity or Mutex Sleep Summary found in reports\n".green().italic());
+        return false;
+    }
+
+    let db_time_s: f64 = snaps.iter()
+        .map(|awr| db_time_s(awr))
+        .sum();
+    let mut event_time: HashMap<String, f64> = HashMap::new();
+    for awr in &snaps {
+        for e in &awr.foreground_wait_events {
+            *event_time.entry(e.event.clone()).or_insert(0.0) += e.total_wait_time_s;
+        }
+    }
+    let pct_of_db_time = |t: f64| if db_time_s > 0.0 { t / db_time_s * 100.0 } else { 0.0 };
+
+    // Latch Activity and Latch Sleep Breakdown summed over snapshots
+    let mut latches: BTreeMap<String, LatchContention> = BTreeMap::new();
+    let mut latch_series: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for (i, awr) in snaps.iter().enumerate() {
+        for la in &awr.latch_activity {
+            let l = latches.entry(la.statname.clone()).or_insert_with(|| LatchContention { latch_name: la.statname.clone(), ..Default::default() });
+            l.get_pct_miss += la.get_requests as f64 * la.get_pct_miss; // weighted, divided below
+            l.get_requests += la.get_requests;
+            l.wait_time_s += la.wait_time;
+            if la.wait_time > 0.0 {
+                l.snapshots_with_waits += 1;
+            }
+            latch_series.entry(la.statname.clone()).or_insert_with(|| vec![0.0; snaps.len()])[i] += la.wait_time;
+        }
+        for ls in &awr.latch_sleep_breakdown {
+            let l = latches.entry(ls.latch_name.clone()).or_insert_with(|| LatchContention { latch_name: ls.latch_name.clone(), ..Default::default() });
+            l.misses += ls.misses;
+            l.sleeps += ls.sleeps;
+            l.spin_gets_pct += ls.spin_gets as f64; // spin gets, divided below
+        }
+    }
+
+    // Latches without own wait event share latch free by sleeps (or by wait time without Latch Sleep Breakdown)
+    let latch_free_s = event_time.get("latch free").copied().unwrap_or(0.0);
+    let without_event: Vec<String> = latches.keys().filter(|n| !event_time.contains_key(&format!("latch: {}", n))).cloned().collect();
+    let sleeps_total: u64 = without_event.iter().map(|n| latches[n].sleeps).sum();
+    let wait_total: f64 = without_event.iter().map(|n| latches[n].wait_time_s).sum();
+    for l in latches.values_mut() {
+        if l.get_requests > 0 {
+            l.get_pct_miss /= l.get_requests as f64;
+        }
+        l.spin_gets_pct = if l.misses > 0 { l.spin_gets_pct / l.misses as f64 * 100.0 } else { 0.0 };
+        let own_event = format!("latch: {}", l.latch_name);
+        if let Some(t) = event_time.get(&own_event) {
+            l.wait_event = own_event;
+            l.event_wait_time_s = *t;
+        } else {
+            l.wait_event = "latch free".to_string();
+            l.event_wait_time_s = if sleeps_total > 0 {
+                latch_free_s * l.sleeps as f64 / sleeps_total as f64
+            } else if wait_total > 0.0 {
+                latch_free_s * l.wait_time_s / wait_total
+            } else {
+                0.0
+            };
+        }
+        l.pct_db_time = pct_of_db_time(l.event_wait_time_s);
+    }
+    let mut latch_contention: Vec<LatchContention> = latches.into_values()
+        .filter(|l| l.event_wait_time_s > 0.0 || l.wait_time_s > 0.0 || l.sleeps > 0)
+        .collect();
+    latch_contention.sort_by(|a, b| b.event_wait_time_s.partial_cmp(&a.event_wait_time_s).unwrap_or(std::cmp::Ordering::Equal)
+        .then(b.wait_time_s.partial_cmp(&a.wait_time_s).unwrap_or(std::cmp::Ordering::Equal)));
+    latch_contention.truncate(LATCHES_MAX);
+    for (i, l) in latch_contention.iter_mut().enumerate() {
+        l.rank = i + 1;
+    }
+
+    // Mutex Sleep Summary summed over snapshots, mutex wait events split within the family by wait time
+    let mut mutexes: BTreeMap<String, (u64, f64, HashMap<String, f64>)> = BTreeMap::new();
+    for awr in &snaps {
+        for ms in &awr.mutex_sleep_summary {
+            let m = mutexes.entry(ms.mutex_type.clone()).or_default();
+            m.0 += ms.sleeps;
+            m.1 += ms.wait_time_ms / 1000.0;
+            *m.2.entry(ms.location.clone()).or_insert(0.0) += ms.wait_time_ms / 1000.0;
+        }
+    }
+    let mut family_wait: HashMap<&str, f64> = HashMap::new();
+    for (mutex_type, (_, wait_s, _)) in &mutexes {
+        if let Some(family) = mutex_family(mutex_type) {
+            *family_wait.entry(family).or_insert(0.0) += wait_s;
+        }
+    }
+    let mut mutex_contention: Vec<MutexContention> = mutexes.into_iter()
+        .map(|(mutex_type, (sleeps, wait_time_s, locations))| {
+            let family = mutex_family(&mutex_type);
+            let mut wait_events: Vec<String> = family.map_or(Vec::new(), |f| event_time.keys().filter(|e| is_mutex_event(e, f)).cloned().collect());
+            wait_events.sort();
+            let family_events_s: f64 = wait_events.iter().map(|e| event_time[e]).sum();
+            let share = family.and_then(|f| family_wait.get(f)).map_or(0.0, |total| if *total > 0.0 { wait_time_s / total } else { 0.0 });
+            let mut locations: Vec<(String, f64)> = locations.into_iter().collect();
+            locations.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            MutexContention {
+                mutex_type,
+                wait_events,
+                sleeps,
+                wait_time_s,
+                event_wait_time_s: family_events_s * share,
+                pct_db_time: pct_of_db_time(family_events_s * share),
+                top_locations: locations.into_iter().take(MUTEX_LOCATIONS).map(|(l, t)| format!("{} ({:.1}s)", l, t)).collect(),
+            }
+        })
+        .collect();
+    mutex_contention.sort_by(|a, b| b.wait_time_s.partial_cmp(&a.wait_time_s).unwrap_or(std::cmp::Ordering::Equal));
+
+    let latch_headers = ["Rank", "Latch", "Wait Event", "Event Time (s)", "% DB Time", "Latch Wait Time (s)", "Get Requests", "Get % Miss", "Misses", "Sleeps", "Spin Gets %", "Snaps with Waits"];
+    let mut latch_table = Table::new();
+    latch_table.set_titles(Row::new(latch_headers.iter().map(|h| Cell::new(h)).collect()));
+    for l in &latch_contention {
+        latch_table.add_row(Row::new(vec![
+            Cell::new(&format!("{}", l.rank)),
+            Cell::new(&l.latch_name),
+            Cell::new(&l.wait_event),
+            Cell::new(&format!("{:.2}", l.event_wait_time_s)),
+            Cell::new(&format!("{:.3}", l.pct_db_time)),
+            Cell::new(&format!("{:.2}", l.wait_time_s)),
+            Cell::new(&format!("{}", l.get_requests)),
+            Cell::new(&format!("{:.3}", l.get_pct_miss)),
+            Cell::new(&format!("{}", l.misses)),
+            Cell::new(&format!("{}", l.sleeps)),
+            Cell::new(&format!("{:.1}", l.spin_gets_pct)),
+            Cell::new(&format!("{}", l.snapshots_with_waits)),
+        ]));
+    }
+    if latch_contention.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo latch waits found\n".green().italic());
+    } else {
+        make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&latch_table));
+    }
+
+    let mutex_headers = ["Mutex Type", "Wait Events", "Sleeps", "Wait Time (s)", "Event Time (s)", "% DB Time", "Top Locations"];
+    let mut mutex_table = Table::new();
+    mutex_table.set_titles(Row::new(mutex_headers.iter().map(|h| Cell::new(h)).collect()));
+    for m in &mutex_contention {
+        mutex_table.add_row(Row::new(vec![
+            Cell::new(&m.mutex_type),
+            Cell::new(&m.wait_events.join(", ")),
+            Cell::new(&format!("{}", m.sleeps)),
+            Cell::new(&format!("{:.2}", m.wait_time_s)),
+            Cell::new(&format!("{:.2}", m.event_wait_time_s)),
+            Cell::new(&format!("{:.3}", m.pct_db_time)),
+            Cell::new(&m.top_locations.join(", ")),
+        ]));
+    }
+    if mutex_contention.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo Mutex Sleep Summary found\n".green().italic());
+    } else {
+        make_notes!(logfile_name, args.quiet, 0, "\n\tMutex sleeps:\n");
+        make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&mutex_table));
+    }
+
+    // Latches and mutexes page - DB Time share vs get miss %, then latch wait time over snapshots
+    let names: Vec<String> = latch_contention.iter().map(|l| l.latch_name.clone()).collect();
+    let mut plot = Plot::new();
+    plot.add_trace(Bar::new(names.clone(), latch_contention.iter().map(|l| l.pct_db_time).collect::<Vec<f64>>())
+        .name("% DB Time")
+        .text_array(latch_contention.iter().map(|l| l.wait_event.clone()).collect::<Vec<String>>())
+        .marker(Marker::new().color("#1f77b4")));
+    plot.add_trace(Scatter::new(names.clone(), latch_contention.iter().map(|l| l.get_pct_miss).collect::<Vec<f64>>())
+        .mode(Mode::Markers)
+        .name("Get % miss")
+        .marker(Marker::new().color("#d62728").size(10).symbol(MarkerSymbol::Diamond))
+        .y_axis("y2"));
+    plot.set_layout(Layout::new()
+        .title("Latches - contribution to DB Time vs get % miss")
+        .height(600)
+        .hover_mode(HoverMode::X)
+        .y_axis(Axis::new().title("% DB Time").range_mode(RangeMode::ToZero))
+        .y_axis2(Axis::new().title("Get % miss").overlaying("y").side(AxisSide::Right).range_mode(RangeMode::ToZero)));
+
+    let fname = format!("{}/stats/latches_mutexes.html", html_dir);
+    plot.write_html(Path::new(&fname));
+
+    let mut page_html = String::new();
+    if !mutex_contention.is_empty() {
+        let mut mutex_plot = Plot::new();
+        let types: Vec<String> = mutex_contention.iter().map(|m| m.mutex_type.clone()).collect();
+        mutex_plot.add_trace(Bar::new(types.clone(), mutex_contention.iter().map(|m| m.wait_time_s).collect::<Vec<f64>>())
+            .name("Mutex wait time (s)")
+            .marker(Marker::new().color("#9467bd")));
+        mutex_plot.add_trace(Bar::new(types, mutex_contention.iter().map(|m| m.event_wait_time_s).collect::<Vec<f64>>())
+            .name("Wait event time (s)")
+            .marker(Marker::new().color("#ff7f0e")));
+        mutex_plot.set_layout(Layout::new()
+            .title("Mutex sleeps by mutex type")
+            .height(450)
+            .y_axis(Axis::new().title("Seconds").range_mode(RangeMode::ToZero)));
+        page_html.push_str(&mutex_plot.to_inline_html(Some("mutex-sleeps-plot")));
+    }
+    let x_vals: Vec<String> = snaps.iter().map(|awr| format!("{} ({})", awr.snap_info.begin_snap_time, awr.snap_info.begin_snap_id)).collect();
+    let mut series_plot = Plot::new();
+    for l in latch_contention.iter().filter(|l| latch_series.contains_key(&l.latch_name)).take(PLOT_LATCHES) {
+        series_plot.add_trace(Scatter::new(x_vals.clone(), latch_series[&l.latch_name].clone())
+            .mode(Mode::Lines)
+            .name(&l.latch_name));
+    }
+    series_plot.set_layout(Layout::new()
+        .title("Latch wait time per snapshot - top latches")
+        .height(450)
+        .hover_mode(HoverMode::X)
+        .y_axis(Axis::new().title("Wait time (s)").range_mode(RangeMode::ToZero)));
+    page_html.push_str(&series_plot.to_inline_html(Some("latch-series-plot")));
+    page_html.push_str(&table_to_html_string(&latch_table, "Latches ranked by contribution to DB Time", &latch_headers));
+    page_html.push_str(&table_to_html_string(&mutex_table, "Mutex Sleep Summary", &mutex_headers));
+    if let Err(e) = append_html_before_body_end(&fname, &page_html) {
+        eprintln!("Error writing file {}: {}", fname, e);
+    }
+
+    report_for_ai.latch_contention = latch_contention;
+    report_for_ai.mutex_contention = mutex_contention;
+    true
+}
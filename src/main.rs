@@ -19,9 +19,26 @@ mod staticdata;
 mod reasonings;
 mod macros;
 mod anomalies;
+mod anomaly_detectors;
+mod correlation;
 mod tools;
 mod reasonings_modular;
 mod gradient;
+mod sql_regression;
+mod changepoints;
+mod decomposition;
+mod capacity;
+mod forecast;
+mod regimes;
+mod rules;
+mod parameters;
+mod redo;
+mod hot_objects;
+mod shared_pool;
+mod latches;
+mod io_slo;
+mod heatmaps;
+mod snapshots;
 
 use crate::reasonings::*;
 use crate::reasonings::{StatisticsDescription,TopPeaksSelected,MadAnomaliesEvents,MadAnomaliesSQL,TopForegroundWaitEvents,TopBackgroundWaitEvents,PctOfTimesThisSQLFoundInOtherTopSections,WaitEventsWithStrongCorrelation,WaitEventsFromASH,TopSQLsByElapsedTime,StatsSummary,IOStatsByFunctionSummary,LatchActivitySummary,Top10SegmentStats,InstanceStatisticCorrelation,LoadProfileAnomalies,AnomalyDescription,AnomlyCluster,ReportForAI,AppState};
@@ -97,7 +114,7 @@ struct Args {
 	#[clap(short, long, default_value="", verbatim_doc_comment)]
 	backend_assistant: String,
 
-	///TOPn for detecting anomalies using MAD 
+	///TOPn anomalies reported for each statistic (highest scores are kept)
 	#[clap(short, long, default_value_t=10)]
 	mad_threshold: usize,
 
@@ -105,6 +122,54 @@ struct Args {
 	#[clap(short = 'W', long, default_value_t = 100)]
     mad_window_size: usize,
 
+	///Seasonality model for anomaly detection - compares each probe with probes from the same time of the day or week:
+	///		none   - global MAD or sliding window MAD (see mad_window_size)
+	///		daily  - robust baseline for each hour of the day
+	///		weekly - robust baseline for each hour of each day of the week
+	#[clap(long, default_value="none", verbatim_doc_comment)]
+	mad_seasonality: String,
+
+	///Anomaly detector:
+	///		mad      - Median Absolute Deviation (threshold: mad_score_threshold)
+	///		iqr      - Tukey fences Q1 - k*IQR, Q3 + k*IQR (threshold: iqr_fence)
+	///		robust-z - robust z-score with 1.4826*MAD (threshold: robust_z_threshold)
+	///		ewma     - EWMA control chart (threshold: ewma_sigma)
+	///		ensemble - voting of all the above (threshold: ensemble_votes)
+	#[clap(long, default_value="mad", verbatim_doc_comment)]
+	anomaly_detector: String,
+
+	///MAD score above which a probe is an anomaly
+	#[clap(long, default_value_t=7.0)]
+	mad_score_threshold: f64,
+
+	///Factor k of Tukey fences for IQR anomaly detector (1.5 - outlier, 3.0 - far out)
+	#[clap(long, default_value_t=3.0)]
+	iqr_fence: f64,
+
+	///Absolute robust z-score above which a probe is an anomaly
+	#[clap(long, default_value_t=3.5)]
+	robust_z_threshold: f64,
+
+	///Smoothing factor of EWMA control chart (0 < lambda <= 1)
+	#[clap(long, default_value_t=0.3)]
+	ewma_lambda: f64,
+
+	///Width of EWMA control limits in standard deviations
+	#[clap(long, default_value_t=3.0)]
+	ewma_sigma: f64,
+
+	///Minimal number of detectors (out of 4) which have to flag a probe in ensemble mode
+	#[clap(long, default_value_t=2)]
+	ensemble_votes: usize,
+
+	///Robust z-score of Mahalanobis distance above which a snapshot is a multivariate anomaly
+	#[clap(long, default_value_t=3.5)]
+	multivariate_threshold: f64,
+
+	///Maximal lag (in snapshots, both directions) for cross-correlation with DB Time
+	#[clap(long, default_value_t=3)]
+	corr_max_lag: usize,
+
 	///Parallelism level 
 	#[clap(short = 'P', long, default_value_t=4)]
     parallel: usize,
@@ -152,6 +217,101 @@ struct Args {
 	#[clap(short = 'T', long, default_value_t=1e-6)]
 	en_tol: f64,
 
+	///Automatic regularization for gradient models - overrides ridge_lambda, en_lambda and en_alpha:
+	///     none - use values given by -R, -E and -A
+	///     cv   - rolling-origin (time-series) cross-validation
+	///     gcv  - generalized cross-validation
+	#[clap(long, default_value="none", verbatim_doc_comment)]
+	gradient_tuning: String,
+
+	///Number of block bootstrap resamples for gradient confidence intervals and Elastic Net stability selection (0 - disabled)
+	///Every resample refits all gradient models, so 100 resamples cost about 100 times the gradient analysis time
+	#[clap(long, default_value_t=0, verbatim_doc_comment)]
+	gradient_bootstrap: usize,
+
+	///Block length (in snapshots) for block bootstrap of gradient models (0 - automatic: n^(1/3))
+	#[clap(long, default_value_t=0)]
+	bootstrap_block_size: usize,
+
+	///Number of lagged deltas (t-1..t-N) added for the top gradient predictors (0 - disabled)
+	#[clap(long, default_value_t=0)]
+	gradient_lags: usize,
+
+	///Add pairwise interactions of the top gradient predictors
+	#[clap(long)]
+	gradient_interactions: bool,
+
+	///Number of top predictors (by correlation of deltas with the target) that get lags and interactions
+	#[clap(long, default_value_t=8)]
+	gradient_expand_top: usize,
+
+	///Lagged/interaction features with VIF above this value are pruned
+	#[clap(long, default_value_t=10.0)]
+	gradient_vif_max: f64,
+
+	///CPU demand (DB CPU + resmgr:cpu quantum) as % of CPUs above which snapshot is flagged as HIGH
+	#[clap(long, default_value_t=80.0)]
+	cpu_high_pct: f64,
+
+	///Capacity forecast horizon in days (0 = no forecast)
+	#[clap(long, default_value_t=14)]
+	forecast_horizon: usize,
+
+	///Capacity thresholds for forecast as KEY=VALUE list, e.g. AAS=16,REDO_MB=50
+	///Keys: DB_TIME, AAS, PHYS_READ_MB, PHYS_WRITE_MB, REDO_MB (AAS defaults to number of CPUs)
+	#[clap(long, default_value="", verbatim_doc_comment)]
+	forecast_thresholds: String,
+
+	///Cluster snapshots into workload regimes:
+	///		none - no clustering
+	///		auto - number of regimes chosen by silhouette
+	///		<N>  - fixed number of regimes
+	#[clap(long, default_value="none", verbatim_doc_comment)]
+	regimes: String,
+
+	///Maximal number of workload regimes tried with --regimes auto
+	#[clap(long, default_value_t=6)]
+	regimes_max_k: usize,
+
+	///Score anomalies against the baseline of the snapshot's workload regime (requires --regimes)
+	#[clap(long)]
+	mad_per_regime: bool,
+
+	///Fit DB Time vs wait events gradient separately for each workload regime (requires --regimes)
+	#[clap(long)]
+	gradient_per_regime: bool,
+
+	///TOML file with diagnostic rules, evaluated together with the built-in rule pack
+	///(rule with the same id replaces the built-in one)
+	#[clap(long, default_value="", verbatim_doc_comment)]
+	rules: String,
+
+	///Do not evaluate the built-in diagnostic rule pack
+	#[clap(long)]
+	no_builtin_rules: bool,
+
+	///Target number of redo log switches per hour used to recommend online redo log size
+	#[clap(long, default_value_t=4.0)]
+	redo_target_switches: f64,
+
+	///IO latency SLOs as TARGET=MS list - target is an IO function from IO Stats by Function or a wait event
+	///(empty - SLOs are not evaluated)
+	#[clap(long, default_value="db file sequential read=5,Buffer Cache Reads=5,log file parallel write=2,LGWR=2", verbatim_doc_comment)]
+	io_slo: String,
+
+	///Number of top foreground wait events and top SQL_IDs with calendar heatmaps (plus DB Time and AAS, 0 = only --heatmaps)
+	#[clap(long, default_value_t=5)]
+	heatmaps_top: usize,
+
+	///Additional calendar heatmaps as comma separated FAMILY:name list, e.g. "EVENT:log file sync,TRACKED:Redo MB/s"
+	///Families: EVENT, BGEVENT, STAT, LP, TM, IO, SQL, TRACKED (display name of a tracked stat), DB_TIME, AAS
+	#[clap(long, default_value="", verbatim_doc_comment)]
+	heatmaps: String,
+
+	///Snapshots on each side of a snapshot whose median is compared with it on snapshot drill-down pages (0 = no pages)
+	#[clap(long, default_value_t=6)]
+	snapshot_window: usize,
+
 	///Convert existing markdown file to HTML without calling AI model 
 	#[clap(short, long, default_value="", verbatim_doc_comment)]
 	convert_md2html: String,
@@ -159,6 +319,44 @@ struct Args {
 	///Build customer gradient analyze for given SQL_ID
     #[clap(short = 'G', long, default_value="", verbatim_doc_comment)]
 	gradient_sql: String,
+
+	///Rolling gradient of DB Time vs wait events:
+	///     none     - disabled
+	///     sliding  - windows of gradient_window_size snapshots moved by gradient_window_step
+	///     segments - segments between change points of DB Time
+	#[clap(long, default_value="none", verbatim_doc_comment)]
+	gradient_windows: String,
+
+	///Number of snapshots in each sliding window of rolling gradient
+	#[clap(long, default_value_t=24)]
+	gradient_window_size: usize,
+
+	///Step between sliding windows of rolling gradient (0 - half of the window)
+	#[clap(long, default_value_t=0)]
+	gradient_window_step: usize,
+
+	///Build gradient analyze against any target series given as FAMILY:name, for example:
+	///     EVENT:log file sync, BGEVENT:log file parallel write, STAT:redo size, LP:Executes,
+	///     TM:sql execute elapsed time, IO:LGWR (average IO latency of function), SQL:<sql_id>
+	///SERVICE is not supported - per-service DB Time is not parsed from the reports
+	#[clap(long, default_value="", verbatim_doc_comment)]
+	gradient_target: String,
+
+	///Predictor families for --gradient-target, comma separated: EVENT, BGEVENT, STAT, SQL, LP
+	#[clap(long, default_value="EVENT,STAT")]
+	gradient_target_features: String,
+
+	///Minimal ratio (median after / median before) of per execution metric to report SQL_ID as regressed
+	#[clap(long, default_value_t=1.5)]
+	sql_regression_ratio: f64,
+
+	///Penalty for change-point detection, multiplied by ln(number of snapshots) - higher value means fewer change points
+	#[clap(long, default_value_t=3.0)]
+	change_point_penalty: f64,
+
+	///Minimal number of snapshots between two change points
+	#[clap(long, default_value_t=4)]
+	change_point_min_size: usize,
 }
 
 
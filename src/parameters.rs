@@ -0,0 +1,342 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use crate::awr::{AWR, AWRSCollection};
+use crate::Args;
+use crate::changepoints::{add_change_point_trace, add_change_point_shapes, is_db_time_change_point};
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, ParameterChange, ParameterCheck};
+use colored::*;
+use plotly::{Plot, Scatter};
+use plotly::common::{DashType, Line, Marker, MarkerSymbol, Mode};
+use plotly::layout::{Axis, HoverMode, Layout, Shape, ShapeLayer, ShapeLine, ShapeType};
+use prettytable::{Table, Row, Cell};
+
+/* Initialization parameters per snapshot.
+   AWR shows only modified (non-default) parameters with the value at the end of the snapshot, so a parameter
+   that appears, disappears or changes its value between consecutive snapshots was changed (ALTER SYSTEM or restart).
+   Every change is compared with median DB Time per second around it and with DB Time change points nearby.
+   The checker flags settings known to cause trouble and memory sizes that SGA Target / PGA Memory advisories
+   recommend to increase. Parameters starting with __ are resized automatically by ASMM/AMM and are ignored. */
+
+const DEFAULT_VALUE: &str = "(default)";
+/// Number of snapshots before and after the change used to compare DB Time
+const CHANGE_CONTEXT_SNAPS: usize = 6;
+/// DB Time change point within this many snapshots from the parameter change is reported with it
+const CHANGE_POINT_DISTANCE: usize = 3;
+/// Minimal % of DB Time saved according to SGA Target Advisory to flag SGA as undersized
+const SGA_ADVICE_MIN_GAIN_PCT: f64 = 10.0;
+/// Minimal gain of estimated PGA cache hit % to flag PGA as undersized
+const PGA_ADVICE_MIN_HIT_GAIN: f64 = 10.0;
+/// Advisories are considered only up to this size factor
+const ADVICE_MAX_FACTOR: f64 = 2.0;
+
+/// Leading numeric components of a version string, e.g. 19.0.0.0.0 -> [19, 0]
+fn version_prefix(v: &str) -> Vec<u32> {
+    v.trim().trim_matches('\'').split('.').take(2).filter_map(|p| p.trim().parse::<u32>().ok()).collect()
+}
+
+/// Known risky settings: (severity, issue, recommendation)
+fn check_parameter(name: &str, value: &str, release: &str) -> Option<(&'static str, String, String)> {
+    let v = value.trim().trim_matches('\'').to_uppercase();
+    let num = v.parse::<f64>().ok();
+    match name {
+        "optimizer_index_cost_adj" if num.is_some_and(|n| n != 100.0) => Some(("WARNING",
+            format!("optimizer_index_cost_adj={} makes index access look {} than it is", v, if num.unwrap_or(100.0) < 100.0 { "cheaper" } else { "more expensive" }),
+            "Legacy tuning knob - reset to default 100 and fix object and system statistics instead".to_string())),
+        "optimizer_index_caching" if num.is_some_and(|n| n != 0.0) => Some(("INFO",
+            format!("optimizer_index_caching={} changes cost of nested loops with index access", v),
+            "Reset to default 0 unless it was proven to help".to_string())),
+        "cursor_sharing" if v == "SIMILAR" => Some(("CRITICAL",
+            "cursor_sharing=SIMILAR is deprecated since 11.2 and causes many child cursors".to_string(),
+            "Use EXACT with bind variables in the application, FORCE only as a workaround".to_string())),
+        "cursor_sharing" if v == "FORCE" => Some(("WARNING",
+            "cursor_sharing=FORCE replaces literals with binds for all statements - bind peeking and histograms may give bad plans".to_string(),
+            "Fix literals in the application and go back to EXACT, or limit FORCE to sessions which need it".to_string())),
+        "optimizer_mode" if v == "FIRST_ROWS" || v == "RULE" || v == "CHOOSE" => Some(("WARNING",
+            format!("optimizer_mode={} is deprecated", v),
+            "Use ALL_ROWS (default) or FIRST_ROWS_n".to_string())),
+        "optimizer_features_enable" => {
+            let ofe = version_prefix(&v);
+            let rel = version_prefix(release);
+            if !ofe.is_empty() && !rel.is_empty() && ofe < rel {
+                Some(("INFO",
+                    format!("optimizer_features_enable={} is older than database release {}", v, release),
+                    "New optimizer features are disabled - plan to test with the current release value".to_string()))
+            } else {
+                None
+            }
+        }
+        "statistics_level" if v == "BASIC" => Some(("WARNING",
+            "statistics_level=BASIC disables advisories, AWR and many statistics".to_string(),
+            "Use TYPICAL".to_string())),
+        "timed_statistics" if v == "FALSE" => Some(("CRITICAL",
+            "timed_statistics=FALSE - wait and CPU times are not collected".to_string(),
+            "Set timed_statistics=TRUE".to_string())),
+        "disk_asynch_io" if v == "FALSE" => Some(("WARNING",
+            "disk_asynch_io=FALSE - DBWR and LGWR use synchronous IO".to_string(),
+            "Enable asynchronous IO unless platform does not support it".to_string())),
+        "filesystemio_options" if v == "NONE" => Some(("INFO",
+            "filesystemio_options=NONE - no direct or asynchronous IO on file systems".to_string(),
+            "Consider SETALL for data files on file systems".to_string())),
+        "db_file_multiblock_read_count" => Some(("INFO",
+            format!("db_file_multiblock_read_count is set explicitly to {} - it changes cost of full scans", v),
+            "Unset it so Oracle uses the value optimal for the platform".to_string())),
+        "session_cached_cursors" if num.is_some_and(|n| n < 50.0) => Some(("INFO",
+            format!("session_cached_cursors={} is low", v),
+            "Increase to at least 50 (default) to reduce soft parse cost".to_string())),
+        "db_block_checking" if v == "FULL" || v == "TRUE" || v == "MEDIUM" => Some(("INFO",
+            format!("db_block_checking={} adds CPU overhead to every block change", v),
+            "Keep it only if the extra protection is required".to_string())),
+        _ if name.starts_with('_') && !name.starts_with("__") => Some(("WARNING",
+            format!("hidden parameter {}={}", name, value.trim()),
+            "Hidden parameters should be set only on Oracle Support advice - verify it is still needed after patching".to_string())),
+        _ => None,
+    }
+}
+
+/// Memory sizing checks against SGA Target and PGA Memory advisories - the snapshot with the biggest gain is reported
+fn check_advisories(snaps: &[&AWR], params: &HashMap<String, String>) -> Vec<ParameterCheck> {
+    let mut checks: Vec<ParameterCheck> = Vec::new();
+
+    let mut best_sga: Option<(f64, f64, f64, u64)> = None; // gain %, current MB, advised MB, snap_id
+    let mut best_pga: Option<(f64, f64, f64, f64, f64, u64)> = None; // hit gain, current MB, advised MB, hit now, overalloc, snap_id
+    for awr in snaps {
+        if let Some(current) = awr.sga_target_advisory.iter().find(|a| (a.size_factor - 1.0).abs() < 1e-6) {
+            if current.est_db_time_s > 0.0 {
+                if let Some(best) = awr.sga_target_advisory.iter()
+                    .filter(|a| a.size_factor > 1.0 && a.size_factor <= ADVICE_MAX_FACTOR)
+                    .min_by(|a, b| a.est_db_time_s.partial_cmp(&b.est_db_time_s).unwrap_or(std::cmp::Ordering::Equal)) {
+                    let gain = (current.est_db_time_s - best.est_db_time_s) / current.est_db_time_s * 100.0;
+                    if best_sga.is_none_or(|b| gain > b.0) {
+                        best_sga = Some((gain, current.size_mb, best.size_mb, awr.snap_info.begin_snap_id));
+                    }
+                }
+            }
+        }
+        if let Some(current) = awr.pga_advisory.iter().find(|a| (a.size_factor - 1.0).abs() < 1e-6) {
+            let best = awr.pga_advisory.iter()
+                .filter(|a| a.size_factor > 1.0 && a.size_factor <= ADVICE_MAX_FACTOR)
+                .max_by(|a, b| a.est_cache_hit_pct.partial_cmp(&b.est_cache_hit_pct).unwrap_or(std::cmp::Ordering::Equal));
+            let (gain, advised) = best.map_or((0.0, current.target_mb), |b| (b.est_cache_hit_pct - current.est_cache_hit_pct, b.target_mb));
+            // over-allocation is always worse than any hit ratio gain
+            let score = if current.est_overalloc_count > 0.0 { 1000.0 + current.est_overalloc_count } else { gain };
+            if best_pga.is_none_or(|b| score > if b.4 > 0.0 { 1000.0 + b.4 } else { b.0 }) {
+                best_pga = Some((gain, current.target_mb, advised, current.est_cache_hit_pct, current.est_overalloc_count, awr.snap_info.begin_snap_id));
+            }
+        }
+    }
+
+    let sga_param = ["sga_target", "memory_target"].into_iter().find(|p| params.contains_key(*p)).unwrap_or("sga_target");
+    if let Some((gain, current_mb, advised_mb, snap_id)) = best_sga {
+        if gain >= SGA_ADVICE_MIN_GAIN_PCT {
+            checks.push(ParameterCheck {
+                parameter: sga_param.to_string(),
+                value: params.get(sga_param).cloned().unwrap_or_else(|| format!("{:.0}M", current_mb)),
+                severity: "WARNING".to_string(),
+                issue: format!("SGA Target Advisory (snap {}) estimates {:.1}% less DB Time with SGA {:.0} MB instead of {:.0} MB", snap_id, gain, advised_mb, current_mb),
+                recommendation: format!("Consider increasing {} towards {:.0} MB if host memory allows", sga_param, advised_mb),
+            });
+        }
+    }
+    if let Some((gain, current_mb, advised_mb, hit_now, overalloc, snap_id)) = best_pga {
+        let value = params.get("pga_aggregate_target").cloned().unwrap_or_else(|| format!("{:.0}M", current_mb));
+        if overalloc > 0.0 {
+            checks.push(ParameterCheck {
+                parameter: "pga_aggregate_target".to_string(),
+                value,
+                severity: "CRITICAL".to_string(),
+                issue: format!("PGA Memory Advisory (snap {}) estimates {:.0} over-allocations at current target {:.0} MB - PGA target is not respected", snap_id, overalloc, current_mb),
+                recommendation: "Increase pga_aggregate_target to the smallest size with Estd PGA Overalloc Count 0".to_string(),
+            });
+        } else if gain >= PGA_ADVICE_MIN_HIT_GAIN {
+            checks.push(ParameterCheck {
+                parameter: "pga_aggregate_target".to_string(),
+                value,
+                severity: "WARNING".to_string(),
+                issue: format!("PGA Memory Advisory (snap {}) estimates cache hit {:.0}% at {:.0} MB vs {:.0}% now - work areas spill to temp", snap_id, hit_now + gain, advised_mb, hit_now),
+                recommendation: format!("Consider increasing pga_aggregate_target towards {:.0} MB", advised_mb),
+            });
+        }
+    }
+    checks
+}
+
+/// Changes of parameters between consecutive snapshots which have parameter list
+fn parameter_changes(snaps: &[&AWR], change_points: &[crate::reasonings::ChangePoint]) -> Vec<ParameterChange> {
+    let db_time: Vec<f64> = snaps.iter().map(|awr| db_time_per_s(awr)).collect();
+    let db_time_cps: Vec<(usize, String)> = change_points.iter()
+        .filter(|cp| is_db_time_change_point(cp))
+        .filter_map(|cp| snaps.iter().position(|awr| awr.snap_info.begin_snap_id == cp.change_begin_snap_id)
+            .map(|idx| (idx, format!("DB Time {:+.1}% at snap {} ({})", cp.change_pct, cp.change_begin_snap_id, cp.change_begin_snap_date))))
+        .collect();
+
+    let mut changes: Vec<ParameterChange> = Vec::new();
+    let mut previous: Option<&HashMap<String, String>> = None;
+    for (idx, awr) in snaps.iter().enumerate() {
+        if awr.parameters.is_empty() {
+            continue;
+        }
+        if let Some(prev) = previous {
+            let names: BTreeSet<&String> = prev.keys().chain(awr.parameters.keys()).filter(|p| !p.starts_with("__")).collect();
+            for name in names {
+                let old_value = prev.get(name).map_or(DEFAULT_VALUE, |v| v.as_str());
+                let new_value = awr.parameters.get(name).map_or(DEFAULT_VALUE, |v| v.as_str());
+                if old_value == new_value {
+                    continue;
+                }
+                let before = &db_time[idx.saturating_sub(CHANGE_CONTEXT_SNAPS)..idx];
+                let after = &db_time[idx..(idx + CHANGE_CONTEXT_SNAPS).min(db_time.len())];
+                changes.push(ParameterChange {
+                    parameter: name.clone(),
+                    old_value: old_value.to_string(),
+                    new_value: new_value.to_string(),
+                    snap_id: awr.snap_info.begin_snap_id,
+                    snap_date: awr.snap_info.begin_snap_time.clone(),
+                    db_time_per_s_before: if before.is_empty() { 0.0 } else { median(before) },
+                    db_time_per_s_after: if after.is_empty() { 0.0 } else { median(after) },
+                    db_time_change_point: db_time_cps.iter()
+                        .filter(|(cp_idx, _)| cp_idx.abs_diff(idx) <= CHANGE_POINT_DISTANCE)
+                        .min_by_key(|(cp_idx, _)| cp_idx.abs_diff(idx))
+                        .map(|(_, desc)| desc.clone()),
+                });
+            }
+        }
+        previous = Some(&awr.parameters);
+    }
+    changes
+}
+
+/// Parameter changes timeline and checks of risky settings. Returns true if parameters page was written.
+pub fn report_parameters(collection: &AWRSCollection, snap_range: &(u64,u64), args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) -> bool {
+    let params = &collection.initialization_parameters;
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{}\n", "Initialization parameters - changes between snapshots and risky settings".yellow());
+    if params.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo initialization parameters found in reports\n".green().italic());
+        return false;
+    }
+
+    let snaps: Vec<&AWR> = snaps_in_range(&collection.awrs, snap_range)
+        .collect();
+
+    // Checks of the latest parameter values and of memory advisories
+    let mut checks: Vec<ParameterCheck> = params.iter()
+        .filter_map(|(name, value)| check_parameter(&name.to_lowercase(), value, &collection.db_instance_information.release)
+            .map(|(severity, issue, recommendation)| ParameterCheck {
+                parameter: name.clone(),
+                value: value.clone(),
+                severity: severity.to_string(),
+                issue,
+                recommendation,
+            }))
+        .collect();
+    checks.extend(check_advisories(&snaps, params));
+    let severity_rank = |s: &str| ["CRITICAL", "WARNING", "INFO"].iter().position(|x| *x == s).unwrap_or(3);
+    checks.sort_by(|a, b| severity_rank(&a.severity).cmp(&severity_rank(&b.severity)).then(a.parameter.cmp(&b.parameter)));
+
+    let changes = parameter_changes(&snaps, &report_for_ai.change_points);
+
+    let check_headers = ["Severity", "Parameter", "Value", "Issue", "Recommendation"];
+    let mut check_table = Table::new();
+    check_table.set_titles(Row::new(check_headers.iter().map(|h| Cell::new(h)).collect()));
+    for c in &checks {
+        check_table.add_row(Row::new(vec![
+            Cell::new(&c.severity),
+            Cell::new(&c.parameter),
+            Cell::new(&c.value),
+            Cell::new(&c.issue),
+            Cell::new(&c.recommendation),
+        ]));
+    }
+    if checks.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo risky parameter settings found\n".green().italic());
+    } else {
+        make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&check_table));
+    }
+
+    let change_headers = ["Snap ID", "Snap Date", "Parameter", "Old Value", "New Value", "DB Time/s Before", "DB Time/s After", "DB Time Change Point"];
+    let mut change_table = Table::new();
+    change_table.set_titles(Row::new(change_headers.iter().map(|h| Cell::new(h)).collect()));
+    for c in &changes {
+        change_table.add_row(Row::new(vec![
+            Cell::new(&format!("{}", c.snap_id)),
+            Cell::new(&c.snap_date),
+            Cell::new(&c.parameter),
+            Cell::new(&c.old_value),
+            Cell::new(&c.new_value),
+            Cell::new(&format!("{:.2}", c.db_time_per_s_before)),
+            Cell::new(&format!("{:.2}", c.db_time_per_s_after)),
+            Cell::new(c.db_time_change_point.as_deref().unwrap_or("")),
+        ]));
+    }
+    if changes.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo parameter changes between snapshots\n".green().italic());
+    } else {
+        make_notes!(logfile_name, args.quiet, 0, "\n\tParameter changes:\n");
+        make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&change_table));
+    }
+
+    // Parameters page - DB Time with parameter changes and DB Time change points
+    let x_vals: Vec<String> = snaps.iter().map(|awr| format!("{} ({})", awr.snap_info.begin_snap_time, awr.snap_info.begin_snap_id)).collect();
+    let y_vals: Vec<f64> = snaps.iter().map(|awr| db_time_per_s(awr)).collect();
+    let mut plot = Plot::new();
+    plot.add_trace(Scatter::new(x_vals.clone(), y_vals.clone()).mode(Mode::Lines).name("DB Time (s/s)").line(Line::new().color("#7f7f7f")));
+
+    let mut by_snap: BTreeMap<u64, Vec<&ParameterChange>> = BTreeMap::new();
+    for c in &changes {
+        by_snap.entry(c.snap_id).or_default().push(c);
+    }
+    let mut layout = Layout::new()
+        .title("Initialization parameter changes")
+        .height(600)
+        .hover_mode(HoverMode::Closest)
+        .y_axis(Axis::new().title("DB Time (s/s)"));
+    let (mut cx, mut cy, mut ctext): (Vec<String>, Vec<f64>, Vec<String>) = (Vec::new(), Vec::new(), Vec::new());
+    for (snap_id, cs) in &by_snap {
+        let Some(idx) = snaps.iter().position(|awr| awr.snap_info.begin_snap_id == *snap_id) else { continue; };
+        cx.push(x_vals[idx].clone());
+        cy.push(y_vals[idx]);
+        ctext.push(cs.iter().map(|c| format!("{}: {} -> {}", c.parameter, c.old_value, c.new_value)).collect::<Vec<String>>().join("<br>"));
+        layout.add_shape(
+            Shape::new()
+                .shape_type(ShapeType::Line)
+                .x_ref("x")
+                .y_ref("paper")
+                .x0(x_vals[idx].clone())
+                .x1(x_vals[idx].clone())
+                .y0(0.0)
+                .y1(1.0)
+                .layer(ShapeLayer::Below)
+                .line(ShapeLine::new().color("#9467bd").width(1.5).dash(DashType::Dot)),
+        );
+    }
+    if !cx.is_empty() {
+        plot.add_trace(Scatter::new(cx, cy)
+            .mode(Mode::Markers)
+            .name("Parameter changes")
+            .text_array(ctext)
+            .hover_template("<b>Parameter change</b><br>%{x}<br>%{text}<extra></extra>")
+            .marker(Marker::new().symbol(MarkerSymbol::Diamond).size(12).color("#9467bd")));
+    }
+    let db_time_cps: Vec<crate::reasonings::ChangePoint> = report_for_ai.change_points.iter()
+        .filter(|cp| is_db_time_change_point(cp))
+        .cloned()
+        .collect();
+    add_change_point_trace(&mut plot, &db_time_cps, &x_vals, &y_vals);
+    add_change_point_shapes(&mut layout, &db_time_cps);
+    plot.set_layout(layout);
+
+    let fname = format!("{}/stats/parameters.html", html_dir);
+    plot.write_html(Path::new(&fname));
+    let mut page_html = table_to_html_string(&check_table, "Parameter checks", &check_headers);
+    page_html.push_str(&table_to_html_string(&change_table, "Parameter changes between snapshots", &change_headers));
+    if let Err(e) = append_html_before_body_end(&fname, &page_html) {
+        eprintln!("Error writing file {}: {}", fname, e);
+    }
+
+    report_for_ai.parameter_checks = checks;
+    report_for_ai.parameter_changes = changes;
+    true
+}
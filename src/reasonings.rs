@@ -38,6 +38,18 @@ pub struct TopPeaksSelected {
     pub db_time_value: f64,
     pub db_cpu_value: f64,
     pub dbcpu_dbtime_ratio: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub db_time_vs_median: Vec<DbTimeComponentChange>,
+}
+
+/// Change of one DB Time component (DB CPU, wait class, Unaccounted) in a peak versus its median over all snapshots
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct DbTimeComponentChange {
+    pub component: String,
+    pub peak_value_s: f64,
+    pub median_value_s: f64,
+    pub delta_s: f64,
+    pub share_of_change: f64,      // delta_s / sum of all deltas (peak DB Time - median baseline)
 }
 
 #[derive(Default,Serialize, Deserialize, Debug, Clone)]
@@ -63,6 +75,7 @@ pub struct MadAnomaliesSQL {
 pub struct TopForegroundWaitEvents {
     pub event_name: String,
     pub correlation_with_db_time: f64,
+    pub correlation_details: CorrelationDetails,
     pub marked_as_top_in_pct_of_probes: f64,
     pub avg_pct_of_dbtime: f64,
     pub stddev_pct_of_db_time: f64,
@@ -81,6 +94,7 @@ pub struct TopForegroundWaitEvents {
 pub struct TopBackgroundWaitEvents {
     pub event_name: String,
     pub correlation_with_db_time: f64,
+    pub correlation_details: CorrelationDetails,
     pub marked_as_top_in_pct_of_probes: f64,
     pub avg_pct_of_dbtime: f64,
     pub stddev_pct_of_db_time: f64,
@@ -175,6 +189,18 @@ pub struct Top10SegmentStats {
 pub struct InstanceStatisticCorrelation {
     pub stat_name: String,
     pub pearson_correlation_value: f64,
+    pub correlation_details: CorrelationDetails,
+}
+
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct CorrelationDetails {
+    pub spearman: f64,
+    pub kendall_tau: f64,
+    pub best_lag: i64,
+    pub best_lag_correlation: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial_correlation_given_executes: Option<f64>,
+    pub significant_by: Vec<String>,
 }
 
 #[derive(Default,Serialize, Deserialize, Debug, Clone)]
@@ -199,6 +225,58 @@ pub struct AnomlyCluster {
     pub begin_snap_date: String,
     pub anomalies_detected: Vec<AnomalyDescription>,
     pub number_of_anomalies: u64,
+    pub multivariate_anomaly_score: Option<f64>,
+    pub multivariate_top_contributors: Vec<MultivariateContributor>,
+}
+
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct MultivariateContributor {
+    pub metric: String,
+    pub robust_z: f64,
+    pub contribution_pct: f64,
+}
+
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct MultivariateAnomaly {
+    pub begin_snap_id: u64,
+    pub begin_snap_date: String,
+    pub mahalanobis_distance: f64,
+    pub anomaly_score: f64,
+    pub is_anomaly: bool,
+    pub top_contributors: Vec<MultivariateContributor>,
+}
+
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct SQLPerformanceRegression {
+    pub sql_id: String,
+    pub metric: String,
+    pub cause: String,
+    pub change_begin_snap_id: u64,
+    pub change_begin_snap_date: String,
+    pub median_before: f64,
+    pub median_after: f64,
+    pub change_ratio: f64,
+    pub shift_score: f64,
+    pub executions_median_before: f64,
+    pub executions_median_after: f64,
+}
+
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct ChangePoint {
+    pub series_type: String,
+    pub series_name: String,
+    pub change_begin_snap_id: u64,
+    pub change_begin_snap_date: String,
+    pub snaps_before: usize,
+    pub snaps_after: usize,
+    pub mean_before: f64,
+    pub mean_after: f64,
+    pub median_before: f64,
+    pub median_after: f64,
+    pub stddev_before: f64,
+    pub stddev_after: f64,
+    pub change_pct: f64,
+    pub cost_reduction: f64,
 }
 
 #[derive(Default,Serialize, Deserialize, Debug, Clone)]
@@ -206,8 +284,26 @@ pub struct GradientSettings {
     pub ridge_lambda: f64,
     pub elastic_net_lambda: f64,
     pub elastic_net_alpha: f64,
+    #[serde(default)]
+    pub regularization_tuning: String,      // none, cv or gcv
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ridge_validation_error: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elastic_net_validation_error: Option<f64>,
     pub elastic_net_max_iter: usize,
     pub elastic_net_tol: f64,
+    #[serde(default)]
+    pub bootstrap_resamples: usize,
+    #[serde(default)]
+    pub bootstrap_block_size: usize,
+    #[serde(default)]
+    pub feature_lags: usize,                // lagged deltas of the top predictors
+    #[serde(default)]
+    pub feature_interactions: bool,         // pairwise interactions of the top predictors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expanded_features: Vec<String>,     // lagged/interaction features kept in the models
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pruned_features: Vec<String>,       // lagged/interaction features dropped by VIF pruning
     pub input_wait_event_unit: String,
     pub input_db_time_unit: String,
 }
@@ -220,6 +316,22 @@ pub struct GradientTopItem {
     pub impact_active: f64,       // P90-based — primary tuning metric
     pub impact_peak: f64,         // P99-based — worst-case
     pub impact_share: f64,        // % of total active impact
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coef_ci_low: Option<f64>,           // block bootstrap 95% interval of coef
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coef_ci_high: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact_active_ci_low: Option<f64>,  // block bootstrap 95% interval of impact_active
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact_active_ci_high: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection_frequency: Option<f64>,   // Elastic Net only - fraction of resamples with non-zero coef
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub std_error: Option<f64>,             // OLS only - Newey-West (HAC) standard error of coef
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub t_stat: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p_value: Option<f64>,
 }
 
 #[derive(Default,Serialize, Deserialize, Debug, Clone)]
@@ -229,6 +341,10 @@ pub struct DbTimeGradientSection {
     pub elastic_net_top: Vec<GradientTopItem>,
     pub huber_top: Vec<GradientTopItem>,
     pub quantile95_top: Vec<GradientTopItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ols_top: Vec<GradientTopItem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ols_fit: Option<OlsFitSummary>,
     pub cross_model_classifications: Vec<CrossModelClassification>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub vif_diagnostics: Vec<VifDiagnostic>,
@@ -236,6 +352,38 @@ pub struct DbTimeGradientSection {
     pub collinear_group_impacts: Vec<CollinearGroupImpact>,
 }
 
+/// Goodness of fit of OLS with Newey-West (HAC) standard errors
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct OlsFitSummary {
+    pub r_squared: f64,
+    pub adj_r_squared: f64,
+    pub hac_lags: usize,                    // Newey-West bandwidth (Bartlett kernel)
+    pub num_predictors: usize,
+    pub degrees_of_freedom: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped_by_vif: Vec<String>,        // predictors removed before OLS to keep X'X well-conditioned
+    #[serde(default)]
+    pub selection_snapshots: usize,         // first Δ-snapshots used only to select predictors, OLS is fitted on the rest
+}
+
+/// Ridge gradient fitted only on snapshots of one window (sliding window or change-point segment)
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct RollingGradientWindow {
+    pub begin_snap_id: u64,
+    pub begin_snap_date: String,
+    pub end_snap_id: u64,
+    pub end_snap_date: String,
+    pub top_items: Vec<GradientTopItem>,
+}
+
+/// Gradient section computed against a target chosen with --gradient-target instead of DB Time
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct TargetGradientSection {
+    pub target: String,            // FAMILY:name, e.g. EVENT:log file sync
+    pub feature_family: String,    // EVENT, BGEVENT, STAT, SQL or LP
+    pub gradient: DbTimeGradientSection,
+}
+
 #[derive(Default,Serialize, Deserialize, Debug, Clone)]
 pub struct CrossModelClassification {
     pub event_name: String,
@@ -246,9 +394,15 @@ pub struct CrossModelClassification {
     pub in_elastic_net: bool,
     pub in_huber: bool,
     pub in_quantile95: bool,
+    #[serde(default)]
+    pub in_ols: bool,                       // positive and significant (p < 0.05) in OLS with HAC errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ols_p_value: Option<f64>,
     pub priority: u8,
     pub combined_impact: f64,
     pub combined_peak_impact: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ci_crosses_zero_in: Vec<String>,
 }
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone)]
@@ -265,6 +419,321 @@ pub struct CollinearGroupImpact {
     pub combined_coef: f64,
 }
 
+/// Consecutive snapshots with CPU demand above the HIGH threshold, saturated CPUs or Resource Manager throttling
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct CpuPressurePeriod {
+    pub begin_snap_id: u64,
+    pub begin_snap_date: String,
+    pub end_snap_id: u64,
+    pub end_snap_date: String,
+    pub snapshots: usize,
+    pub status: String,            // HIGH or SATURATED - the worst status within period
+    pub resmgr_throttling: bool,   // sessions waited on resmgr:cpu quantum
+    pub max_aas: f64,
+    pub max_cpu_demand: f64,       // DB CPU + resmgr:cpu quantum in sessions
+    pub max_resmgr_aas: f64,
+    pub max_host_busy_pct: f64,
+    pub min_headroom_cpus: f64,    // CPUs - CPU demand, negative means sessions queued for CPU
+}
+
+/// Average Active Sessions compared with CPU capacity of the host and instance
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct CpuCapacity {
+    pub host_cpus: u32,
+    pub host_cores: u32,
+    pub db_instance_cpus: u32,
+    pub high_threshold_pct: f64,
+    pub aas_mean: f64,
+    pub aas_p95: f64,
+    pub aas_max: f64,
+    pub cpu_demand_p95: f64,
+    pub cpu_demand_max: f64,
+    pub min_headroom_cpus: f64,
+    pub snapshots_saturated: usize,
+    pub snapshots_high: usize,
+    pub snapshots_resmgr_throttled: usize,
+    pub pressure_periods: Vec<CpuPressurePeriod>,
+}
+
+/// Group of snapshots with similar workload (k-means of Load Profile, Time Model and wait class rates)
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct WorkloadRegime {
+    pub regime_id: usize,
+    pub label: String,             // heuristic: BACKUP, IDLE, BATCH, OLTP or MIXED + DAY/NIGHT/WEEKEND
+    pub snapshots: usize,
+    pub share_pct: f64,
+    pub runs: usize,               // number of separate periods of consecutive snapshots in this regime
+    pub silhouette: f64,           // how well snapshots fit this regime, -1..1
+    pub typical_aas: f64,
+    pub typical_db_cpu_per_s: f64,
+    pub typical_executes_per_s: f64,
+    pub typical_user_calls_per_s: f64,
+    pub dominant_hours: String,
+    pub weekend_share_pct: f64,
+    pub distinguishing_features: Vec<String>,  // standardized centroid values furthest from overall mean
+    pub top_wait_events: Vec<String>,
+    pub top_sqls: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub db_time_gradient_top: Vec<GradientTopItem>,  // Ridge DB Time vs wait events within the regime (--gradient-per-regime)
+}
+
+/// Holt-Winters projection of one workload series and the date it crosses the capacity threshold
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct CapacityForecast {
+    pub series: String,
+    pub unit: String,
+    pub seasonality: String,       // none, daily or daily + weekly
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub residual_stddev: f64,
+    pub last_day_mean: f64,
+    pub trend_per_day: f64,
+    pub horizon_days: usize,
+    pub horizon_end_mean: f64,
+    pub horizon_end_lower95: f64,
+    pub horizon_end_upper95: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    pub status: String,            // EXCEEDED, CROSSES, MAY_CROSS, BELOW or NO_THRESHOLD
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crossing_date: Option<String>,         // forecast mean reaches threshold
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crossing_date_upper95: Option<String>, // upper 95% bound reaches threshold - earliest plausible date
+}
+
+/// Snapshot with unusual commit behaviour - commit rate or user calls per transaction far from the median
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct CommitOutlier {
+    pub snap_id: u64,
+    pub snap_date: String,
+    pub commits_per_s: f64,
+    pub user_calls_per_transaction: f64, // user calls / (user commits + user rollbacks)
+    pub commits_robust_z: f64,
+    pub calls_per_transaction_robust_z: f64,
+    pub log_file_sync_ms: f64,
+}
+
+/// Redo generation vs log switches, recommended online redo log size and what log file sync waits for
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct RedoCommitAnalysis {
+    pub redo_mb_per_s_median: f64,
+    pub redo_mb_per_s_p95: f64,
+    pub log_switches_per_hour_median: f64,
+    pub log_switches_per_hour_max: f64,
+    pub redo_mb_per_switch_median: f64,    // estimate of the current online redo log size
+    pub target_switches_per_hour: f64,
+    pub recommended_log_size_mb: u64,      // P95 of redo MB per hour / target switches per hour
+    pub log_file_sync_ms_median: f64,
+    pub log_file_parallel_write_ms_median: f64,
+    pub commit_bound: String,              // IO, CPU, MIXED, OK or UNKNOWN
+    pub snapshots_io_bound: usize,
+    pub snapshots_cpu_bound: usize,
+    pub snapshots_mixed: usize,
+    pub commit_outliers: Vec<CommitOutlier>,
+}
+
+/// Segment that is often in Segment Statistics top lists or follows DB Time, with top SQLs referencing it
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct HotObject {
+    pub object_name: String,       // # below security level 1
+    pub object_type: String,
+    pub object_id: u64,
+    pub data_object_id: u64,
+    pub snapshots_in_top: usize,
+    pub persistence_pct: f64,      // % of snapshots with the object in at least one top list
+    pub persistence_rank: usize,
+    pub statistics: Vec<String>,   // segment statistics with the object in top list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub most_correlated_statistic: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_time_correlation: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_rank: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub top_sql_ids: Vec<String>,  // top SQLs by elapsed time whose text references the object (security level 2)
+}
+
+/// Dictionary cache (dc_*) get miss % over the analyzed period
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct DictionaryCacheTrend {
+    pub cache: String,
+    pub get_requests_median: f64,
+    pub get_pct_miss_median: f64,
+    pub get_pct_miss_first_half: f64,  // median of the first half of snapshots
+    pub get_pct_miss_second_half: f64, // median of the second half of snapshots
+    pub trend: String,                 // INCREASING, DECREASING, STABLE or INSUFFICIENT_DATA
+}
+
+/// Snapshot with unusual number of library cache invalidations
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct InvalidationStorm {
+    pub snap_id: u64,
+    pub snap_date: String,
+    pub invalidations: u64,
+    pub reloads: u64,
+    pub robust_z: f64,
+    pub cursor_pin_s_wait_on_x_s: f64,
+}
+
+/// Pearson correlation of a library/dictionary cache metric with hard parses or mutex waits
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct SharedPoolCorrelation {
+    pub metric: String,
+    pub signal: String,
+    pub pearson_r: f64,
+}
+
+/// Finding about shared pool pressure, cursor invalidations or dictionary cache misses
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct SharedPoolFinding {
+    pub severity: String,          // INFO, WARNING or CRITICAL
+    pub issue: String,
+    pub evidence: String,
+    pub recommendation: String,
+}
+
+/// Library cache and dictionary cache health derived from Library Cache Activity and Dictionary Cache Stats
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct SharedPoolHealth {
+    pub reload_pct_median: f64,    // reloads / pin requests of all namespaces
+    pub reload_pct_max: f64,
+    pub sql_area_get_pct_miss_median: f64,
+    pub library_pin_pct_miss_median: f64,
+    pub invalidations_total: u64,
+    pub dictionary_get_pct_miss_median: f64,
+    pub dictionary_caches: Vec<DictionaryCacheTrend>,
+    pub invalidation_storms: Vec<InvalidationStorm>,
+    pub correlations: Vec<SharedPoolCorrelation>,
+    pub findings: Vec<SharedPoolFinding>,
+}
+
+/// Latch ranked by its contribution to DB Time, with the wait event it shows up as
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct LatchContention {
+    pub rank: usize,
+    pub latch_name: String,
+    pub wait_event: String,          // latch: <name> or latch free for latches without own event
+    pub wait_time_s: f64,            // Latch Activity wait time
+    pub event_wait_time_s: f64,      // time of the wait event, latch free is split by sleeps
+    pub pct_db_time: f64,
+    pub get_requests: u64,
+    pub get_pct_miss: f64,
+    pub misses: u64,                 // Latch Sleep Breakdown
+    pub sleeps: u64,
+    pub spin_gets_pct: f64,          // % of misses resolved by spinning
+    pub snapshots_with_waits: usize,
+}
+
+/// Mutex type from Mutex Sleep Summary with the wait events it causes
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct MutexContention {
+    pub mutex_type: String,
+    pub wait_events: Vec<String>,
+    pub sleeps: u64,
+    pub wait_time_s: f64,
+    pub event_wait_time_s: f64,
+    pub pct_db_time: f64,
+    pub top_locations: Vec<String>,  // code locations with the most wait time
+}
+
+/// Longest run of consecutive snapshots violating an IO latency SLO
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct IoSloViolationPeriod {
+    pub begin_snap_id: u64,
+    pub end_snap_id: u64,
+    pub begin_snap_date: String,
+    pub snapshots: usize,
+    pub avg_latency_ms: f64,
+    pub max_latency_ms: f64,
+}
+
+/// IO latency SLO (--io-slo) evaluated in every snapshot for an IO function or a wait event
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct IoSloCompliance {
+    pub target: String,
+    pub source: String,                // IO_FUNCTION (IO Stats by Function) or WAIT_EVENT
+    pub slo_ms: f64,
+    pub snapshots_evaluated: usize,    // snapshots with at least 100 waits
+    pub violations: usize,
+    pub compliance_pct: f64,
+    pub median_latency_ms: f64,
+    pub p95_latency_ms: f64,
+    pub worst_latency_ms: f64,
+    pub worst_snap_id: u64,
+    pub worst_snap_date: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worst_period: Option<IoSloViolationPeriod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waits_within_slo_pct: Option<f64>, // median % of waits in histogram buckets below the SLO
+}
+
+/// When a metric is high - hour of day x day of week cells of the calendar heatmap
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct TimeOfDayProfile {
+    pub metric: String,            // FAMILY:name, DB_TIME or AAS
+    pub peak_weekday: String,      // cell with the highest mean
+    pub peak_hour: usize,
+    pub peak_value: f64,
+    pub busiest_hour: usize,       // hour of day with the highest mean over all days
+    pub quietest_hour: usize,
+    pub busiest_weekday: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_to_median: Option<f64>, // peak cell / median cell - how concentrated the load is
+}
+
+/// Initialization parameter changed between consecutive snapshots
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct ParameterChange {
+    pub parameter: String,
+    pub old_value: String,         // (default) when parameter was not set
+    pub new_value: String,
+    pub snap_id: u64,              // first snapshot with the new value
+    pub snap_date: String,
+    pub db_time_per_s_before: f64, // median of up to 6 snapshots before the change
+    pub db_time_per_s_after: f64,  // median of up to 6 snapshots from the change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_time_change_point: Option<String>, // DB Time change point within 3 snapshots
+}
+
+/// Risky initialization parameter setting or memory size the advisories recommend to increase
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct ParameterCheck {
+    pub parameter: String,
+    pub value: String,
+    pub severity: String,          // INFO, WARNING or CRITICAL
+    pub issue: String,
+    pub recommendation: String,
+}
+
+/// Snapshot (or whole report) in which a diagnostic rule matched, with values of its metrics
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct RuleEvidence {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snap_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub begin_snap_time: Option<String>,
+    pub values: Vec<String>,
+}
+
+/// Finding of a deterministic diagnostic rule (built-in rule pack or --rules file)
+#[derive(Default,Serialize, Deserialize, Debug, Clone)]
+pub struct RuleFinding {
+    pub rule_id: String,
+    pub title: String,
+    pub severity: String,          // INFO, WARNING or CRITICAL
+    pub scope: String,             // snapshot or report
+    pub source: String,            // builtin or rules file name
+    pub matched_snapshots: usize,
+    pub total_snapshots: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_snap_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_snap_id: Option<u64>,
+    pub evidence: Vec<RuleEvidence>, // worst matching snapshots first
+    pub recommendation: String,
+}
+
 #[derive(Default,Serialize, Deserialize, Debug, Clone)]
 pub struct ReportForAI {
     pub general_data: StatisticsDescription,
@@ -285,6 +754,8 @@ pub struct ReportForAI {
     pub instance_stats_pearson_correlation: Vec<InstanceStatisticCorrelation>,
     pub load_profile_anomalies: Vec<LoadProfileAnomalies>,
     pub anomaly_clusters: Vec<AnomlyCluster>,
+    pub sql_performance_regressions: Vec<SQLPerformanceRegression>,
+    pub change_points: Vec<ChangePoint>,
     pub db_time_gradient_fg_wait_events: Option<DbTimeGradientSection>,
     pub db_time_gradient_instance_stats_counters: Option<DbTimeGradientSection>,
     pub db_time_gradient_instance_stats_volumes: Option<DbTimeGradientSection>,
@@ -294,6 +765,36 @@ pub struct ReportForAI {
     pub db_cpu_gradient_sql_cpu_time: Option<DbTimeGradientSection>,
     pub sql_id_gradient_wait_events: Option<DbTimeGradientSection>,
     pub sql_id_gradient_instance_stats: Option<DbTimeGradientSection>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub db_time_gradient_rolling: Vec<RollingGradientWindow>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub target_gradients: Vec<TargetGradientSection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_capacity: Option<CpuCapacity>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capacity_forecast: Vec<CapacityForecast>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workload_regimes: Vec<WorkloadRegime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redo_commit: Option<RedoCommitAnalysis>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hot_objects: Vec<HotObject>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared_pool_health: Option<SharedPoolHealth>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub latch_contention: Vec<LatchContention>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mutex_contention: Vec<MutexContention>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub io_slo_compliance: Vec<IoSloCompliance>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub time_of_day_profiles: Vec<TimeOfDayProfile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameter_checks: Vec<ParameterCheck>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameter_changes: Vec<ParameterChange>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rule_findings: Vec<RuleFinding>,
     pub initialization_parameters: HashMap<String, String>,
 }
 
@@ -320,6 +821,12 @@ pub fn strip_gradient_descriptions(report: &mut ReportForAI) {
             }
         }
     }
+
+    for target_gradient in &mut report.target_gradients {
+        for item in &mut target_gradient.gradient.cross_model_classifications {
+            item.description = None;
+        }
+    }
 }
 
 static SPELL: &str =
@@ -337,7 +844,9 @@ and write a comprehensive statistical summary for all metrics before proceeding.
 
 The ReportForAI contains these analytical sections:
 - `general_data` — overall DB load shape description with MAD analysis
-- `top_spikes_marked` — peak periods with DB Time, DB CPU, and their ratio
+- `top_spikes_marked` — peak periods with DB Time, DB CPU, and their ratio. `db_time_vs_median` splits each 
+  peak into DB CPU, wait classes and Unaccounted (not instrumented, e.g. CPU run queue) with `delta_s` vs median 
+  and `share_of_change` - start the explanation of every peak from the components with the largest share
 - `top_foreground_wait_events` / `top_background_wait_events` — wait event statistics with 
   correlations, averages, stddevs, and MAD anomalies.
   **Note:** `top_foreground_wait_events` may contain an optional field 
@@ -354,9 +863,80 @@ The ReportForAI contains these analytical sections:
 - `latch_activity_summary` — latch contention metrics
 - `top_10_segments_by_*` — 8 segment ranking sections (row lock waits, physical reads/writes, 
   logical reads, buffer busy waits, direct I/O). May be empty for STATSPACK reports.
-- `instance_stats_pearson_correlation` — instance statistics correlated with DB Time (abs(rho) >= 0.5)
+- `instance_stats_pearson_correlation` — instance statistics correlated with DB Time (Bonferroni corrected, never below abs(rho) >= 0.5). 
+  `correlation_details` holds Spearman and Kendall tau (robust to outliers and non-linear monotonic relations), 
+  `best_lag` with its correlation (positive lag means the statistic leads DB Time by that many snapshots) and 
+  `partial_correlation_given_executes` (relation which remains after removing overall load). `significant_by` lists 
+  methods which passed their threshold - a statistic significant only by PARTIAL or LAG is not just a proxy of load. 
+  The same `correlation_details` are provided for top foreground and background wait events.
 - `load_profile_anomalies` — MAD-detected load profile anomalies
 - `anomaly_clusters` — temporally grouped anomalies across multiple domains
+  `area_of_anomaly` MULTIVARIATE marks snapshots where many metrics were unusual at the same time 
+  (robust Mahalanobis distance); `multivariate_anomaly_score` and `multivariate_top_contributors` show which metrics drove it.
+- `sql_performance_regressions` — SQL_IDs with a lasting level shift of per-execution metrics 
+  (elapsed/CPU time, buffer gets, physical reads per exec) and the snapshot where it began. 
+  `cause` separates SLOWER_PER_EXEC (each execution got more expensive) from MORE_EXECUTIONS 
+  (same cost per execution, only executed more often) and SLOWER_PER_EXEC_AND_MORE_EXECUTIONS.
+- `change_points` — lasting level or variance shifts (binary segmentation) of DB Time, DB CPU (`series_type` TM), 
+  top foreground wait events (EVENT) and load profile rates (LP), with mean/median/stddev of the segments 
+  before and after the snapshot where the change began. Unlike MAD anomalies these are new normals, not single spikes - 
+  correlate simultaneous shifts across series to find what changed.
+- `db_time_gradient_rolling` — (optional) Ridge gradient of DB Time vs wait events fitted separately in 
+  sliding windows or change-point segments (begin/end snapshot + top drivers). Use it to tell WHEN each driver 
+  mattered - a driver that dominates only some windows is a period-specific bottleneck, not a constant one.
+- `target_gradients` — (optional) gradient sections explaining a user-chosen `target` (FAMILY:name, e.g. 
+  EVENT:log file sync) instead of DB Time, one for each `feature_family`. Interpret them like DB Time gradients: 
+  they answer what drives the target metric.
+- `cpu_capacity` — (optional) Average Active Sessions (DB Time / elapsed) and CPU demand (DB CPU + resmgr:cpu quantum 
+  per second) compared with host CPUs and cores. `pressure_periods` are consecutive snapshots with status HIGH 
+  (above `high_threshold_pct` of CPUs) or SATURATED (demand >= CPUs); `resmgr_throttling` means Resource Manager 
+  kept sessions off CPU. Negative `min_headroom_cpus` means sessions queued for CPU - in such periods large 
+  Unaccounted DB Time and inflated wait times are a symptom of CPU starvation, not a separate root cause.
+- `capacity_forecast` — (optional) Holt-Winters projection (trend + daily/weekly seasonality) of DB Time, AAS, 
+  physical read/write MB/s and redo MB/s with 95% prediction interval at the end of `horizon_days`. `status` EXCEEDED, 
+  CROSSES or MAY_CROSS with `crossing_date` (forecast mean) and `crossing_date_upper95` (earliest plausible date) 
+  answer when the database runs out of headroom - report these dates explicitly, and treat a forecast with large 
+  `residual_stddev` relative to `last_day_mean` or `seasonality` none (short history) as low confidence.
+- `workload_regimes` — (optional) snapshots clustered by k-means into workload regimes (e.g. OLTP DAY, BATCH NIGHT, 
+  BACKUP). Each regime has a `label`, `typical_aas`, `dominant_hours`, `distinguishing_features` (z-scores vs all 
+  snapshots), its own top wait events and SQLs and optionally `db_time_gradient_top` fitted only inside the regime. 
+  Analyze each regime against its own norms - a wait event normal for nightly batch is not a problem in OLTP hours 
+  and vice versa. When MAD anomalies were scored per regime they are deviations from the regime, not from the whole period.
+- `rule_findings` — (optional) deterministic findings of diagnostic rules (built-in pack of classic Oracle patterns 
+  and user rules) with `severity`, `matched_snapshots`, `evidence` (worst matching snapshots with metric values) and 
+  `recommendation`. Treat them as verified facts - confirm or refine them with other sections, explain their root cause 
+  and do not contradict them without evidence. User rules (`source` other than builtin) encode local know-how of the DBA team.
+- `parameter_checks` — (optional) risky initialization parameter settings (non-default optimizer knobs, cursor_sharing, 
+  hidden underscore parameters, disabled statistics or async IO) and `sga_target`/`pga_aggregate_target` sizes that 
+  SGA Target / PGA Memory advisories recommend to increase. Link them to the symptoms they explain (e.g. hard parsing, 
+  temp spills, physical reads) instead of listing them separately.
+- `parameter_changes` — (optional) parameters changed between snapshots with DB Time per second before/after and 
+  `db_time_change_point` when DB Time shifted within 3 snapshots. A change coinciding with a change point is a strong 
+  root cause candidate - always mention it.
+- `redo_commit` — (optional) redo MB per log switch (estimate of redo log size), `recommended_log_size_mb` for 
+  `target_switches_per_hour`, median log file sync vs log file parallel write and `commit_bound`: IO means slow redo 
+  storage, CPU means sessions wait for LGWR or CPU (starvation, commit storms). `commit_outliers` are snapshots with 
+  unusual commit rate or very few user calls per transaction - point to row-by-row commits in the application.
+- `hot_objects` — (optional) segments ranked by `persistence_rank` (how often they are in Segment Statistics top lists) 
+  and `correlation_rank` (segment statistic most correlated with DB Time), with `top_sql_ids` referencing them when 
+  SQL text is available. Use them to name the objects behind wait events like buffer busy waits, row lock contention 
+  or physical reads and the SQLs to tune.
+- `shared_pool_health` — (optional) library cache reload %, SQL AREA get % miss, invalidations, `invalidation_storms`, 
+  dictionary cache (dc_*) miss trends, correlations of these metrics with hard parses/s, library cache: mutex X and 
+  cursor: pin S wait on X, and `findings`. Explain mutex and hard parse waits by shared pool pressure or cursor 
+  invalidations when the correlations support it.
+- `latch_contention` — (optional) latches ranked by % of DB Time of their wait event (latch: <name>, or a share of 
+  latch free split by sleeps), with get % miss, misses, sleeps and spin gets %. A high get % miss with no wait time is 
+  not a problem - judge latches by pct_db_time.
+- `mutex_contention` — (optional) mutex types from Mutex Sleep Summary with the wait events they cause, sleeps, wait 
+  time, % of DB Time and top code locations. Use the locations to tell hard parsing, cursor invalidation or hot 
+  object contention apart.
+- `io_slo_compliance` — (optional) IO latency SLOs per IO function or wait event with violations, compliance_pct, 
+  median, P95 and worst latency, `worst_period` (longest run of violating snapshots) and waits_within_slo_pct from 
+  wait event histograms. Report SLO violations as storage problems only when most waits are slow, not just the average.
+- `time_of_day_profiles` — (optional) calendar heatmap summary per metric (DB_TIME, AAS, EVENT:..., SQL:...): peak 
+  weekday and hour, busiest and quietest hour of day, busiest weekday and peak_to_median. Use it to tie problems to 
+  batch windows or business hours and to recommend when maintenance should run.
 - `initialization_parameters` — Oracle instance initialization parameters (name-value pairs). 
   Contains both explicit (user-set) and default parameter values from the analyzed instance.
 
@@ -384,11 +964,17 @@ The `db_cpu_gradient_sql_cpu_time` section is particularly important for CPU-bou
   consumes CPU but does not significantly impact overall DB Time — lower priority unless 
   CPU saturation is observed.
 
-Each gradient section contains results from four regression models:
+Each gradient section contains results from five regression models:
 - **Ridge** (`ridge_top`) — stabilized, dense ranking of all contributing factors
 - **Elastic Net** (`elastic_net_top`) — sparse ranking highlighting dominant factors
 - **Huber** (`huber_top`) — outlier-resistant ranking (downweights extreme snapshots)
 - **Quantile 95** (`quantile95_top`) — models the worst 5% of snapshots (tail risk)
+- **OLS** (`ols_top`) — least squares on a VIF-pruned predictor set with Newey-West (autocorrelation-robust) 
+  standard errors: `std_error`, `t_stat`, `p_value` per predictor and `ols_fit` (R², adjusted R², HAC lags). 
+  p_value < 0.05 = statistically defensible effect; quote it when a customer asks 'how sure are you'. 
+  Predictors are picked on the first `ols_fit.selection_snapshots` Δ-snapshots and p-values come from the rest only, 
+  so they are not inflated by the selection - but they rest on half of the data. 
+  Low adjusted R² means DB Time changes are poorly explained by the predictors of this section
 
 ### Gradient Impact Metrics (GradientTopItem fields)
 
@@ -415,6 +1001,11 @@ Each entry in `ridge_top` / `elastic_net_top` / `huber_top` / `quantile95_top` c
   Range [0.0, 1.0]. Use to communicate relative importance, e.g., 
   'this event explains 23% of DB Time variance'.
 
+- `coef_ci_low`/`coef_ci_high` and `impact_active_ci_low`/`impact_active_ci_high` — 95% block bootstrap 
+  intervals (resampling blocks of consecutive snapshots). Wide interval or interval containing zero means 
+  the estimate depends on a few snapshots. `selection_frequency` (Elastic Net only) — fraction of resamples 
+  in which the predictor was selected (stability selection).
+
 **Ranking rule:** Entries are sorted by signed_impact_active descending — positive contributors 
 (real bottlenecks) first, suppressors last. When reporting top factors, focus on entries with 
 gradient_coef > 0.
@@ -451,6 +1042,10 @@ Interpret classifications using this priority hierarchy:
   peak periods.
 - `STRONG_CONTRIBUTOR` (Ridge + EN + Huber, not Q95) — reliable systematic contributor. 
   MEDIUM priority.
+- `CONFIRMED_BOTTLENECK_NOT_SIGNIFICANT`, `CONFIRMED_BOTTLENECK_EN_COLLINEAR_NOT_SIGNIFICANT`, 
+  `STRONG_CONTRIBUTOR_NOT_SIGNIFICANT` — the same model pattern, but OLS estimated the predictor 
+  with HAC p-value >= 0.05. Ranking signal without statistical confirmation. MEDIUM priority — 
+  check collinear groups and say explicitly that significance is missing.
 - `OUTLIER_DRIVEN` (Ridge, not Huber) — few extreme snapshots only. MEDIUM priority — 
   check anomaly_clusters.
 - `SPARSE_DOMINANT` (EN, not Ridge) — dominant among correlated group. MEDIUM priority.
@@ -458,8 +1053,14 @@ Interpret classifications using this priority hierarchy:
   LOW-MEDIUM priority.
 - `ROBUST_ONLY` (Huber only) — background factor without outliers. LOW priority.
 - `MULTI_MODEL_MINOR` (2+ models, no pattern) — minor contributor. LOW priority.
+- `OLS_SIGNIFICANT_ONLY` (only OLS, p < 0.05) — real but small effect. INFORMATIONAL.
 - `SINGLE_MODEL` (1 model only) — low confidence. INFORMATIONAL.
 
+`in_ols` is true when OLS confirms the predictor with HAC p-value < 0.05; `ols_p_value` is given whenever 
+OLS estimated it. CONFIRMED_BOTTLENECK, CONFIRMED_BOTTLENECK_EN_COLLINEAR and STRONG_CONTRIBUTOR are 
+given only when OLS did not reject the predictor - with `ols_p_value` >= 0.05 they become the 
+*_NOT_SIGNIFICANT variants. Without `ols_p_value` (OLS did not estimate the predictor) the class is not downgraded.
+
 The `combined_impact` field in each classification is the sum of `impact_active` across all 
 models where the predictor appears — treat it as the aggregate active-impact signal.
 
@@ -498,6 +1099,15 @@ Each gradient section may contain:
    `db_time_gradient_sql_elapsed_time` to classify each SQL as CPU-dominant, wait-dominant, 
    or mixed — this determines whether optimization should target execution plans/LIOs (CPU) 
    or wait events/I/O (waits)
+9. Check bootstrap stability: `ci_crosses_zero_in` lists models where the 95% block bootstrap interval 
+   of the coefficient contains zero — downgrade confidence of such classification. For Elastic Net 
+   prefer factors with `selection_frequency` >= 0.6 (selected in most resamples)
+10. `settings.regularization_tuning` tells whether lambdas were set manually (none) or selected by 
+   cross-validation (cv/gcv); a large `*_validation_error` relative to DB Time variance means the 
+   linear models explain little — state lower confidence in gradient findings
+11. Names ending with `(lag N)` are deltas N snapshots earlier - a queuing effect that hits DB Time later; 
+   `A × B` is an interaction - DB Time grows when A and B rise together (e.g. commits AND slow log writes). 
+   `settings.pruned_features` were dropped as collinear (VIF) and carry no evidence either way
 
 # ANALYTICAL METHODOLOGY
 
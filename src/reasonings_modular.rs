@@ -327,7 +327,9 @@ and write a comprehensive statistical summary for all metrics before proceeding.
 
 The ReportForAI contains these analytical sections:
 - `general_data` — overall DB load shape description with MAD analysis
-- `top_spikes_marked` — peak periods with DB Time, DB CPU, and their ratio
+- `top_spikes_marked` — peak periods with DB Time, DB CPU, and their ratio. `db_time_vs_median` splits each 
+  peak into DB CPU, wait classes and Unaccounted (not instrumented, e.g. CPU run queue) with `delta_s` vs median 
+  and `share_of_change` - start the explanation of every peak from the components with the largest share
 - `top_foreground_wait_events` / `top_background_wait_events` — wait event statistics with 
   correlations, averages, stddevs, and MAD anomalies
 - `top_sqls_by_elapsed_time` — SQL-level metrics including cross-section presence, correlations, 
@@ -336,9 +338,71 @@ The ReportForAI contains these analytical sections:
 - `latch_activity_summary` — latch contention metrics
 - `top_10_segments_by_*` — 8 segment ranking sections (row lock waits, physical reads/writes, 
   logical reads, buffer busy waits, direct I/O). May be empty for STATSPACK reports.
-- `instance_stats_pearson_correlation` — instance statistics correlated with DB Time (|ρ| ≥ 0.5)
+- `instance_stats_pearson_correlation` — instance statistics correlated with DB Time by Pearson, Spearman, Kendall, 
+  lagged or partial correlation (Bonferroni corrected); `correlation_details.significant_by` lists the methods
 - `load_profile_anomalies` — MAD-detected load profile anomalies
-- `anomaly_clusters` — temporally grouped anomalies across multiple domains
+- `anomaly_clusters` — temporally grouped anomalies across multiple domains; MULTIVARIATE entries come from robust 
+  Mahalanobis distance across all metric families with top contributing metrics
+- `sql_performance_regressions` — SQL_IDs with a lasting level shift of per-execution metrics 
+  and the snapshot where it began; `cause` separates SLOWER_PER_EXEC from MORE_EXECUTIONS
+- `change_points` — lasting level or variance shifts of DB Time, DB CPU, top wait events and load profile 
+  with statistics of the segments before and after the change
+- `db_time_gradient_rolling` — (optional) Ridge gradient of DB Time vs wait events fitted separately in 
+  sliding windows or change-point segments (begin/end snapshot + top drivers). Use it to tell WHEN each driver 
+  mattered - a driver that dominates only some windows is a period-specific bottleneck, not a constant one.
+- `target_gradients` — (optional) gradient sections explaining a user-chosen `target` (FAMILY:name, e.g. 
+  EVENT:log file sync) instead of DB Time, one for each `feature_family`. Interpret them like DB Time gradients: 
+  they answer what drives the target metric.
+- `cpu_capacity` — (optional) Average Active Sessions (DB Time / elapsed) and CPU demand (DB CPU + resmgr:cpu quantum 
+  per second) compared with host CPUs and cores. `pressure_periods` are consecutive snapshots with status HIGH 
+  (above `high_threshold_pct` of CPUs) or SATURATED (demand >= CPUs); `resmgr_throttling` means Resource Manager 
+  kept sessions off CPU. Negative `min_headroom_cpus` means sessions queued for CPU - in such periods large 
+  Unaccounted DB Time and inflated wait times are a symptom of CPU starvation, not a separate root cause.
+- `capacity_forecast` — (optional) Holt-Winters projection (trend + daily/weekly seasonality) of DB Time, AAS, 
+  physical read/write MB/s and redo MB/s with 95% prediction interval at the end of `horizon_days`. `status` EXCEEDED, 
+  CROSSES or MAY_CROSS with `crossing_date` (forecast mean) and `crossing_date_upper95` (earliest plausible date) 
+  answer when the database runs out of headroom - report these dates explicitly, and treat a forecast with large 
+  `residual_stddev` relative to `last_day_mean` or `seasonality` none (short history) as low confidence.
+- `workload_regimes` — (optional) snapshots clustered by k-means into workload regimes (e.g. OLTP DAY, BATCH NIGHT, 
+  BACKUP). Each regime has a `label`, `typical_aas`, `dominant_hours`, `distinguishing_features` (z-scores vs all 
+  snapshots), its own top wait events and SQLs and optionally `db_time_gradient_top` fitted only inside the regime. 
+  Analyze each regime against its own norms - a wait event normal for nightly batch is not a problem in OLTP hours 
+  and vice versa. When MAD anomalies were scored per regime they are deviations from the regime, not from the whole period.
+- `rule_findings` — (optional) deterministic findings of diagnostic rules (built-in pack of classic Oracle patterns 
+  and user rules) with `severity`, `matched_snapshots`, `evidence` (worst matching snapshots with metric values) and 
+  `recommendation`. Treat them as verified facts - confirm or refine them with other sections, explain their root cause 
+  and do not contradict them without evidence. User rules (`source` other than builtin) encode local know-how of the DBA team.
+- `parameter_checks` — (optional) risky initialization parameter settings (non-default optimizer knobs, cursor_sharing, 
+  hidden underscore parameters, disabled statistics or async IO) and `sga_target`/`pga_aggregate_target` sizes that 
+  SGA Target / PGA Memory advisories recommend to increase. Link them to the symptoms they explain (e.g. hard parsing, 
+  temp spills, physical reads) instead of listing them separately.
+- `parameter_changes` — (optional) parameters changed between snapshots with DB Time per second before/after and 
+  `db_time_change_point` when DB Time shifted within 3 snapshots. A change coinciding with a change point is a strong 
+  root cause candidate - always mention it.
+- `redo_commit` — (optional) redo MB per log switch (estimate of redo log size), `recommended_log_size_mb` for 
+  `target_switches_per_hour`, median log file sync vs log file parallel write and `commit_bound`: IO means slow redo 
+  storage, CPU means sessions wait for LGWR or CPU (starvation, commit storms). `commit_outliers` are snapshots with 
+  unusual commit rate or very few user calls per transaction - point to row-by-row commits in the application.
+- `hot_objects` — (optional) segments ranked by `persistence_rank` (how often they are in Segment Statistics top lists) 
+  and `correlation_rank` (segment statistic most correlated with DB Time), with `top_sql_ids` referencing them when 
+  SQL text is available. Use them to name the objects behind wait events like buffer busy waits, row lock contention 
+  or physical reads and the SQLs to tune.
+- `shared_pool_health` — (optional) library cache reload %, SQL AREA get % miss, invalidations, `invalidation_storms`, 
+  dictionary cache (dc_*) miss trends, correlations of these metrics with hard parses/s, library cache: mutex X and 
+  cursor: pin S wait on X, and `findings`. Explain mutex and hard parse waits by shared pool pressure or cursor 
+  invalidations when the correlations support it.
+- `latch_contention` — (optional) latches ranked by % of DB Time of their wait event (latch: <name>, or a share of 
+  latch free split by sleeps), with get % miss, misses, sleeps and spin gets %. A high get % miss with no wait time is 
+  not a problem - judge latches by pct_db_time.
+- `mutex_contention` — (optional) mutex types from Mutex Sleep Summary with the wait events they cause, sleeps, wait 
+  time, % of DB Time and top code locations. Use the locations to tell hard parsing, cursor invalidation or hot 
+  object contention apart.
+- `io_slo_compliance` — (optional) IO latency SLOs per IO function or wait event with violations, compliance_pct, 
+  median, P95 and worst latency, `worst_period` (longest run of violating snapshots) and waits_within_slo_pct from 
+  wait event histograms. Report SLO violations as storage problems only when most waits are slow, not just the average.
+- `time_of_day_profiles` — (optional) calendar heatmap summary per metric (DB_TIME, AAS, EVENT:..., SQL:...): peak 
+  weekday and hour, busiest and quietest hour of day, busiest weekday and peak_to_median. Use it to tie problems to 
+  batch windows or business hours and to recommend when maintenance should run.
 - `initialization_parameters` — Oracle instance initialization parameters (name-value pairs). 
   Contains both explicit (user-set) and default parameter values from the analyzed instance.
 
@@ -366,11 +430,17 @@ The `db_cpu_gradient_sql_cpu_time` section is particularly important for CPU-bou
   consumes CPU but does not significantly impact overall DB Time — lower priority unless 
   CPU saturation is observed.
 
-Each gradient section contains results from four regression models:
+Each gradient section contains results from five regression models:
 - **Ridge** (`ridge_top`) — stabilized, dense ranking of all contributing factors
 - **Elastic Net** (`elastic_net_top`) — sparse ranking highlighting dominant factors
 - **Huber** (`huber_top`) — outlier-resistant ranking (downweights extreme snapshots)
 - **Quantile 95** (`quantile95_top`) — models the worst 5% of snapshots (tail risk)
+- **OLS** (`ols_top`) — least squares on a VIF-pruned predictor set with Newey-West (autocorrelation-robust) 
+  standard errors: `std_error`, `t_stat`, `p_value` per predictor and `ols_fit` (R², adjusted R², HAC lags). 
+  p_value < 0.05 = statistically defensible effect; quote it when a customer asks 'how sure are you'. 
+  Predictors are picked on the first `ols_fit.selection_snapshots` Δ-snapshots and p-values come from the rest only, 
+  so they are not inflated by the selection - but they rest on half of the data. 
+  Low adjusted R² means DB Time changes are poorly explained by the predictors of this section
 
 ### Cross-Model Classification Rules
 
@@ -384,13 +454,20 @@ Interpret classifications using this priority hierarchy:
 | `TAIL_OUTLIER` | Ridge+Q95 (not Huber) | Extreme snapshots that ARE the worst periods | HIGH |
 | `TAIL_RISK` | Q95 (not Ridge) | Rare catastrophic spikes | HIGH — warn about peak periods |
 | `STRONG_CONTRIBUTOR` | Ridge+EN+Huber (not Q95) | Reliable systematic contributor | MEDIUM |
+| `*_NOT_SIGNIFICANT` | Pattern of the three classes above, OLS p >= 0.05 | Ranking signal without statistical confirmation | MEDIUM — say that significance is missing |
 | `OUTLIER_DRIVEN` | Ridge (not Huber) | Few extreme snapshots only | MEDIUM — check anomaly_clusters |
 | `SPARSE_DOMINANT` | EN (not Ridge) | Dominant among correlated group | MEDIUM |
 | `STABLE_CONTRIBUTOR` | Ridge+Huber (not EN, Q95) | Steady background contributor | LOW-MEDIUM |
 | `ROBUST_ONLY` | Huber only | Background factor without outliers | LOW |
 | `MULTI_MODEL_MINOR` | 2+ models, no pattern | Minor contributor | LOW |
+| `OLS_SIGNIFICANT_ONLY` | OLS only (p < 0.05) | Real but small effect | INFORMATIONAL |
 | `SINGLE_MODEL` | 1 model only | Low confidence | INFORMATIONAL |
 
+`in_ols` is true when OLS confirms the predictor with HAC p-value < 0.05; `ols_p_value` is given whenever 
+OLS estimated it. CONFIRMED_BOTTLENECK, CONFIRMED_BOTTLENECK_EN_COLLINEAR and STRONG_CONTRIBUTOR with 
+`ols_p_value` >= 0.05 are downgraded to CONFIRMED_BOTTLENECK_NOT_SIGNIFICANT, 
+CONFIRMED_BOTTLENECK_EN_COLLINEAR_NOT_SIGNIFICANT and STRONG_CONTRIBUTOR_NOT_SIGNIFICANT.
+
 **Gradient analysis strategy:**
 1. Start with CONFIRMED_BOTTLENECK and CONFIRMED_BOTTLENECK_EN_COLLINEAR — highest priority
 2. Flag TAIL_RISK and TAIL_OUTLIER items as hidden dangers
@@ -401,6 +478,15 @@ Interpret classifications using this priority hierarchy:
    `db_time_gradient_sql_elapsed_time` to classify each SQL as CPU-dominant, wait-dominant, 
    or mixed — this determines whether optimization should target execution plans/LIOs (CPU) 
    or wait events/I/O (waits)
+7. Check bootstrap stability: `ci_crosses_zero_in` lists models where the 95% block bootstrap interval 
+   of the coefficient contains zero — downgrade confidence of such classification. For Elastic Net 
+   prefer factors with `selection_frequency` >= 0.6 (selected in most resamples)
+8. `settings.regularization_tuning` tells whether lambdas were set manually (none) or selected by 
+   cross-validation (cv/gcv); a large `*_validation_error` relative to DB Time variance means the 
+   linear models explain little — state lower confidence in gradient findings
+9. Names ending with `(lag N)` are deltas N snapshots earlier - a queuing effect that hits DB Time later; 
+   `A × B` is an interaction - DB Time grows when A and B rise together (e.g. commits AND slow log writes). 
+   `settings.pruned_features` were dropped as collinear (VIF) and carry no evidence either way
 
 # ANALYTICAL METHODOLOGY
 
@@ -564,6 +650,7 @@ INPUT:
 Requirements:
 - Rank top events by avg_pct_of_dbtime (top 5) and show exact values.
 - For each top event include: correlation_with_db_time, avg_pct_of_dbtime, avg_wait_time_s, avg_wait_for_execution_ms, marked_as_top_in_pct_of_probes.
+- If correlation_details.significant_by contains LAG or the event is significant only by SPEARMAN/KENDALL, mention the lead/lag or non-linear relation with DB Time.
 - If MAD anomalies exist: list up to 5 with anomaly_date, mad_score, pct_of_db_time.
 - Cross-reference anomaly dates with spikes using context capsule when possible.
 - Provide DBA + DEV actions backed by evidence.
@@ -933,6 +1020,7 @@ fn trim_foreground_waits_by_budget(
                 "top_foreground_wait_events": [{
                     "event_name": e.event_name,
                     "correlation_with_db_time": e.correlation_with_db_time,
+                    "correlation_details": e.correlation_details,
                     "marked_as_top_in_pct_of_probes": e.marked_as_top_in_pct_of_probes,
                     "avg_pct_of_dbtime": e.avg_pct_of_dbtime,
                     "stddev_pct_of_db_time": e.stddev_pct_of_db_time,
@@ -998,6 +1086,7 @@ fn trim_background_waits_by_budget(
                 "top_background_wait_events": [{
                     "event_name": e.event_name,
                     "correlation_with_db_time": e.correlation_with_db_time,
+                    "correlation_details": e.correlation_details,
                     "marked_as_top_in_pct_of_probes": e.marked_as_top_in_pct_of_probes,
                     "avg_pct_of_dbtime": e.avg_pct_of_dbtime,
                     "stddev_pct_of_db_time": e.stddev_pct_of_db_time,
@@ -0,0 +1,304 @@
+use std::fs;
+use std::path::Path;
+use crate::awr::AWR;
+use crate::Args;
+use crate::capacity::snapshot_elapsed_s;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, RedoCommitAnalysis, CommitOutlier};
+use colored::*;
+use plotly::{Plot, Scatter};
+use plotly::common::{DashType, Line, Marker, MarkerSymbol, Mode};
+use plotly::layout::{Axis, HoverMode, Layout, RangeMode};
+use prettytable::{Table, Row, Cell};
+
+/* Redo generation and commit pressure.
+   Redo MB per log switch = Redo size per second * 3600 / log switches per hour - with logs switched by size
+   it estimates the size of online redo logs. Recommended size is P95 of redo MB per hour divided by the target
+   number of switches per hour (--redo-target-switches).
+   log file sync = post of LGWR + redo write (log file parallel write) + wake up of the committing session.
+   When redo write is most of log file sync, commits are IO bound (storage of redo logs); when it is a small part,
+   sessions wait for LGWR or for CPU to be scheduled (CPU starvation, too many commits in parallel).
+   Commit rate and user calls per transaction are checked with robust z-score for snapshots that commit unusually often. */
+
+/// log file sync below this average (ms) is treated as healthy
+const LOG_FILE_SYNC_MIN_MS: f64 = 2.0;
+/// log file parallel write / log file sync ratio from which commits are IO bound
+const IO_BOUND_RATIO: f64 = 0.6;
+/// log file parallel write / log file sync ratio below which commits are CPU (scheduling) bound
+const CPU_BOUND_RATIO: f64 = 0.4;
+/// Recommended redo log size is rounded up to this number of MB
+const LOG_SIZE_ROUND_MB: f64 = 256.0;
+/// Robust z-score of commit rate (or of user calls per transaction below the median) from which a snapshot is a commit outlier
+const COMMIT_OUTLIER_Z: f64 = 3.5;
+
+struct SnapRedo {
+    x_val: String,
+    snap_id: u64,
+    snap_date: String,
+    redo_mb_per_s: f64,
+    switches_per_hour: f64,
+    redo_mb_per_switch: Option<f64>,
+    log_file_sync_ms: Option<f64>,
+    log_file_parallel_write_ms: Option<f64>,
+    commit_bound: &'static str,
+    commits_per_s: Option<f64>,
+    calls_per_transaction: Option<f64>,
+}
+
+fn instance_stat(awr: &AWR, name: &str) -> Option<f64> {
+    awr.instance_stats.iter().find(|s| s.statname == name).map(|s| s.total as f64)
+}
+
+/// IO, CPU or MIXED by share of log file parallel write in log file sync, OK for fast commits
+fn commit_bound(lfs_ms: f64, lfpw_ms: Option<f64>) -> &'static str {
+    if lfs_ms < LOG_FILE_SYNC_MIN_MS {
+        return "OK";
+    }
+    match lfpw_ms {
+        Some(w) if w / lfs_ms >= IO_BOUND_RATIO => "IO",
+        Some(w) if w / lfs_ms < CPU_BOUND_RATIO => "CPU",
+        Some(_) => "MIXED",
+        None => "UNKNOWN",
+    }
+}
+
+fn snapshot_redo(awr: &AWR) -> SnapRedo {
+    let redo_mb_per_s = awr.load_profile.iter()
+        .find(|lp| lp.stat_name.starts_with("Redo size"))
+        .map_or(0.0, |lp| lp.per_second / (1024.0 * 1024.0));
+    let switches_per_hour = awr.redo_log.per_hour;
+    let log_file_sync_ms = awr.foreground_wait_events.iter()
+        .find(|e| e.event == "log file sync" && e.waits > 0)
+        .map(|e| e.avg_wait);
+    let log_file_parallel_write_ms = awr.background_wait_events.iter()
+        .find(|e| e.event == "log file parallel write" && e.waits > 0)
+        .map(|e| e.avg_wait);
+
+    let commits = instance_stat(awr, "user commits");
+    let rollbacks = instance_stat(awr, "user rollbacks").unwrap_or(0.0);
+    let calls = instance_stat(awr, "user calls");
+    let commits_per_s = match (commits, snapshot_elapsed_s(awr)) {
+        (Some(c), Some(elapsed)) => Some(c / elapsed),
+        _ => None,
+    };
+    let calls_per_transaction = match (calls, commits) {
+        (Some(calls), Some(c)) if c + rollbacks > 0.0 => Some(calls / (c + rollbacks)),
+        _ => None,
+    };
+
+    SnapRedo {
+        x_val: format!("{} ({})", awr.snap_info.begin_snap_time, awr.snap_info.begin_snap_id),
+        snap_id: awr.snap_info.begin_snap_id,
+        snap_date: awr.snap_info.begin_snap_time.clone(),
+        redo_mb_per_s,
+        switches_per_hour,
+        redo_mb_per_switch: if switches_per_hour > 0.0 && redo_mb_per_s > 0.0 { Some(redo_mb_per_s * 3600.0 / switches_per_hour) } else { None },
+        log_file_sync_ms,
+        log_file_parallel_write_ms,
+        commit_bound: log_file_sync_ms.map_or("UNKNOWN", |lfs| commit_bound(lfs, log_file_parallel_write_ms)),
+        commits_per_s,
+        calls_per_transaction,
+    }
+}
+
+/// Snapshots with commit rate far above or user calls per transaction far below the median
+fn commit_outliers(snaps: &[SnapRedo], threshold: f64) -> Vec<CommitOutlier> {
+    let with_data: Vec<&SnapRedo> = snaps.iter()
+        .filter(|s| s.commits_per_s.is_some() && s.calls_per_transaction.is_some())
+        .collect();
+    if with_data.len() < 3 {
+        return Vec::new();
+    }
+    let commits: Vec<f64> = with_data.iter().map(|s| s.commits_per_s.unwrap_or(0.0)).collect();
+    let calls: Vec<f64> = with_data.iter().map(|s| s.calls_per_transaction.unwrap_or(0.0)).collect();
+    let commits_z = robust_z_score(&commits);
+    let calls_z = robust_z_score(&calls);
+
+    let mut outliers: Vec<CommitOutlier> = with_data.iter().enumerate()
+        .filter(|(i, _)| commits_z[*i] >= threshold || calls_z[*i] <= -threshold)
+        .map(|(i, s)| CommitOutlier {
+            snap_id: s.snap_id,
+            snap_date: s.snap_date.clone(),
+            commits_per_s: commits[i],
+            user_calls_per_transaction: calls[i],
+            commits_robust_z: commits_z[i],
+            calls_per_transaction_robust_z: calls_z[i],
+            log_file_sync_ms: s.log_file_sync_ms.unwrap_or(0.0),
+        })
+        .collect();
+    let score = |o: &CommitOutlier| o.commits_robust_z.max(-o.calls_per_transaction_robust_z);
+    outliers.sort_by(|a, b| score(b).partial_cmp(&score(a)).unwrap_or(std::cmp::Ordering::Equal));
+    outliers
+}
+
+/// Redo log sizing and commit pressure analysis. Writes stats/redo_commits.html and attaches
+/// RedoCommitAnalysis to ReportForAI. Returns true if the page was written.
+pub fn report_redo_commits(awrs: &[AWR], snap_range: &(u64,u64), args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) -> bool {
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{}\n", "Redo log sizing and commit pressure - log file sync vs log file parallel write".yellow());
+
+    let snaps: Vec<SnapRedo> = snaps_in_range(awrs, snap_range)
+        .map(snapshot_redo)
+        .collect();
+    if snaps.iter().all(|s| s.redo_mb_per_s == 0.0 && s.log_file_sync_ms.is_none()) {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo redo generation or log file sync waits found\n".green().italic());
+        return false;
+    }
+
+    let redo: Vec<f64> = snaps.iter().map(|s| s.redo_mb_per_s).collect();
+    let switches: Vec<f64> = snaps.iter().map(|s| s.switches_per_hour).collect();
+    let per_switch: Vec<f64> = snaps.iter().filter_map(|s| s.redo_mb_per_switch).collect();
+    let lfs: Vec<f64> = snaps.iter().filter_map(|s| s.log_file_sync_ms).collect();
+    let lfpw: Vec<f64> = snaps.iter().filter_map(|s| s.log_file_parallel_write_ms).collect();
+    // Commits are classified by median share of redo write in log file sync of snapshots with slow commits
+    let slow_ratios: Vec<f64> = snaps.iter()
+        .filter_map(|s| match (s.log_file_sync_ms, s.log_file_parallel_write_ms) {
+            (Some(sync), Some(write)) if sync >= LOG_FILE_SYNC_MIN_MS => Some(write / sync),
+            _ => None,
+        })
+        .collect();
+    let overall_bound = if lfs.is_empty() {
+        "UNKNOWN"
+    } else if slow_ratios.is_empty() {
+        "OK"
+    } else {
+        let ratio = median(&slow_ratios);
+        if ratio >= IO_BOUND_RATIO { "IO" } else if ratio < CPU_BOUND_RATIO { "CPU" } else { "MIXED" }
+    };
+    let recommended_log_size_mb = if args.redo_target_switches > 0.0 {
+        let mb = percentile(&redo, 95.0) * 3600.0 / args.redo_target_switches;
+        ((mb / LOG_SIZE_ROUND_MB).ceil() * LOG_SIZE_ROUND_MB) as u64
+    } else {
+        0
+    };
+
+    let analysis = RedoCommitAnalysis {
+        redo_mb_per_s_median: median(&redo),
+        redo_mb_per_s_p95: percentile(&redo, 95.0),
+        log_switches_per_hour_median: median(&switches),
+        log_switches_per_hour_max: switches.iter().cloned().fold(0.0, f64::max),
+        redo_mb_per_switch_median: median(&per_switch),
+        target_switches_per_hour: args.redo_target_switches,
+        recommended_log_size_mb,
+        log_file_sync_ms_median: median(&lfs),
+        log_file_parallel_write_ms_median: median(&lfpw),
+        commit_bound: overall_bound.to_string(),
+        snapshots_io_bound: snaps.iter().filter(|s| s.commit_bound == "IO").count(),
+        snapshots_cpu_bound: snaps.iter().filter(|s| s.commit_bound == "CPU").count(),
+        snapshots_mixed: snaps.iter().filter(|s| s.commit_bound == "MIXED").count(),
+        commit_outliers: commit_outliers(&snaps, COMMIT_OUTLIER_Z),
+    };
+
+    make_notes!(logfile_name, args.quiet, 0, "\tRedo MB/s median/P95: {:.2}/{:.2}  Log switches/h median/max: {:.1}/{:.1}  Redo MB per switch (median): {:.0}\n",
+        analysis.redo_mb_per_s_median, analysis.redo_mb_per_s_p95,
+        analysis.log_switches_per_hour_median, analysis.log_switches_per_hour_max, analysis.redo_mb_per_switch_median);
+    if recommended_log_size_mb > 0 {
+        make_notes!(logfile_name, args.quiet, 0, "\tRecommended online redo log size for {} switches/h: {} MB\n",
+            args.redo_target_switches, recommended_log_size_mb);
+    }
+    make_notes!(logfile_name, args.quiet, 0, "\tlog file sync median: {:.2} ms  log file parallel write median: {:.2} ms  Commits are: {} bound (snapshots IO: {}, CPU: {}, MIXED: {})\n",
+        analysis.log_file_sync_ms_median, analysis.log_file_parallel_write_ms_median, analysis.commit_bound,
+        analysis.snapshots_io_bound, analysis.snapshots_cpu_bound, analysis.snapshots_mixed);
+
+    let outlier_headers = ["Snap ID", "Snap Date", "Commits/s", "User Calls/Transaction", "Commits Robust Z", "Calls/Transaction Robust Z", "log file sync (ms)"];
+    let mut outlier_table = Table::new();
+    outlier_table.set_titles(Row::new(outlier_headers.iter().map(|h| Cell::new(h)).collect()));
+    for o in &analysis.commit_outliers {
+        outlier_table.add_row(Row::new(vec![
+            Cell::new(&format!("{}", o.snap_id)),
+            Cell::new(&o.snap_date),
+            Cell::new(&format!("{:.2}", o.commits_per_s)),
+            Cell::new(&format!("{:.2}", o.user_calls_per_transaction)),
+            Cell::new(&format!("{:.2}", o.commits_robust_z)),
+            Cell::new(&format!("{:.2}", o.calls_per_transaction_robust_z)),
+            Cell::new(&format!("{:.2}", o.log_file_sync_ms)),
+        ]));
+    }
+    if analysis.commit_outliers.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo commit outliers found\n".green().italic());
+    } else {
+        make_notes!(logfile_name, args.quiet, 0, "\n\tCommit outliers:\n");
+        make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&outlier_table));
+    }
+
+    // Redo and commits page - four panels sharing snapshot axis
+    let x_vals: Vec<String> = snaps.iter().map(|s| s.x_val.clone()).collect();
+    let opt = |v: Option<f64>| v.unwrap_or(f64::NAN);
+    let mut plot = Plot::new();
+    plot.add_trace(Scatter::new(x_vals.clone(), snaps.iter().map(|s| opt(s.redo_mb_per_switch)).collect::<Vec<f64>>())
+        .mode(Mode::LinesMarkers).name("Redo MB per switch").marker(Marker::new().color("#1f77b4")).y_axis("y4"));
+    if recommended_log_size_mb > 0 {
+        plot.add_trace(Scatter::new(x_vals.clone(), vec![recommended_log_size_mb as f64; x_vals.len()])
+            .mode(Mode::Lines).name(format!("Recommended log size ({} MB)", recommended_log_size_mb))
+            .line(Line::new().color("#2ca02c").dash(DashType::Dash)).y_axis("y4"));
+    }
+    plot.add_trace(Scatter::new(x_vals.clone(), switches.clone())
+        .mode(Mode::Lines).name("Log switches/h").marker(Marker::new().color("#8c564b")).y_axis("y3"));
+    plot.add_trace(Scatter::new(x_vals.clone(), vec![args.redo_target_switches; x_vals.len()])
+        .mode(Mode::Lines).name(format!("Target switches/h ({})", args.redo_target_switches))
+        .line(Line::new().color("#2ca02c").dash(DashType::Dash)).y_axis("y3"));
+    plot.add_trace(Scatter::new(x_vals.clone(), snaps.iter().map(|s| opt(s.log_file_sync_ms)).collect::<Vec<f64>>())
+        .mode(Mode::Lines).name("log file sync (ms)").marker(Marker::new().color("#d62728")).y_axis("y2"));
+    plot.add_trace(Scatter::new(x_vals.clone(), snaps.iter().map(|s| opt(s.log_file_parallel_write_ms)).collect::<Vec<f64>>())
+        .mode(Mode::Lines).name("log file parallel write (ms)").marker(Marker::new().color("#ff7f0e")).y_axis("y2"));
+    let slow: Vec<&SnapRedo> = snaps.iter().filter(|s| s.commit_bound == "IO" || s.commit_bound == "CPU" || s.commit_bound == "MIXED").collect();
+    if !slow.is_empty() {
+        plot.add_trace(Scatter::new(slow.iter().map(|s| s.x_val.clone()).collect::<Vec<String>>(), slow.iter().map(|s| opt(s.log_file_sync_ms)).collect::<Vec<f64>>())
+            .mode(Mode::Markers)
+            .name("Slow commits")
+            .text_array(slow.iter().map(|s| format!("{} bound", s.commit_bound)).collect::<Vec<String>>())
+            .hover_template("<b>%{text}</b><br>%{x}<br>log file sync: %{y:.2} ms<extra></extra>")
+            .marker(Marker::new().color("#d62728").size(9).symbol(MarkerSymbol::TriangleUp))
+            .y_axis("y2"));
+    }
+    plot.add_trace(Scatter::new(x_vals.clone(), snaps.iter().map(|s| opt(s.calls_per_transaction)).collect::<Vec<f64>>())
+        .mode(Mode::Lines).name("User calls per transaction").marker(Marker::new().color("#9467bd")));
+    if !analysis.commit_outliers.is_empty() {
+        let idx: Vec<usize> = analysis.commit_outliers.iter()
+            .filter_map(|o| snaps.iter().position(|s| s.snap_id == o.snap_id))
+            .collect();
+        plot.add_trace(Scatter::new(idx.iter().map(|i| x_vals[*i].clone()).collect::<Vec<String>>(), idx.iter().map(|i| opt(snaps[*i].calls_per_transaction)).collect::<Vec<f64>>())
+            .mode(Mode::Markers)
+            .name("Commit outliers")
+            .text_array(idx.iter().map(|i| format!("commits/s: {:.2}", opt(snaps[*i].commits_per_s))).collect::<Vec<String>>())
+            .hover_template("<b>Commit outlier</b><br>%{x}<br>user calls per transaction: %{y:.2}<br>%{text}<extra></extra>")
+            .marker(Marker::new().color("#e377c2").size(10).symbol(MarkerSymbol::Diamond)));
+    }
+    plot.set_layout(Layout::new()
+        .title(format!("Redo log sizing and commit pressure - commits are {} bound", analysis.commit_bound))
+        .height(1200)
+        .hover_mode(HoverMode::X)
+        .y_axis(Axis::new().domain(&[0.0, 0.22]).anchor("x").title("Calls / transaction").range_mode(RangeMode::ToZero))
+        .y_axis2(Axis::new().domain(&[0.26, 0.48]).anchor("x").title("Commit latency (ms)").range_mode(RangeMode::ToZero))
+        .y_axis3(Axis::new().domain(&[0.52, 0.74]).anchor("x").title("Log switches/h").range_mode(RangeMode::ToZero))
+        .y_axis4(Axis::new().domain(&[0.78, 1.0]).anchor("x").title("Redo MB").range_mode(RangeMode::ToZero)));
+
+    let summary_headers = ["Metric", "Value"];
+    let mut summary_table = Table::new();
+    summary_table.set_titles(Row::new(summary_headers.iter().map(|h| Cell::new(h)).collect()));
+    for (metric, value) in [
+        ("Redo MB/s median / P95", format!("{:.2} / {:.2}", analysis.redo_mb_per_s_median, analysis.redo_mb_per_s_p95)),
+        ("Log switches/h median / max", format!("{:.1} / {:.1}", analysis.log_switches_per_hour_median, analysis.log_switches_per_hour_max)),
+        ("Redo MB per switch (median)", format!("{:.0}", analysis.redo_mb_per_switch_median)),
+        ("Target switches/h", format!("{}", analysis.target_switches_per_hour)),
+        ("Recommended log size (MB)", format!("{}", analysis.recommended_log_size_mb)),
+        ("log file sync median (ms)", format!("{:.2}", analysis.log_file_sync_ms_median)),
+        ("log file parallel write median (ms)", format!("{:.2}", analysis.log_file_parallel_write_ms_median)),
+        ("Commits are bound by", analysis.commit_bound.clone()),
+        ("Snapshots IO / CPU / MIXED bound", format!("{} / {} / {}", analysis.snapshots_io_bound, analysis.snapshots_cpu_bound, analysis.snapshots_mixed)),
+    ] {
+        summary_table.add_row(Row::new(vec![Cell::new(metric), Cell::new(&value)]));
+    }
+
+    let fname = format!("{}/stats/redo_commits.html", html_dir);
+    plot.write_html(Path::new(&fname));
+    let mut page_html = table_to_html_string(&summary_table, "Redo and commits summary", &summary_headers);
+    page_html.push_str(&table_to_html_string(&outlier_table, "Commit outliers", &outlier_headers));
+    if let Err(e) = append_html_before_body_end(&fname, &page_html) {
+        eprintln!("Error writing file {}: {}", fname, e);
+    }
+
+    report_for_ai.redo_commit = Some(analysis);
+    true
+}
@@ -0,0 +1,516 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use chrono::{Datelike, Timelike, Weekday};
+use crate::awr::AWR;
+use crate::Args;
+use crate::capacity::snapshot_elapsed_s;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, WorkloadRegime};
+use colored::*;
+use plotly::{Plot, Scatter};
+use plotly::common::{Line, Marker, Mode};
+use plotly::layout::{Axis, HoverMode, Layout, Shape, ShapeLayer, ShapeLine, ShapeType};
+use prettytable::{Table, Row, Cell};
+
+/* Workload regimes.
+   Every snapshot is described by a vector of Load Profile rates, Time Model statistics and wait class times
+   (both per second of the snapshot). Values are log1p transformed (rates are heavily skewed) and standardized,
+   then clustered with k-means (k-means++ seeding, several restarts). With --regimes auto the number of regimes
+   is chosen by the simplified silhouette:
+        s(i) = (b - a) / max(a, b)     a - distance to own centroid, b - distance to the nearest other centroid
+   Regimes are learned from all loaded snapshots, so anomaly detection can compare every probe
+   with its own regime (an OLTP day is not judged by batch night norms). */
+
+/// Number of k-means restarts with different seeds - the one with the lowest inertia wins
+const KMEANS_RESTARTS: u64 = 5;
+const KMEANS_MAX_ITER: usize = 100;
+/// With automatic k, the best silhouette has to be at least this high, otherwise all snapshots are one regime
+const REGIMES_MIN_SILHOUETTE: f64 = 0.15;
+/// Minimal number of snapshots per regime for the automatic k
+const REGIME_MIN_SNAPS: usize = 3;
+/// Share of wait time in RMAN/backup events above which regime is labeled BACKUP
+const BACKUP_WAIT_SHARE: f64 = 0.2;
+const TOP_N: usize = 5;
+const REGIME_COLORS: [(u8, u8, u8); 8] = [
+    (31, 119, 180), (44, 160, 44), (255, 127, 14), (148, 103, 189),
+    (140, 86, 75), (227, 119, 194), (23, 190, 207), (188, 189, 34),
+];
+
+/// Regime of each loaded snapshot (aligned with awrs) and regime names
+pub struct WorkloadRegimes {
+    pub snap_ids: Vec<u64>,
+    pub labels: Vec<usize>,
+    pub names: Vec<String>,
+    pub silhouette: f64,
+    centroids: Vec<Vec<f64>>,
+    feature_names: Vec<String>,
+    features: Vec<Vec<f64>>,
+    label_by_snap: HashMap<u64, usize>,
+}
+
+impl WorkloadRegimes {
+    pub fn label_of(&self, snap_id: u64) -> Option<usize> {
+        self.label_by_snap.get(&snap_id).copied()
+    }
+}
+
+pub fn regime_color(regime: usize, alpha: f64) -> String {
+    let (r, g, b) = REGIME_COLORS[regime % REGIME_COLORS.len()];
+    format!("rgba({},{},{},{})", r, g, b, alpha)
+}
+
+/// Standardized feature vectors of all snapshots: log1p of Load Profile, Time Model and wait class rates
+fn snapshot_features(awrs: &[AWR]) -> (Vec<String>, Vec<Vec<f64>>) {
+    let elapsed: Vec<Option<f64>> = awrs.iter()
+        .map(snapshot_elapsed_s)
+        .collect();
+    let known: Vec<f64> = elapsed.iter().flatten().copied().collect();
+    let default_elapsed = if known.is_empty() { 1.0 } else { median(&known) };
+
+    let rows: Vec<BTreeMap<String, f64>> = awrs.iter().zip(elapsed.iter()).map(|(awr, el)| {
+        let el = el.unwrap_or(default_elapsed);
+        let mut row: BTreeMap<String, f64> = BTreeMap::new();
+        for lp in &awr.load_profile {
+            row.insert(format!("LP: {}", lp.stat_name.trim_end_matches(':')), lp.per_second);
+        }
+        for tm in &awr.time_model_stats {
+            row.insert(format!("TM: {}", tm.stat_name), tm.time_s / el);
+        }
+        for wc in awr.wait_classes.iter().filter(|wc| wc.wait_class != "Idle" && wc.wait_class != "DB CPU") {
+            row.insert(format!("WC: {}", wc.wait_class), wc.total_wait_time_s / el);
+        }
+        row
+    }).collect();
+
+    let mut names: Vec<String> = rows.iter().flat_map(|r| r.keys().cloned()).collect();
+    names.sort();
+    names.dedup();
+
+    let mut columns: Vec<(String, Vec<f64>)> = Vec::new();
+    for name in names {
+        let raw: Vec<f64> = rows.iter().map(|r| r.get(&name).copied().unwrap_or(0.0).max(0.0).ln_1p()).collect();
+        let z = z_score_normalize(&raw);
+        if z.iter().all(|v| v.is_finite()) && z.iter().any(|v| v.abs() > 1e-9) {
+            columns.push((name, z));
+        }
+    }
+    let feature_names: Vec<String> = columns.iter().map(|(n, _)| n.clone()).collect();
+    let features: Vec<Vec<f64>> = (0..awrs.len()).map(|i| columns.iter().map(|(_, c)| c[i]).collect()).collect();
+    (feature_names, features)
+}
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+fn nearest(point: &[f64], centroids: &[Vec<f64>]) -> (usize, f64) {
+    centroids.iter()
+        .enumerate()
+        .map(|(c, centroid)| (c, squared_distance(point, centroid)))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .unwrap_or((0, 0.0))
+}
+
+/// Lloyd's k-means with k-means++ seeding - returns centroids, labels and inertia
+fn kmeans(data: &[Vec<f64>], k: usize, rng: &mut SplitMix64) -> (Vec<Vec<f64>>, Vec<usize>, f64) {
+    let n = data.len();
+    let mut centroids: Vec<Vec<f64>> = vec![data[rng.next_index(n)].clone()];
+    while centroids.len() < k {
+        let d2: Vec<f64> = data.iter().map(|p| nearest(p, &centroids).1).collect();
+        let total: f64 = d2.iter().sum();
+        if total <= 0.0 {
+            centroids.push(data[rng.next_index(n)].clone());
+            continue;
+        }
+        let mut target = (rng.next_u64() as f64 / u64::MAX as f64) * total;
+        let mut chosen = n - 1;
+        for (i, d) in d2.iter().enumerate() {
+            if target <= *d {
+                chosen = i;
+                break;
+            }
+            target -= d;
+        }
+        centroids.push(data[chosen].clone());
+    }
+
+    let dims = data[0].len();
+    let mut labels = vec![usize::MAX; n];
+    for _ in 0..KMEANS_MAX_ITER {
+        let new_labels: Vec<usize> = data.iter().map(|p| nearest(p, &centroids).0).collect();
+        if new_labels == labels {
+            break;
+        }
+        labels = new_labels;
+        let mut sums = vec![vec![0.0; dims]; k];
+        let mut counts = vec![0usize; k];
+        for (p, &l) in data.iter().zip(&labels) {
+            counts[l] += 1;
+            for (s, v) in sums[l].iter_mut().zip(p) {
+                *s += v;
+            }
+        }
+        for c in 0..k {
+            if counts[c] > 0 {
+                centroids[c] = sums[c].iter().map(|s| s / counts[c] as f64).collect();
+            } else {
+                // Empty cluster takes the point farthest from its centroid
+                let far = (0..n)
+                    .max_by(|a, b| squared_distance(&data[*a], &centroids[labels[*a]]).partial_cmp(&squared_distance(&data[*b], &centroids[labels[*b]])).unwrap_or(std::cmp::Ordering::Equal))
+                    .unwrap_or(0);
+                centroids[c] = data[far].clone();
+            }
+        }
+    }
+    let inertia = data.iter().zip(&labels).map(|(p, &l)| squared_distance(p, &centroids[l])).sum();
+    (centroids, labels, inertia)
+}
+
+/// Simplified silhouette - distances to centroids instead of all pairs of points
+fn simplified_silhouette(data: &[Vec<f64>], centroids: &[Vec<f64>], labels: &[usize]) -> f64 {
+    if centroids.len() < 2 || data.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = data.iter().zip(labels).map(|(p, &l)| {
+        let a = squared_distance(p, &centroids[l]).sqrt();
+        let b = centroids.iter()
+            .enumerate()
+            .filter(|(c, _)| *c != l)
+            .map(|(_, c)| squared_distance(p, c).sqrt())
+            .fold(f64::INFINITY, f64::min);
+        if a.max(b) > 0.0 { (b - a) / a.max(b) } else { 0.0 }
+    }).sum();
+    total / data.len() as f64
+}
+
+fn best_kmeans(data: &[Vec<f64>], k: usize) -> (Vec<Vec<f64>>, Vec<usize>, f64) {
+    (0..KMEANS_RESTARTS)
+        .map(|seed| kmeans(data, k, &mut SplitMix64::new(42 + seed)))
+        .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal))
+        .unwrap()
+}
+
+/// Clusters all loaded snapshots into workload regimes according to --regimes (none, auto or number of regimes).
+/// Regimes are numbered by size - regime 0 is the biggest one.
+pub fn cluster_workload_regimes(awrs: &[AWR], args: &Args) -> Option<WorkloadRegimes> {
+    let requested_k: Option<usize> = match args.regimes.as_str() {
+        "none" => return None,
+        "auto" => None,
+        other => match other.parse::<usize>() {
+            Ok(k) if k >= 1 => Some(k),
+            _ => {
+                eprintln!("{} {}", "Unknown workload regimes mode, expected none, auto or number of regimes:".red(), other);
+                return None;
+            }
+        },
+    };
+    let (feature_names, features) = snapshot_features(awrs);
+    if features.len() < 2 * REGIME_MIN_SNAPS || feature_names.is_empty() {
+        return None;
+    }
+
+    let (centroids, labels, silhouette) = match requested_k {
+        Some(k) => {
+            let (c, l, _) = best_kmeans(&features, k.min(features.len()));
+            let s = simplified_silhouette(&features, &c, &l);
+            (c, l, s)
+        }
+        None => {
+            let max_k = args.regimes_max_k.min(features.len() / REGIME_MIN_SNAPS).max(2);
+            let best = (2..=max_k)
+                .map(|k| {
+                    let (c, l, _) = best_kmeans(&features, k);
+                    let s = simplified_silhouette(&features, &c, &l);
+                    (c, l, s)
+                })
+                .filter(|(c, l, _)| (0..c.len()).all(|r| l.iter().filter(|x| **x == r).count() >= REGIME_MIN_SNAPS))
+                .max_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
+            match best {
+                Some(b) if b.2 >= REGIMES_MIN_SILHOUETTE => b,
+                _ => {
+                    let (c, l, _) = best_kmeans(&features, 1);
+                    (c, l, 0.0)
+                }
+            }
+        }
+    };
+
+    // Renumber regimes by size
+    let k = centroids.len();
+    let mut order: Vec<usize> = (0..k).collect();
+    order.sort_by_key(|r| std::cmp::Reverse(labels.iter().filter(|l| **l == *r).count()));
+    let rank: HashMap<usize, usize> = order.iter().enumerate().map(|(new, old)| (*old, new)).collect();
+    let labels: Vec<usize> = labels.iter().map(|l| rank[l]).collect();
+    let centroids: Vec<Vec<f64>> = order.iter().map(|old| centroids[*old].clone()).collect();
+
+    let snap_ids: Vec<u64> = awrs.iter().map(|awr| awr.snap_info.begin_snap_id).collect();
+    let label_by_snap: HashMap<u64, usize> = snap_ids.iter().copied().zip(labels.iter().copied()).collect();
+    let mut regimes = WorkloadRegimes {
+        snap_ids,
+        labels,
+        names: Vec::new(),
+        silhouette,
+        centroids,
+        feature_names,
+        features,
+        label_by_snap,
+    };
+    regimes.names = (0..k).map(|r| format!("R{} {}", r + 1, regime_kind(&regimes, awrs, r))).collect();
+    Some(regimes)
+}
+
+fn centroid_z(regimes: &WorkloadRegimes, regime: usize, prefixes: &[&str]) -> f64 {
+    regimes.feature_names.iter()
+        .zip(&regimes.centroids[regime])
+        .filter(|(name, _)| prefixes.iter().any(|p| name.starts_with(p)))
+        .map(|(_, z)| *z)
+        .fold(f64::NEG_INFINITY, f64::max)
+}
+
+fn lp_value(awr: &AWR, prefix: &str) -> f64 {
+    awr.load_profile.iter().find(|lp| lp.stat_name.starts_with(prefix)).map_or(0.0, |lp| lp.per_second)
+}
+
+/// Heuristic label of a regime: BACKUP, IDLE, BATCH, OLTP or MIXED with DAY/NIGHT/WEEKEND when hours are typical
+fn regime_kind(regimes: &WorkloadRegimes, awrs: &[AWR], regime: usize) -> String {
+    let members: Vec<&AWR> = awrs.iter().zip(&regimes.labels).filter(|(_, l)| **l == regime).map(|(a, _)| a).collect();
+    let all_aas: Vec<f64> = awrs.iter().map(|a| lp_value(a, "DB Time").max(lp_value(a, "DB time"))).collect();
+    let aas: Vec<f64> = members.iter().map(|a| lp_value(a, "DB Time").max(lp_value(a, "DB time"))).collect();
+
+    let (backup_s, wait_s) = members.iter()
+        .flat_map(|a| a.foreground_wait_events.iter().chain(a.background_wait_events.iter()))
+        .fold((0.0, 0.0), |(b, w), e| {
+            let is_backup = e.event.starts_with("RMAN backup") || e.event.starts_with("Backup:");
+            (b + if is_backup { e.total_wait_time_s } else { 0.0 }, w + e.total_wait_time_s)
+        });
+    let io_z = centroid_z(regimes, regime, &["LP: Physical read", "LP: Physical write", "LP: Read IO", "LP: Write IO"]);
+    let call_z = centroid_z(regimes, regime, &["LP: User calls", "LP: Executes", "LP: Transactions"]);
+
+    let kind = if wait_s > 0.0 && backup_s / wait_s > BACKUP_WAIT_SHARE {
+        "BACKUP"
+    } else if median(&aas) < 0.25 * median(&all_aas) {
+        "IDLE"
+    } else if io_z - call_z > 0.5 {
+        "BATCH"
+    } else if call_z > io_z - 0.5 && call_z > -0.5 {
+        "OLTP"
+    } else {
+        "MIXED"
+    };
+
+    let times: Vec<chrono::NaiveDateTime> = members.iter().filter_map(|a| parse_snap_time(&a.snap_info.begin_snap_time)).collect();
+    if times.is_empty() {
+        return kind.to_string();
+    }
+    let day_share = times.iter().filter(|t| (7..19).contains(&t.hour())).count() as f64 / times.len() as f64;
+    let weekend_share = times.iter().filter(|t| matches!(t.weekday(), Weekday::Sat | Weekday::Sun)).count() as f64 / times.len() as f64;
+    let when = if weekend_share >= 0.7 {
+        " WEEKEND"
+    } else if day_share >= 0.7 {
+        " DAY"
+    } else if day_share <= 0.3 {
+        " NIGHT"
+    } else {
+        ""
+    };
+    format!("{}{}", kind, when)
+}
+
+/// Shortest circular range of hours which holds at least 80% of regime snapshots, e.g. 22:00-05:59
+fn dominant_hours(hours: &[u32]) -> String {
+    if hours.is_empty() {
+        return "-".to_string();
+    }
+    let needed = (hours.len() as f64 * 0.8).ceil() as usize;
+    for len in 1..=24u32 {
+        let best = (0..24u32)
+            .map(|start| (start, hours.iter().filter(|h| (**h + 24 - start) % 24 < len).count()))
+            .max_by_key(|(_, count)| *count)
+            .unwrap();
+        if best.1 >= needed {
+            if len == 24 {
+                return "all day".to_string();
+            }
+            return format!("{:02}:00-{:02}:59", best.0, (best.0 + len - 1) % 24);
+        }
+    }
+    "all day".to_string()
+}
+
+fn top_by_time(items: impl Iterator<Item = (String, f64)>) -> Vec<String> {
+    let mut totals: HashMap<String, f64> = HashMap::new();
+    for (name, t) in items {
+        *totals.entry(name).or_insert(0.0) += t;
+    }
+    let mut totals: Vec<(String, f64)> = totals.into_iter().collect();
+    totals.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0)));
+    totals.into_iter().take(TOP_N).map(|(name, t)| format!("{} ({:.1}s)", name, t)).collect()
+}
+
+/// Summary of each regime over snapshots in snap_range - typical load, hours, what makes it different and what it waits on
+fn summarize_regimes(regimes: &WorkloadRegimes, awrs: &[AWR], snap_range: &(u64,u64)) -> Vec<WorkloadRegime> {
+    let in_range: Vec<usize> = (0..awrs.len()).filter(|i| in_snap_range(&awrs[*i], snap_range)).collect();
+    (0..regimes.names.len()).map(|r| {
+        let idx: Vec<usize> = in_range.iter().copied().filter(|i| regimes.labels[*i] == r).collect();
+        let members: Vec<&AWR> = idx.iter().map(|i| &awrs[*i]).collect();
+        let times: Vec<chrono::NaiveDateTime> = members.iter().filter_map(|a| parse_snap_time(&a.snap_info.begin_snap_time)).collect();
+        let runs = idx.iter().enumerate().filter(|(j, i)| *j == 0 || idx[j - 1] + 1 != **i).count();
+
+        let mut distinguishing: Vec<(String, f64)> = regimes.feature_names.iter().cloned().zip(regimes.centroids[r].iter().copied()).collect();
+        distinguishing.sort_by(|a, b| b.1.abs().partial_cmp(&a.1.abs()).unwrap_or(std::cmp::Ordering::Equal));
+
+        let silhouette = if regimes.names.len() > 1 && !idx.is_empty() {
+            let data: Vec<Vec<f64>> = idx.iter().map(|i| regimes.features[*i].clone()).collect();
+            let labels: Vec<usize> = vec![r; idx.len()];
+            simplified_silhouette(&data, &regimes.centroids, &labels)
+        } else {
+            0.0
+        };
+
+        WorkloadRegime {
+            regime_id: r + 1,
+            label: regimes.names[r].clone(),
+            snapshots: members.len(),
+            share_pct: 100.0 * members.len() as f64 / in_range.len().max(1) as f64,
+            runs,
+            silhouette,
+            typical_aas: median(&members.iter().map(|a| lp_value(a, "DB Time").max(lp_value(a, "DB time"))).collect::<Vec<f64>>()),
+            typical_db_cpu_per_s: median(&members.iter().map(|a| lp_value(a, "DB CPU")).collect::<Vec<f64>>()),
+            typical_executes_per_s: median(&members.iter().map(|a| lp_value(a, "Executes")).collect::<Vec<f64>>()),
+            typical_user_calls_per_s: median(&members.iter().map(|a| lp_value(a, "User calls")).collect::<Vec<f64>>()),
+            dominant_hours: dominant_hours(&times.iter().map(|t| t.hour()).collect::<Vec<u32>>()),
+            weekend_share_pct: if times.is_empty() { 0.0 } else {
+                100.0 * times.iter().filter(|t| matches!(t.weekday(), Weekday::Sat | Weekday::Sun)).count() as f64 / times.len() as f64
+            },
+            distinguishing_features: distinguishing.iter().take(TOP_N).map(|(name, z)| format!("{} {:+.1} sd", name, z)).collect(),
+            top_wait_events: top_by_time(members.iter().flat_map(|a| a.foreground_wait_events.iter().map(|e| (e.event.clone(), e.total_wait_time_s)))),
+            top_sqls: top_by_time(members.iter().flat_map(|a| a.sql_elapsed_time.iter().map(|s| (s.sql_id.clone(), s.elapsed_time_s)))),
+            db_time_gradient_top: Vec::new(),
+        }
+    }).collect()
+}
+
+/// Runs of consecutive snapshots in the same regime as (regime, first x value, last x value)
+fn regime_runs(regimes: &WorkloadRegimes, awrs: &[AWR], snap_range: &(u64,u64)) -> Vec<(usize, String, String)> {
+    let mut runs: Vec<(usize, String, String)> = Vec::new();
+    for awr in snaps_in_range(awrs, snap_range) {
+        let Some(r) = regimes.label_of(awr.snap_info.begin_snap_id) else { continue; };
+        let xval = format!("{} ({})", awr.snap_info.begin_snap_time, awr.snap_info.begin_snap_id);
+        match runs.last_mut() {
+            Some(last) if last.0 == r => last.2 = xval,
+            _ => runs.push((r, xval.clone(), xval)),
+        }
+    }
+    runs
+}
+
+/// Colors the background of the main chart by regime
+pub fn add_regime_shapes(layout: &mut Layout, regimes: &WorkloadRegimes, awrs: &[AWR], snap_range: &(u64,u64)) {
+    if regimes.names.len() < 2 {
+        return;
+    }
+    for (r, x0, x1) in regime_runs(regimes, awrs, snap_range) {
+        layout.add_shape(
+            Shape::new()
+                .shape_type(ShapeType::Rect)
+                .x_ref("x")
+                .y_ref("paper")
+                .x0(x0)
+                .x1(x1)
+                .y0(0.0)
+                .y1(1.0)
+                .fill_color(regime_color(r, 0.12))
+                .layer(ShapeLayer::Below)
+                .line(ShapeLine::new().width(0.0))
+        );
+    }
+}
+
+/// DB Time markers colored by regime - one legend entry per regime
+pub fn add_regime_traces(plot: &mut Plot, regimes: &WorkloadRegimes, awrs: &[AWR], snap_range: &(u64,u64), x_vals: &[String], y_vals_dbtime: &[f64]) {
+    let snaps: Vec<&AWR> = snaps_in_range(awrs, snap_range)
+        .collect();
+    for (r, name) in regimes.names.iter().enumerate() {
+        let (x, y): (Vec<String>, Vec<f64>) = snaps.iter()
+            .zip(x_vals.iter().zip(y_vals_dbtime))
+            .filter(|(awr, _)| regimes.label_of(awr.snap_info.begin_snap_id) == Some(r))
+            .map(|(_, (x, y))| (x.clone(), *y))
+            .unzip();
+        if x.is_empty() {
+            continue;
+        }
+        plot.add_trace(Scatter::new(x, y)
+            .mode(Mode::Markers)
+            .name(format!("Regime {}", name))
+            .marker(Marker::new().color(regime_color(r, 0.9)).size(6))
+            .x_axis("x1")
+            .y_axis("y1"));
+    }
+}
+
+/// Prints summary of workload regimes found by cluster_workload_regimes, writes stats/workload_regimes.html
+/// and attaches regimes to ReportForAI. Returns regimes for coloring of the main chart and per regime analyses.
+/// Regimes are clustered once per run, so the regime page shows the same assignment the anomaly detector uses.
+pub fn report_workload_regimes(awrs: &[AWR], snap_range: &(u64,u64), regimes: Option<WorkloadRegimes>, args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) -> Option<WorkloadRegimes> {
+    if args.regimes == "none" {
+        return None;
+    }
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{}\n", "Workload regimes - k-means clustering of Load Profile, Time Model and wait classes".yellow());
+
+    let Some(regimes) = regimes else {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNot enough snapshots or statistics to find workload regimes\n".green().italic());
+        return None;
+    };
+    if regimes.names.len() < 2 {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo distinct workload regimes - all snapshots belong to one regime\n".green().italic());
+    }
+    make_notes!(logfile_name, args.quiet, 0, "\tRegimes: {}  simplified silhouette: {:.3}\n", regimes.names.len(), regimes.silhouette);
+
+    let summaries = summarize_regimes(&regimes, awrs, snap_range);
+    let headers = ["Regime", "Snaps", "Share", "Runs", "Hours", "AAS", "DB CPU/s", "Executes/s", "Top wait events", "Distinguishing features"];
+    let mut table = Table::new();
+    table.set_titles(Row::new(headers.iter().map(|h| Cell::new(h)).collect()));
+    for s in &summaries {
+        table.add_row(Row::new(vec![
+            Cell::new(&s.label),
+            Cell::new(&format!("{}", s.snapshots)),
+            Cell::new(&format!("{:.1}%", s.share_pct)),
+            Cell::new(&format!("{}", s.runs)),
+            Cell::new(&s.dominant_hours),
+            Cell::new(&format!("{:.2}", s.typical_aas)),
+            Cell::new(&format!("{:.2}", s.typical_db_cpu_per_s)),
+            Cell::new(&format!("{:.1}", s.typical_executes_per_s)),
+            Cell::new(&s.top_wait_events.iter().take(3).cloned().collect::<Vec<String>>().join("\n")),
+            Cell::new(&s.distinguishing_features.iter().take(3).cloned().collect::<Vec<String>>().join("\n")),
+        ]));
+    }
+    make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&table));
+
+    // Regime page - DB Time colored by regime and the summary table
+    let (x_vals, y_vals): (Vec<String>, Vec<f64>) = snaps_in_range(awrs, snap_range)
+        .map(|awr| (format!("{} ({})", awr.snap_info.begin_snap_time, awr.snap_info.begin_snap_id), lp_value(awr, "DB Time").max(lp_value(awr, "DB time"))))
+        .unzip();
+    let mut plot = Plot::new();
+    plot.add_trace(Scatter::new(x_vals.clone(), y_vals.clone()).mode(Mode::Lines).name("DB Time (s/s)").line(Line::new().color("#7f7f7f")));
+    add_regime_traces(&mut plot, &regimes, awrs, snap_range, &x_vals, &y_vals);
+    let mut layout = Layout::new()
+        .title("Workload regimes")
+        .height(600)
+        .hover_mode(HoverMode::Closest)
+        .y_axis(Axis::new().title("DB Time (s/s)"));
+    add_regime_shapes(&mut layout, &regimes, awrs, snap_range);
+    plot.set_layout(layout);
+
+    let fname = format!("{}/stats/workload_regimes.html", html_dir);
+    plot.write_html(Path::new(&fname));
+    let table_html = table_to_html_string(&table, "Workload regimes", &headers);
+    if let Err(e) = append_html_before_body_end(&fname, &table_html) {
+        eprintln!("Error writing file {}: {}", fname, e);
+    }
+
+    report_for_ai.workload_regimes = summaries;
+    Some(regimes)
+}
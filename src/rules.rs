@@ -0,0 +1,493 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+use crate::awr::AWR;
+use crate::Args;
+use crate::capacity::snapshot_elapsed_s;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, RuleFinding, RuleEvidence};
+use colored::*;
+use plotly::{Plot, Scatter};
+use plotly::common::{Marker, MarkerSymbol, Mode};
+use plotly::layout::{Axis, HoverMode, Layout};
+use prettytable::{Table, Row, Cell};
+use regex::Regex;
+use serde::Deserialize;
+
+/* Rule based diagnostics.
+   Rules are declarative (TOML) and deterministic - no AI is needed to evaluate them. Built-in rule pack
+   (src/rules_builtin.toml) covers classic Oracle patterns, rules from --rules file are added to it
+   (a rule with the same id replaces the built-in one). Every condition has the form METRIC OPERATOR NUMBER,
+   metric syntax is described in the header of the built-in pack. Snapshot scope rules are checked in every snapshot
+   and raise a finding when at least min_snapshots snapshots match; report scope rules are checked once. */
+
+const BUILTIN_RULES: &str = include_str!("../src/rules_builtin.toml");
+/// Maximal number of evidence snapshots kept for a finding
+const EVIDENCE_MAX: usize = 10;
+const SEVERITIES: [&str; 3] = ["CRITICAL", "WARNING", "INFO"];
+const SNAPSHOT_FAMILIES: [&str; 17] = [
+    "EVENT", "EVENT_PCT", "EVENT_MS", "BGEVENT", "BGEVENT_PCT", "BGEVENT_MS", "WAITCLASS_PCT", "LP",
+    "TM", "TM_PCT", "STAT", "STAT_PS", "EFF", "IO", "LIBCACHE_MISS", "LATCH_MISS", "SQL",
+];
+const SNAPSHOT_SCALARS: [&str; 4] = ["AAS", "HOST_CPU_BUSY_PCT", "LOG_SWITCHES_PER_HOUR", "ELAPSED_S"];
+
+#[derive(Deserialize, Debug, Clone, Default)]
+struct RulePack {
+    #[serde(default)]
+    rule: Vec<Rule>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Rule {
+    id: String,
+    title: String,
+    #[serde(default = "default_severity")]
+    severity: String,
+    #[serde(default = "default_scope")]
+    scope: String,
+    #[serde(default)]
+    any: bool,
+    #[serde(default = "default_min_snapshots")]
+    min_snapshots: usize,
+    conditions: Vec<String>,
+    #[serde(default)]
+    recommendation: String,
+    #[serde(skip)]
+    source: String,
+}
+
+fn default_severity() -> String { "WARNING".to_string() }
+fn default_scope() -> String { "snapshot".to_string() }
+fn default_min_snapshots() -> usize { 1 }
+
+#[derive(Debug, Clone)]
+enum Metric {
+    Snapshot { family: String, name: String },
+    Aggregate { func: String, family: String, name: String },
+    Report(String),
+    Param(String),
+}
+
+#[derive(Debug, Clone)]
+struct Condition {
+    text: String,
+    metric: Metric,
+    op: String,
+    value: f64,
+}
+
+impl Condition {
+    fn holds(&self, v: f64) -> bool {
+        match self.op.as_str() {
+            ">"  => v > self.value,
+            ">=" => v >= self.value,
+            "<"  => v < self.value,
+            "<=" => v <= self.value,
+            "==" => v == self.value,
+            "!=" => v != self.value,
+            _ => false,
+        }
+    }
+
+    /// Name of the metric without the threshold - used to describe evidence
+    fn metric_text(&self) -> &str {
+        self.text.rsplit_once(self.op.as_str()).map_or(self.text.as_str(), |(m, _)| m.trim())
+    }
+}
+
+struct CompiledRule {
+    rule: Rule,
+    conditions: Vec<Condition>,
+}
+
+/// Names in AWR differ in spacing and case between versions ("Buffer  Hit   %")
+fn norm(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase()
+}
+
+static AGG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?i)(MAX|MIN|AVG|MEDIAN|P95)\s*\((.*)\)$").unwrap());
+static COND_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(.+?)\s*(>=|<=|==|!=|>|<)\s*(-?[0-9][0-9_.]*(?:[eE][-+]?[0-9]+)?)\s*$").unwrap());
+
+fn parse_metric(text: &str) -> Result<Metric, String> {
+    if let Some(caps) = AGG_RE.captures(text) {
+        let func = caps[1].to_uppercase();
+        return match parse_metric(caps[2].trim())? {
+            Metric::Snapshot { family, name } => Ok(Metric::Aggregate { func, family, name }),
+            _ => Err(format!("{} can only aggregate snapshot metrics: '{}'", func, text)),
+        };
+    }
+    let (family, name) = match text.split_once(':') {
+        Some((f, n)) => (f.trim().to_uppercase(), n.trim().to_string()),
+        None => (text.trim().to_uppercase(), String::new()),
+    };
+    match family.as_str() {
+        "REPORT" => Ok(Metric::Report(name)),
+        "PARAM" => Ok(Metric::Param(name.to_lowercase())),
+        f if SNAPSHOT_SCALARS.contains(&f) => Ok(Metric::Snapshot { family, name }),
+        f if SNAPSHOT_FAMILIES.contains(&f) && !name.is_empty() => Ok(Metric::Snapshot { family, name }),
+        _ => Err(format!("unknown metric '{}'", text)),
+    }
+}
+
+fn parse_condition(text: &str) -> Result<Condition, String> {
+    let caps = COND_RE.captures(text.trim())
+        .ok_or_else(|| format!("condition '{}' should be METRIC OPERATOR NUMBER", text))?;
+    let value = caps[3].replace('_', "").parse::<f64>()
+        .map_err(|_| format!("wrong number in condition '{}'", text))?;
+    Ok(Condition {
+        text: text.trim().to_string(),
+        metric: parse_metric(caps[1].trim())?,
+        op: caps[2].to_string(),
+        value,
+    })
+}
+
+fn compile_rule(rule: Rule) -> Result<CompiledRule, String> {
+    let mut rule = rule;
+    rule.severity = rule.severity.to_uppercase();
+    rule.scope = rule.scope.to_lowercase();
+    if !SEVERITIES.contains(&rule.severity.as_str()) {
+        return Err(format!("rule {}: severity should be INFO, WARNING or CRITICAL", rule.id));
+    }
+    if rule.scope != "snapshot" && rule.scope != "report" {
+        return Err(format!("rule {}: scope should be snapshot or report", rule.id));
+    }
+    if rule.conditions.is_empty() {
+        return Err(format!("rule {}: no conditions", rule.id));
+    }
+    let conditions = rule.conditions.iter()
+        .map(|c| parse_condition(c))
+        .collect::<Result<Vec<Condition>, String>>()
+        .map_err(|e| format!("rule {}: {}", rule.id, e))?;
+    if rule.scope == "report" && conditions.iter().any(|c| matches!(c.metric, Metric::Snapshot { .. })) {
+        return Err(format!("rule {}: report scope rule can use snapshot metrics only in aggregates", rule.id));
+    }
+    Ok(CompiledRule { rule, conditions })
+}
+
+/// Built-in rule pack merged with rules from --rules file; errors are returned for logging, broken rules are skipped
+fn load_rules(args: &Args) -> (Vec<CompiledRule>, Vec<String>) {
+    let mut errors: Vec<String> = Vec::new();
+    let mut rules: Vec<Rule> = Vec::new();
+    if !args.no_builtin_rules {
+        match toml::from_str::<RulePack>(BUILTIN_RULES) {
+            Ok(pack) => rules.extend(pack.rule.into_iter().map(|mut r| { r.source = "builtin".to_string(); r })),
+            Err(e) => errors.push(format!("built-in rule pack: {}", e)),
+        }
+    }
+    if !args.rules.is_empty() {
+        match fs::read_to_string(&args.rules).map_err(|e| e.to_string()).and_then(|s| toml::from_str::<RulePack>(&s).map_err(|e| e.to_string())) {
+            Ok(pack) => {
+                for mut r in pack.rule {
+                    r.source = args.rules.clone();
+                    rules.retain(|existing| existing.id != r.id);
+                    rules.push(r);
+                }
+            }
+            Err(e) => errors.push(format!("{}: {}", args.rules, e)),
+        }
+    }
+    let mut compiled: Vec<CompiledRule> = Vec::new();
+    for r in rules {
+        match compile_rule(r) {
+            Ok(c) => compiled.push(c),
+            Err(e) => errors.push(e),
+        }
+    }
+    (compiled, errors)
+}
+
+fn snapshot_metric(awr: &AWR, family: &str, name: &str) -> Option<f64> {
+    let n = norm(name);
+    match family {
+        "EVENT"         => awr.foreground_wait_events.iter().find(|e| norm(&e.event) == n).map(|e| e.total_wait_time_s),
+        "EVENT_PCT"     => awr.foreground_wait_events.iter().find(|e| norm(&e.event) == n).map(|e| e.pct_dbtime),
+        "EVENT_MS"      => awr.foreground_wait_events.iter().find(|e| norm(&e.event) == n).map(|e| e.avg_wait),
+        "BGEVENT"       => awr.background_wait_events.iter().find(|e| norm(&e.event) == n).map(|e| e.total_wait_time_s),
+        "BGEVENT_PCT"   => awr.background_wait_events.iter().find(|e| norm(&e.event) == n).map(|e| e.pct_dbtime),
+        "BGEVENT_MS"    => awr.background_wait_events.iter().find(|e| norm(&e.event) == n).map(|e| e.avg_wait),
+        "WAITCLASS_PCT" => awr.wait_classes.iter().find(|wc| norm(&wc.wait_class) == n).map(|wc| wc.db_time_pct),
+        "LP"            => awr.load_profile.iter().find(|lp| norm(&lp.stat_name).starts_with(&n)).map(|lp| lp.per_second),
+        "TM"            => awr.time_model_stats.iter().find(|tm| norm(&tm.stat_name) == n).map(|tm| tm.time_s),
+        "TM_PCT"        => awr.time_model_stats.iter().find(|tm| norm(&tm.stat_name) == n).map(|tm| tm.pct_dbtime),
+        "STAT"          => awr.instance_stats.iter().find(|st| norm(&st.statname) == n).map(|st| st.total as f64),
+        "STAT_PS"       => {
+            let total = awr.instance_stats.iter().find(|st| norm(&st.statname) == n)?.total as f64;
+            snapshot_elapsed_s(awr).map(|elapsed| total / elapsed)
+        }
+        "EFF"           => awr.instance_efficiency.iter().find(|ie| norm(&ie.eff_stat) == n).and_then(|ie| ie.eff_pct).map(|v| v as f64),
+        "IO"            => awr.io_stats_byfunc.iter().find(|(f, _)| norm(f) == n).and_then(|(_, io)| io.avg_time),
+        "LIBCACHE_MISS" => awr.library_cache.iter().find(|lc| norm(&lc.statname) == n).map(|lc| lc.get_pct_miss),
+        "LATCH_MISS"    => awr.latch_activity.iter().find(|la| norm(&la.statname) == n).map(|la| la.get_pct_miss),
+        "SQL"           => awr.sql_elapsed_time.iter().find(|sq| sq.sql_id == name).map(|sq| sq.elapsed_time_s),
+        "AAS"           => {
+            snapshot_elapsed_s(awr).map(|elapsed| db_time_s(awr) / elapsed)
+        }
+        "HOST_CPU_BUSY_PCT"     => if awr.host_cpu.cpus > 0 { Some(100.0 - awr.host_cpu.pct_idle) } else { None },
+        "LOG_SWITCHES_PER_HOUR" => Some(awr.redo_log.per_hour),
+        "ELAPSED_S"             => snapshot_elapsed_s(awr),
+        _ => None,
+    }
+}
+
+/// Value of JSON pointer into ReportForAI - numbers as is, booleans as 1/0, arrays and objects as their length
+fn report_metric(report: &serde_json::Value, pointer: &str) -> Option<f64> {
+    match report.pointer(pointer)? {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        serde_json::Value::Array(a) => Some(a.len() as f64),
+        serde_json::Value::Object(o) => Some(o.len() as f64),
+        serde_json::Value::String(s) => s.trim().parse::<f64>().ok(),
+        serde_json::Value::Null => None,
+    }
+}
+
+fn param_metric(params: &HashMap<String, String>, name: &str) -> Option<f64> {
+    let value = params.iter().find(|(k, _)| k.to_lowercase() == name).map(|(_, v)| v.trim().to_uppercase())?;
+    match value.as_str() {
+        "TRUE" => Some(1.0),
+        "FALSE" => Some(0.0),
+        v => v.parse::<f64>().ok(),
+    }
+}
+
+/// Value of a metric that doesn't depend on snapshot (aggregate, report or parameter)
+fn global_metric(metric: &Metric, snaps: &[&AWR], report: &serde_json::Value, params: &HashMap<String, String>) -> Option<f64> {
+    match metric {
+        Metric::Aggregate { func, family, name } => {
+            let values: Vec<f64> = snaps.iter().filter_map(|awr| snapshot_metric(awr, family, name)).collect();
+            if values.is_empty() {
+                return None;
+            }
+            match func.as_str() {
+                "MAX" => values.iter().cloned().reduce(f64::max),
+                "MIN" => values.iter().cloned().reduce(f64::min),
+                "AVG" => mean(values),
+                "MEDIAN" => Some(median(&values)),
+                "P95" => Some(percentile(&values, 95.0)),
+                _ => None,
+            }
+        }
+        Metric::Report(pointer) => report_metric(report, pointer),
+        Metric::Param(name) => param_metric(params, name),
+        Metric::Snapshot { .. } => None,
+    }
+}
+
+fn combine(any: bool, results: &[bool]) -> bool {
+    if any { results.iter().any(|r| *r) } else { results.iter().all(|r| *r) }
+}
+
+fn evaluate_rule(c: &CompiledRule, snaps: &[&AWR], report: &serde_json::Value, params: &HashMap<String, String>) -> Option<RuleFinding> {
+    // Aggregates, report values and parameters are the same for every snapshot
+    let globals: Vec<Option<f64>> = c.conditions.iter().map(|cond| global_metric(&cond.metric, snaps, report, params)).collect();
+    let mut finding = RuleFinding {
+        rule_id: c.rule.id.clone(),
+        title: c.rule.title.clone(),
+        severity: c.rule.severity.clone(),
+        scope: c.rule.scope.clone(),
+        source: c.rule.source.clone(),
+        total_snapshots: snaps.len(),
+        recommendation: c.rule.recommendation.clone(),
+        ..Default::default()
+    };
+
+    if c.rule.scope == "report" {
+        let results: Vec<bool> = c.conditions.iter().zip(&globals).map(|(cond, v)| v.is_some_and(|v| cond.holds(v))).collect();
+        if !combine(c.rule.any, &results) {
+            return None;
+        }
+        finding.evidence.push(RuleEvidence {
+            snap_id: None,
+            begin_snap_time: None,
+            values: c.conditions.iter().zip(&globals)
+                .filter_map(|(cond, v)| v.map(|v| format!("{} = {:.2}", cond.metric_text(), v)))
+                .collect(),
+        });
+        return Some(finding);
+    }
+
+    // Snapshot scope - evidence is ordered by the first snapshot metric, worst values first
+    let rank_idx = c.conditions.iter().position(|cond| matches!(cond.metric, Metric::Snapshot { .. }));
+    let mut matched: Vec<(f64, RuleEvidence)> = Vec::new();
+    for awr in snaps {
+        let values: Vec<Option<f64>> = c.conditions.iter().zip(&globals)
+            .map(|(cond, g)| match &cond.metric {
+                Metric::Snapshot { family, name } => snapshot_metric(awr, family, name),
+                _ => *g,
+            })
+            .collect();
+        let results: Vec<bool> = c.conditions.iter().zip(&values).map(|(cond, v)| v.is_some_and(|v| cond.holds(v))).collect();
+        if !combine(c.rule.any, &results) {
+            continue;
+        }
+        let rank = rank_idx.and_then(|i| values[i].map(|v| if c.conditions[i].op.starts_with('<') { -v } else { v })).unwrap_or(0.0);
+        matched.push((rank, RuleEvidence {
+            snap_id: Some(awr.snap_info.begin_snap_id),
+            begin_snap_time: Some(awr.snap_info.begin_snap_time.clone()),
+            values: c.conditions.iter().zip(&values)
+                .filter_map(|(cond, v)| v.map(|v| format!("{} = {:.2}", cond.metric_text(), v)))
+                .collect(),
+        }));
+    }
+    if matched.is_empty() || matched.len() < c.rule.min_snapshots {
+        return None;
+    }
+    finding.matched_snapshots = matched.len();
+    finding.first_snap_id = matched.iter().filter_map(|(_, e)| e.snap_id).min();
+    finding.last_snap_id = matched.iter().filter_map(|(_, e)| e.snap_id).max();
+    matched.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    finding.evidence = matched.into_iter().take(EVIDENCE_MAX).map(|(_, e)| e).collect();
+    Some(finding)
+}
+
+fn severity_rank(severity: &str) -> usize {
+    SEVERITIES.iter().position(|s| *s == severity).unwrap_or(SEVERITIES.len())
+}
+
+fn severity_color(severity: &str) -> &'static str {
+    match severity {
+        "CRITICAL" => "#d62728",
+        "WARNING" => "#ff7f0e",
+        _ => "#1f77b4",
+    }
+}
+
+/// Evaluates built-in and user rules over snapshots in snap_range and the (complete) ReportForAI.
+/// Findings go to the log, stats/rule_findings.html and ReportForAI
+pub fn report_rule_findings(awrs: &[AWR], snap_range: &(u64,u64), params: &HashMap<String, String>, args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) {
+    if args.no_builtin_rules && args.rules.is_empty() {
+        return;
+    }
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{}\n", "Diagnostic rules - findings of built-in and user defined rules".yellow());
+
+    let (rules, errors) = load_rules(args);
+    if !errors.is_empty() {
+        let skipped: String = errors.iter().map(|e| format!("\t{} {}\n", "Rule skipped:".red(), e)).collect();
+        make_notes!(logfile_name, false, 1, "{}", skipped);
+    }
+
+    let snaps: Vec<&AWR> = snaps_in_range(awrs, snap_range)
+        .collect();
+    let report = serde_json::to_value(&*report_for_ai).unwrap_or(serde_json::Value::Null);
+
+    let mut findings: Vec<RuleFinding> = rules.iter()
+        .filter_map(|c| evaluate_rule(c, &snaps, &report, params))
+        .collect();
+    findings.sort_by(|a, b| severity_rank(&a.severity).cmp(&severity_rank(&b.severity))
+        .then(b.matched_snapshots.cmp(&a.matched_snapshots))
+        .then(a.rule_id.cmp(&b.rule_id)));
+
+    make_notes!(logfile_name, args.quiet, 0, "\tRules evaluated: {}  findings: {}\n", rules.len(), findings.len());
+    if findings.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo rule matched\n".green().italic());
+    }
+
+    let headers = ["Severity", "Rule", "Title", "Snapshots", "Evidence", "Recommendation"];
+    let mut table = Table::new();
+    table.set_titles(Row::new(headers.iter().map(|h| Cell::new(h)).collect()));
+    for f in &findings {
+        let snapshots = if f.scope == "report" { "report".to_string() } else { format!("{}/{}", f.matched_snapshots, f.total_snapshots) };
+        let evidence = f.evidence.iter().take(3)
+            .map(|e| match e.snap_id {
+                Some(id) => format!("{}: {}", id, e.values.join(", ")),
+                None => e.values.join(", "),
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        table.add_row(Row::new(vec![
+            Cell::new(&f.severity),
+            Cell::new(&f.rule_id),
+            Cell::new(&f.title),
+            Cell::new(&snapshots),
+            Cell::new(&evidence),
+            Cell::new(&f.recommendation),
+        ]));
+    }
+    if !findings.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&table));
+    }
+
+    // Findings page - matching snapshots of every rule on a timeline, colored by severity
+    let mut plot = Plot::new();
+    for severity in SEVERITIES {
+        let (x_vals, (y_vals, hover)): (Vec<String>, (Vec<String>, Vec<String>)) = findings.iter()
+            .filter(|f| f.severity == severity && f.scope == "snapshot")
+            .flat_map(|f| f.evidence.iter().map(move |e| (f, e)))
+            .map(|(f, e)| (
+                format!("{} ({})", e.begin_snap_time.clone().unwrap_or_default(), e.snap_id.unwrap_or_default()),
+                (f.rule_id.clone(), format!("{}<br>{}", f.title, e.values.join("<br>"))),
+            ))
+            .unzip();
+        if x_vals.is_empty() {
+            continue;
+        }
+        plot.add_trace(Scatter::new(x_vals, y_vals)
+            .mode(Mode::Markers)
+            .name(severity)
+            .hover_text_array(hover)
+            .marker(Marker::new().color(severity_color(severity)).size(10).symbol(MarkerSymbol::Square)));
+    }
+    plot.set_layout(Layout::new()
+        .title("Diagnostic rule findings - evidence snapshots")
+        .height(600)
+        .hover_mode(HoverMode::Closest)
+        .x_axis(Axis::new().title("Snapshot"))
+        .y_axis(Axis::new().title("Rule")));
+
+    let fname = format!("{}/stats/rule_findings.html", html_dir);
+    plot.write_html(Path::new(&fname));
+    let mut page_html = table_to_html_string(&table, "Diagnostic rule findings", &headers);
+    if !errors.is_empty() {
+        page_html.push_str(&format!("<p><span style=\"color:red;font-weight:bold;\">Rules skipped:</span><br>{}</p>\n", errors.join("<br>")));
+    }
+    if let Err(e) = append_html_before_body_end(&fname, &page_html) {
+        eprintln!("Error writing file {}: {}", fname, e);
+    }
+
+    report_for_ai.rule_findings = findings;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_metric_families() {
+        assert!(matches!(parse_metric("event: db file sequential read").unwrap(),
+            Metric::Snapshot { family, name } if family == "EVENT" && name == "db file sequential read"));
+        assert!(matches!(parse_metric("HOST_CPU_BUSY_PCT").unwrap(),
+            Metric::Snapshot { family, name } if family == "HOST_CPU_BUSY_PCT" && name.is_empty()));
+        assert!(matches!(parse_metric("REPORT:/top_sqls/0").unwrap(), Metric::Report(p) if p == "/top_sqls/0"));
+        assert!(matches!(parse_metric("PARAM:Cursor_Sharing").unwrap(), Metric::Param(p) if p == "cursor_sharing"));
+        assert!(parse_metric("EVENT").is_err());
+        assert!(parse_metric("FOO:bar").is_err());
+    }
+
+    #[test]
+    fn parse_metric_aggregates_snapshot_metrics_only() {
+        assert!(matches!(parse_metric("p95(TM:DB CPU)").unwrap(),
+            Metric::Aggregate { func, family, name } if func == "P95" && family == "TM" && name == "DB CPU"));
+        assert!(parse_metric("MAX(PARAM:processes)").is_err());
+    }
+
+    #[test]
+    fn parse_condition_operators_and_numbers() {
+        let c = parse_condition(" EVENT_PCT:log file sync >= 1_000.5 ").unwrap();
+        assert_eq!(c.op, ">=");
+        assert_eq!(c.value, 1000.5);
+        assert_eq!(c.metric_text(), "EVENT_PCT:log file sync");
+        assert!(c.holds(1000.5) && !c.holds(1000.0));
+
+        let c = parse_condition("AAS<-1e2").unwrap();
+        assert_eq!(c.op, "<");
+        assert_eq!(c.value, -100.0);
+
+        assert!(parse_condition("AAS > high").is_err());
+        assert!(parse_condition("AAS").is_err());
+        assert!(parse_condition("FOO:bar > 1").is_err());
+    }
+}
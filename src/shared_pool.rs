@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::awr::AWR;
+use crate::Args;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, SharedPoolHealth, DictionaryCacheTrend, InvalidationStorm, SharedPoolCorrelation, SharedPoolFinding};
+use colored::*;
+use plotly::{Bar, Plot, Scatter};
+use plotly::common::{Marker, MarkerSymbol, Mode};
+use plotly::layout::{Axis, HoverMode, Layout, RangeMode};
+use prettytable::{Table, Row, Cell};
+
+/* Library cache and dictionary cache health.
+   Reload % = reloads / pin requests of all namespaces - objects aged out of the shared pool and loaded again.
+   Get and pin % miss are weighted by the number of requests. Invalidations (DDL, statistics gathering, grants)
+   make cursors unusable, so the next execution hard parses them and sessions wait on cursor: pin S wait on X.
+   Snapshots with invalidations far above the median (robust z-score) are invalidation storms.
+   Dictionary cache (dc_*) get % miss is compared between the first and the second half of the period. */
+
+/// Reload % above which the shared pool is considered too small
+const RELOAD_PCT_WARN: f64 = 1.0;
+/// SQL AREA get % miss above which many statements are hard parsed
+const SQL_AREA_MISS_WARN_PCT: f64 = 10.0;
+/// Dictionary cache get % miss reported when the cache has at least DC_MIN_GETS get requests per snapshot
+const DC_MISS_WARN_PCT: f64 = 10.0;
+const DC_MIN_GETS: f64 = 1000.0;
+/// Minimal number of invalidations in a snapshot to call it a storm
+const STORM_MIN_INVALIDATIONS: u64 = 100;
+/// Robust z-score of invalidations from which a snapshot is a storm
+const INVALIDATION_STORM_Z: f64 = 3.5;
+/// Correlation with hard parses or mutex waits reported from this absolute value
+const CORRELATION_MIN: f64 = 0.5;
+/// Condition has to hold in this many snapshots to raise a finding
+const MIN_SNAPSHOTS: usize = 3;
+
+struct SnapPool {
+    x_val: String,
+    snap_id: u64,
+    snap_date: String,
+    reloads: u64,
+    invalidations: u64,
+    reload_pct: f64,
+    sql_area_get_pct_miss: f64,
+    pin_pct_miss: f64,
+    dc_get_pct_miss: f64,
+    hard_parses_per_s: f64,
+    mutex_x_s: f64,
+    pin_s_wait_on_x_s: f64,
+}
+
+fn weighted_pct(pairs: impl Iterator<Item = (u64, f64)>) -> f64 {
+    let (weighted, total) = pairs.fold((0.0, 0.0), |(w, t), (requests, pct)| (w + requests as f64 * pct, t + requests as f64));
+    if total > 0.0 { weighted / total } else { 0.0 }
+}
+
+fn is_constant(values: &[f64]) -> bool {
+    values.iter().all(|v| *v == values[0])
+}
+
+fn event_time_s(awr: &AWR, event: &str) -> f64 {
+    awr.foreground_wait_events.iter().find(|e| e.event == event).map_or(0.0, |e| e.total_wait_time_s)
+}
+
+fn snapshot_pool(awr: &AWR) -> SnapPool {
+    let pin_requests: u64 = awr.library_cache.iter().map(|lc| lc.pin_requests).sum();
+    let reloads: u64 = awr.library_cache.iter().map(|lc| lc.reloads).sum();
+    SnapPool {
+        x_val: format!("{} ({})", awr.snap_info.begin_snap_time, awr.snap_info.begin_snap_id),
+        snap_id: awr.snap_info.begin_snap_id,
+        snap_date: awr.snap_info.begin_snap_time.clone(),
+        reloads,
+        invalidations: awr.library_cache.iter().map(|lc| lc.invalidations).sum(),
+        reload_pct: if pin_requests > 0 { reloads as f64 / pin_requests as f64 * 100.0 } else { 0.0 },
+        sql_area_get_pct_miss: weighted_pct(awr.library_cache.iter().filter(|lc| lc.statname == "SQL AREA").map(|lc| (lc.get_requests, lc.get_pct_miss))),
+        pin_pct_miss: weighted_pct(awr.library_cache.iter().map(|lc| (lc.pin_requests, lc.pin_pct_miss))),
+        dc_get_pct_miss: weighted_pct(awr.dictionary_cache.iter().map(|dc| (dc.get_requests, dc.get_pct_miss))),
+        hard_parses_per_s: awr.load_profile.iter().find(|lp| lp.stat_name.starts_with("Hard parses")).map_or(0.0, |lp| lp.per_second),
+        mutex_x_s: event_time_s(awr, "library cache: mutex X"),
+        pin_s_wait_on_x_s: event_time_s(awr, "cursor: pin S wait on X"),
+    }
+}
+
+/// Get % miss of every dc_* cache - median over the period and change between halves of the period
+fn dictionary_cache_trends(snaps: &[&AWR]) -> Vec<DictionaryCacheTrend> {
+    let half = snaps.len() / 2;
+    let mut caches: BTreeMap<String, Vec<(usize, f64, f64)>> = BTreeMap::new();
+    for (i, awr) in snaps.iter().enumerate() {
+        for dc in &awr.dictionary_cache {
+            if dc.get_requests > 0 {
+                caches.entry(dc.statname.clone()).or_default().push((i, dc.get_requests as f64, dc.get_pct_miss));
+            }
+        }
+    }
+    let mut trends: Vec<DictionaryCacheTrend> = caches.into_iter()
+        .filter(|(_, v)| v.len() >= MIN_SNAPSHOTS)
+        .map(|(cache, v)| {
+            let miss: Vec<f64> = v.iter().map(|(_, _, m)| *m).collect();
+            let first: Vec<f64> = v.iter().filter(|(i, _, _)| *i < half).map(|(_, _, m)| *m).collect();
+            let second: Vec<f64> = v.iter().filter(|(i, _, _)| *i >= half).map(|(_, _, m)| *m).collect();
+            //Cache seen in only one half of the range has no trend - median of the empty half would be 0
+            let insufficient = first.is_empty() || second.is_empty();
+            let (first, second) = (median(&first), median(&second));
+            let trend = if insufficient {
+                "INSUFFICIENT_DATA"
+            } else if second >= first * 1.5 && second - first >= 1.0 {
+                "INCREASING"
+            } else if first >= second * 1.5 && first - second >= 1.0 {
+                "DECREASING"
+            } else {
+                "STABLE"
+            };
+            DictionaryCacheTrend {
+                cache,
+                get_requests_median: median(&v.iter().map(|(_, g, _)| *g).collect::<Vec<f64>>()),
+                get_pct_miss_median: median(&miss),
+                get_pct_miss_first_half: first,
+                get_pct_miss_second_half: second,
+                trend: trend.to_string(),
+            }
+        })
+        .collect();
+    // Caches with most misses first
+    trends.sort_by(|a, b| (b.get_requests_median * b.get_pct_miss_median).partial_cmp(&(a.get_requests_median * a.get_pct_miss_median)).unwrap_or(std::cmp::Ordering::Equal));
+    trends
+}
+
+fn dictionary_cache_advice(cache: &str) -> &'static str {
+    match cache {
+        "dc_sequences" => "Increase CACHE of frequently used sequences.",
+        "dc_objects" | "dc_object_grants" | "dc_users" => "Objects are often aged out or invalidated - check shared pool size and frequent DDL or grants.",
+        c if c.starts_with("dc_histogram") => "Histograms are reloaded often - check statistics gathering and shared pool size.",
+        "dc_segments" | "dc_tablespace_quotas" | "dc_used_extents" => "Space management activity - check segments growing by small extents.",
+        _ => "Check shared pool size and what changes the dictionary objects cached by it.",
+    }
+}
+
+/// Library cache and dictionary cache health analysis. Writes stats/shared_pool.html and attaches
+/// SharedPoolHealth to ReportForAI. Returns true if the page was written.
+pub fn report_shared_pool_health(awrs: &[AWR], snap_range: &(u64,u64), args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) -> bool {
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{}\n", "Shared pool health - Library Cache and Dictionary Cache".yellow());
+
+    let awr_snaps: Vec<&AWR> = snaps_in_range(awrs, snap_range)
+        .filter(|awr| !awr.library_cache.is_empty() || !awr.dictionary_cache.is_empty())
+        .collect();
+    if awr_snaps.len() < MIN_SNAPSHOTS {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNot enough snapshots with Library Cache or Dictionary Cache statistics\n".green().italic());
+        return false;
+    }
+    let snaps: Vec<SnapPool> = awr_snaps.iter().map(|awr| snapshot_pool(awr)).collect();
+
+    let reload_pct: Vec<f64> = snaps.iter().map(|s| s.reload_pct).collect();
+    let invalidations: Vec<f64> = snaps.iter().map(|s| s.invalidations as f64).collect();
+    let sql_area_miss: Vec<f64> = snaps.iter().map(|s| s.sql_area_get_pct_miss).collect();
+    let dc_miss: Vec<f64> = snaps.iter().map(|s| s.dc_get_pct_miss).collect();
+    let hard_parses: Vec<f64> = snaps.iter().map(|s| s.hard_parses_per_s).collect();
+    let mutex_x: Vec<f64> = snaps.iter().map(|s| s.mutex_x_s).collect();
+    let pin_s: Vec<f64> = snaps.iter().map(|s| s.pin_s_wait_on_x_s).collect();
+
+    // Invalidation storms
+    let inv_z = robust_z_score(&invalidations);
+    let storms: Vec<InvalidationStorm> = snaps.iter().enumerate()
+        .filter(|(i, s)| inv_z[*i] >= INVALIDATION_STORM_Z && s.invalidations >= STORM_MIN_INVALIDATIONS)
+        .map(|(i, s)| InvalidationStorm {
+            snap_id: s.snap_id,
+            snap_date: s.snap_date.clone(),
+            invalidations: s.invalidations,
+            reloads: s.reloads,
+            robust_z: inv_z[i],
+            cursor_pin_s_wait_on_x_s: s.pin_s_wait_on_x_s,
+        })
+        .collect();
+
+    // Cache metrics vs hard parses and mutex waits
+    let mut correlations: Vec<SharedPoolCorrelation> = Vec::new();
+    for (metric, m) in [("Reload %", &reload_pct), ("Invalidations", &invalidations), ("SQL AREA get % miss", &sql_area_miss), ("Dictionary get % miss", &dc_miss)] {
+        for (signal, sig) in [("Hard parses/s", &hard_parses), ("library cache: mutex X", &mutex_x), ("cursor: pin S wait on X", &pin_s)] {
+            if is_constant(m) || is_constant(sig) {
+                continue;
+            }
+            let r = pearson_correlation_2v(m, sig);
+            if r.is_finite() {
+                correlations.push(SharedPoolCorrelation { metric: metric.to_string(), signal: signal.to_string(), pearson_r: r });
+            }
+        }
+    }
+    correlations.sort_by(|a, b| b.pearson_r.abs().partial_cmp(&a.pearson_r.abs()).unwrap_or(std::cmp::Ordering::Equal));
+    let corr_of = |metric: &str, signal: &str| correlations.iter().find(|c| c.metric == metric && c.signal == signal).map_or(0.0, |c| c.pearson_r);
+
+    let dictionary_caches = dictionary_cache_trends(&awr_snaps);
+
+    // Findings
+    let mut findings: Vec<SharedPoolFinding> = Vec::new();
+    let high_reload = snaps.iter().filter(|s| s.reload_pct > RELOAD_PCT_WARN).count();
+    if high_reload >= MIN_SNAPSHOTS {
+        findings.push(SharedPoolFinding {
+            severity: "WARNING".to_string(),
+            issue: "Shared pool pressure - library cache objects are reloaded".to_string(),
+            evidence: format!("Reload % above {} in {} of {} snapshots (max {:.2}%), correlation with hard parses/s: {:.2}",
+                RELOAD_PCT_WARN, high_reload, snaps.len(), reload_pct.iter().cloned().fold(0.0, f64::max), corr_of("Reload %", "Hard parses/s")),
+            recommendation: "Objects are aged out and loaded again. Check shared pool size (shared_pool_size, SGA Target Advisory), reduce hard parsing with bind variables and look for fragmentation (ORA-04031).".to_string(),
+        });
+    }
+    let high_miss = snaps.iter().filter(|s| s.sql_area_get_pct_miss > SQL_AREA_MISS_WARN_PCT).count();
+    if high_miss >= MIN_SNAPSHOTS {
+        findings.push(SharedPoolFinding {
+            severity: "WARNING".to_string(),
+            issue: "Many statements are not found in the library cache".to_string(),
+            evidence: format!("SQL AREA get % miss above {} in {} of {} snapshots, median hard parses/s: {:.1}",
+                SQL_AREA_MISS_WARN_PCT, high_miss, snaps.len(), median(&hard_parses)),
+            recommendation: "Statements are parsed from scratch, usually because of literals. Use bind variables and check version counts of top SQLs.".to_string(),
+        });
+    }
+    if !storms.is_empty() {
+        let with_waits = storms.iter().filter(|s| s.cursor_pin_s_wait_on_x_s > 0.0).count();
+        findings.push(SharedPoolFinding {
+            severity: if with_waits > 0 { "CRITICAL" } else { "WARNING" }.to_string(),
+            issue: "Cursor invalidation storms".to_string(),
+            evidence: format!("{} snapshots with unusual number of invalidations (max {}), {} of them with cursor: pin S wait on X, snapshots: {}",
+                storms.len(), storms.iter().map(|s| s.invalidations).max().unwrap_or(0), with_waits,
+                storms.iter().map(|s| s.snap_id.to_string()).collect::<Vec<String>>().join(", ")),
+            recommendation: "Invalidated cursors are hard parsed again by all sessions at once. Look for DDL, statistics gathering without NO_INVALIDATE, grants or partition maintenance in these periods.".to_string(),
+        });
+    }
+    for (metric, signal) in [("Invalidations", "cursor: pin S wait on X"), ("Reload %", "library cache: mutex X"), ("Invalidations", "library cache: mutex X")] {
+        let r = corr_of(metric, signal);
+        if r >= CORRELATION_MIN {
+            findings.push(SharedPoolFinding {
+                severity: "WARNING".to_string(),
+                issue: format!("{} waits follow {}", signal, metric.to_lowercase()),
+                evidence: format!("Pearson r = {:.2}, total wait time: {:.1}s", r, if signal.starts_with("cursor") { pin_s.iter().sum::<f64>() } else { mutex_x.iter().sum::<f64>() }),
+                recommendation: "Mutex waits are caused by cursors loaded again after invalidation or aging out. Remove the source of invalidations and reloads before tuning the mutexes.".to_string(),
+            });
+        }
+    }
+    for dc in dictionary_caches.iter().filter(|dc| dc.get_pct_miss_median > DC_MISS_WARN_PCT && dc.get_requests_median >= DC_MIN_GETS) {
+        findings.push(SharedPoolFinding {
+            severity: "INFO".to_string(),
+            issue: format!("Dictionary cache {} misses", dc.cache),
+            evidence: format!("Median get % miss {:.1} with {:.0} get requests per snapshot, trend: {} ({:.1}% -> {:.1}%)",
+                dc.get_pct_miss_median, dc.get_requests_median, dc.trend, dc.get_pct_miss_first_half, dc.get_pct_miss_second_half),
+            recommendation: dictionary_cache_advice(&dc.cache).to_string(),
+        });
+    }
+
+    let health = SharedPoolHealth {
+        reload_pct_median: median(&reload_pct),
+        reload_pct_max: reload_pct.iter().cloned().fold(0.0, f64::max),
+        sql_area_get_pct_miss_median: median(&sql_area_miss),
+        library_pin_pct_miss_median: median(&snaps.iter().map(|s| s.pin_pct_miss).collect::<Vec<f64>>()),
+        invalidations_total: snaps.iter().map(|s| s.invalidations).sum(),
+        dictionary_get_pct_miss_median: median(&dc_miss),
+        dictionary_caches,
+        invalidation_storms: storms,
+        correlations,
+        findings,
+    };
+
+    make_notes!(logfile_name, args.quiet, 0, "\tReload % median/max: {:.3}/{:.3}  SQL AREA get % miss median: {:.2}  pin % miss median: {:.2}  Invalidations: {}  Dictionary get % miss median: {:.2}\n",
+        health.reload_pct_median, health.reload_pct_max, health.sql_area_get_pct_miss_median, health.library_pin_pct_miss_median,
+        health.invalidations_total, health.dictionary_get_pct_miss_median);
+
+    let finding_headers = ["Severity", "Issue", "Evidence", "Recommendation"];
+    let mut finding_table = Table::new();
+    finding_table.set_titles(Row::new(finding_headers.iter().map(|h| Cell::new(h)).collect()));
+    for f in &health.findings {
+        finding_table.add_row(Row::new(vec![Cell::new(&f.severity), Cell::new(&f.issue), Cell::new(&f.evidence), Cell::new(&f.recommendation)]));
+    }
+    if health.findings.is_empty() {
+        make_notes!(logfile_name, args.quiet, 0, "{}", "\tNo shared pool issues found\n".green().italic());
+    } else {
+        make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&finding_table));
+    }
+
+    let dc_headers = ["Cache", "Get Requests (median)", "Get % Miss (median)", "First Half", "Second Half", "Trend"];
+    let mut dc_table = Table::new();
+    dc_table.set_titles(Row::new(dc_headers.iter().map(|h| Cell::new(h)).collect()));
+    for dc in &health.dictionary_caches {
+        dc_table.add_row(Row::new(vec![
+            Cell::new(&dc.cache),
+            Cell::new(&format!("{:.0}", dc.get_requests_median)),
+            Cell::new(&format!("{:.2}", dc.get_pct_miss_median)),
+            Cell::new(&format!("{:.2}", dc.get_pct_miss_first_half)),
+            Cell::new(&format!("{:.2}", dc.get_pct_miss_second_half)),
+            Cell::new(&dc.trend),
+        ]));
+    }
+
+    let corr_headers = ["Metric", "Signal", "Pearson r"];
+    let mut corr_table = Table::new();
+    corr_table.set_titles(Row::new(corr_headers.iter().map(|h| Cell::new(h)).collect()));
+    for c in &health.correlations {
+        corr_table.add_row(Row::new(vec![Cell::new(&c.metric), Cell::new(&c.signal), Cell::new(&format!("{:.3}", c.pearson_r))]));
+    }
+
+    // Shared pool page - four panels sharing snapshot axis
+    let x_vals: Vec<String> = snaps.iter().map(|s| s.x_val.clone()).collect();
+    let mut plot = Plot::new();
+    plot.add_trace(Scatter::new(x_vals.clone(), reload_pct.clone()).mode(Mode::Lines).name("Reload %").marker(Marker::new().color("#d62728")).y_axis("y4"));
+    plot.add_trace(Scatter::new(x_vals.clone(), sql_area_miss.clone()).mode(Mode::Lines).name("SQL AREA get % miss").marker(Marker::new().color("#1f77b4")).y_axis("y4"));
+    plot.add_trace(Scatter::new(x_vals.clone(), snaps.iter().map(|s| s.pin_pct_miss).collect::<Vec<f64>>()).mode(Mode::Lines).name("Library pin % miss").marker(Marker::new().color("#17becf")).y_axis("y4"));
+    plot.add_trace(Scatter::new(x_vals.clone(), dc_miss.clone()).mode(Mode::Lines).name("Dictionary get % miss").marker(Marker::new().color("#bcbd22")).y_axis("y4"));
+    plot.add_trace(Bar::new(x_vals.clone(), invalidations.clone()).name("Invalidations").marker(Marker::new().color("#9467bd")).y_axis("y3"));
+    if !health.invalidation_storms.is_empty() {
+        let idx: Vec<usize> = health.invalidation_storms.iter()
+            .filter_map(|st| snaps.iter().position(|s| s.snap_id == st.snap_id))
+            .collect();
+        plot.add_trace(Scatter::new(idx.iter().map(|i| x_vals[*i].clone()).collect::<Vec<String>>(), idx.iter().map(|i| invalidations[*i]).collect::<Vec<f64>>())
+            .mode(Mode::Markers)
+            .name("Invalidation storms")
+            .marker(Marker::new().color("#d62728").size(10).symbol(MarkerSymbol::TriangleUp))
+            .y_axis("y3"));
+    }
+    plot.add_trace(Scatter::new(x_vals.clone(), hard_parses.clone()).mode(Mode::Lines).name("Hard parses/s").marker(Marker::new().color("#ff7f0e")).y_axis("y2"));
+    plot.add_trace(Scatter::new(x_vals.clone(), mutex_x.clone()).mode(Mode::Lines).name("library cache: mutex X (s)").marker(Marker::new().color("#8c564b")));
+    plot.add_trace(Scatter::new(x_vals.clone(), pin_s.clone()).mode(Mode::Lines).name("cursor: pin S wait on X (s)").marker(Marker::new().color("#e377c2")));
+    plot.set_layout(Layout::new()
+        .title("Shared pool health - Library Cache and Dictionary Cache")
+        .height(1200)
+        .hover_mode(HoverMode::X)
+        .y_axis(Axis::new().domain(&[0.0, 0.22]).anchor("x").title("Mutex waits (s)").range_mode(RangeMode::ToZero))
+        .y_axis2(Axis::new().domain(&[0.26, 0.48]).anchor("x").title("Hard parses/s").range_mode(RangeMode::ToZero))
+        .y_axis3(Axis::new().domain(&[0.52, 0.74]).anchor("x").title("Invalidations").range_mode(RangeMode::ToZero))
+        .y_axis4(Axis::new().domain(&[0.78, 1.0]).anchor("x").title("%").range_mode(RangeMode::ToZero)));
+
+    let fname = format!("{}/stats/shared_pool.html", html_dir);
+    plot.write_html(Path::new(&fname));
+    let mut page_html = table_to_html_string(&finding_table, "Shared pool findings", &finding_headers);
+    page_html.push_str(&table_to_html_string(&dc_table, "Dictionary cache get misses", &dc_headers));
+    page_html.push_str(&table_to_html_string(&corr_table, "Correlation with hard parses and mutex waits", &corr_headers));
+    if let Err(e) = append_html_before_body_end(&fname, &page_html) {
+        eprintln!("Error writing file {}: {}", fname, e);
+    }
+
+    report_for_ai.shared_pool_health = Some(health);
+    true
+}
@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use crate::awr::AWR;
+use crate::Args;
+use crate::tools::*;
+use plotly::{Plot, Scatter};
+use plotly::common::{Marker, MarkerSymbol, Mode};
+use plotly::layout::{Axis, HoverMode, Layout, RangeMode};
+use prettytable::{Table, Row, Cell};
+
+/* Snapshot drill-down pages.
+   One page per snapshot (snapshots/snap_<begin snap id>.html) with load profile, time model, top wait events, top SQLs,
+   IO functions, segments and anomalies of the snapshot next to the median of the surrounding window
+   (--snapshot-window snapshots on each side, the snapshot itself excluded). A missing value in a window snapshot is 0.
+   Points of the main chart open the page of their snapshot. */
+
+/// Number of wait events and SQLs on a page
+const TOP_ITEMS: usize = 10;
+/// Number of objects for each segment statistic
+const TOP_SEGMENTS: usize = 3;
+
+/// Injected into jasmin_main.html - click on a point of the main chart opens the snapshot page, x value ends with (snap id)
+pub const SNAPSHOT_CLICK_SCRIPT: &str = r#"<script>//JAS-MIN snapshot drill-down
+        document.addEventListener('DOMContentLoaded', function() {
+            const mainPlot = document.getElementById('plotly-html-element');
+            if (!mainPlot || !mainPlot.on) return;
+            mainPlot.on('plotly_click', function(data) {
+                if (!data.points || data.points.length === 0) return;
+                const m = String(data.points[0].x).match(/\((\d+)\)\s*$/);
+                if (m) {
+                    window.open('snapshots/snap_' + m[1] + '.html', '_blank');
+                }
+            });
+        });
+        </script>"#;
+
+fn change_pct(value: f64, median: f64) -> String {
+    if median.abs() > f64::EPSILON {
+        format!("{:+.1}%", (value / median - 1.0) * 100.0)
+    } else {
+        "-".to_string()
+    }
+}
+
+fn window_median<F: Fn(&AWR) -> f64>(window: &[&AWR], value_of: F) -> f64 {
+    if window.is_empty() {
+        return f64::NAN;
+    }
+    median(&window.iter().map(|awr| value_of(awr)).collect::<Vec<f64>>())
+}
+
+fn comparison_table(rows: &[(String, f64, f64)]) -> Table {
+    let mut table = Table::new();
+    table.set_titles(Row::new(vec![Cell::new("Statistic"), Cell::new("This Snapshot"), Cell::new("Window Median"), Cell::new("Change")]));
+    for (name, value, med) in rows {
+        table.add_row(Row::new(vec![
+            Cell::new(name),
+            Cell::new(&format!("{:.2}", value)),
+            Cell::new(&format!("{:.2}", med)),
+            Cell::new(&change_pct(*value, *med)),
+        ]));
+    }
+    table
+}
+
+/// Link to an existing page of the report (relative to snapshots/), plain text when the page was not generated
+fn link_if_exists(html_dir: &str, page: &str, text: &str) -> String {
+    if Path::new(html_dir).join(page).exists() {
+        format!("<a href=\"../{}\" target=\"_blank\">{}</a>", page, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Page header (above the plot) and tables (below the plot) of a snapshot page
+fn snapshot_page(awr: &AWR, window: &[&AWR], prev: Option<u64>, next: Option<u64>, window_awrs: &[&AWR], anomalies: &[String], html_dir: &str) -> (String, String) {
+    let id = awr.snap_info.begin_snap_id;
+    let header = format!(
+        "<p><span style=\"font-size:20px;font-weight:bold;\">Snapshot {} - {} to {}</span><br>\
+        {} | <a href=\"../jasmin_main.html\">Main report</a> | {}<br>\
+        Window median of {} surrounding snapshots</p>\n",
+        id, awr.snap_info.begin_snap_time, awr.snap_info.end_snap_time,
+        prev.map_or("&lt; previous".to_string(), |p| format!("<a href=\"snap_{}.html\">&lt; previous</a>", p)),
+        next.map_or("next &gt;".to_string(), |n| format!("<a href=\"snap_{}.html\">next &gt;</a>", n)),
+        window.len()
+    );
+    let mut html = String::new();
+
+    // Load Profile and Time Model
+    let lp_rows: Vec<(String, f64, f64)> = awr.load_profile.iter()
+        .map(|lp| (lp.stat_name.clone(), lp.per_second, window_median(window, |w| w.load_profile.iter().find(|x| x.stat_name == lp.stat_name).map_or(0.0, |x| x.per_second))))
+        .collect();
+    html.push_str(&table_to_html_string(&comparison_table(&lp_rows), "Load Profile (per second)", &["Statistic", "This Snapshot", "Window Median", "Change"]));
+    let tm_rows: Vec<(String, f64, f64)> = awr.time_model_stats.iter()
+        .map(|tm| (tm.stat_name.clone(), tm.time_s, window_median(window, |w| w.time_model_stats.iter().find(|x| x.stat_name == tm.stat_name).map_or(0.0, |x| x.time_s))))
+        .collect();
+    html.push_str(&table_to_html_string(&comparison_table(&tm_rows), "Time Model (s)", &["Statistic", "This Snapshot", "Window Median", "Change"]));
+
+    // Top foreground wait events
+    let mut events: Vec<_> = awr.foreground_wait_events.iter().collect();
+    events.sort_by(|a, b| b.total_wait_time_s.partial_cmp(&a.total_wait_time_s).unwrap_or(std::cmp::Ordering::Equal));
+    let headers = ["Event", "Waits", "Wait Time (s)", "Avg Wait (ms)", "% DB Time", "Window Median (s)", "Change"];
+    let mut table = Table::new();
+    for e in events.iter().take(TOP_ITEMS) {
+        let med = window_median(window, |w| w.foreground_wait_events.iter().find(|x| x.event == e.event).map_or(0.0, |x| x.total_wait_time_s));
+        table.add_row(Row::new(vec![
+            Cell::new(&link_if_exists(html_dir, &get_safe_filename(e.event.clone(), "fg".to_string()), &e.event)),
+            Cell::new(&format!("{}", e.waits)),
+            Cell::new(&format!("{:.2}", e.total_wait_time_s)),
+            Cell::new(&format!("{:.2}", if e.waits > 0 { e.total_wait_time_s * 1000.0 / e.waits as f64 } else { 0.0 })),
+            Cell::new(&format!("{:.1}", e.pct_dbtime)),
+            Cell::new(&format!("{:.2}", med)),
+            Cell::new(&change_pct(e.total_wait_time_s, med)),
+        ]));
+    }
+    html.push_str(&table_to_html_string(&table, "Top foreground wait events", &headers));
+
+    // Top SQLs by elapsed time
+    let mut sqls: Vec<_> = awr.sql_elapsed_time.iter().collect();
+    sqls.sort_by(|a, b| b.elapsed_time_s.partial_cmp(&a.elapsed_time_s).unwrap_or(std::cmp::Ordering::Equal));
+    let headers = ["SQL_ID", "Module", "Elapsed (s)", "Executions", "Elapsed/Exec (s)", "% Total", "Window Median (s)", "Change"];
+    let mut table = Table::new();
+    for s in sqls.iter().take(TOP_ITEMS) {
+        let med = window_median(window, |w| w.sql_elapsed_time.iter().find(|x| x.sql_id == s.sql_id).map_or(0.0, |x| x.elapsed_time_s));
+        table.add_row(Row::new(vec![
+            Cell::new(&link_if_exists(html_dir, &format!("sqlid/sqlid_{}.html", s.sql_id), &s.sql_id)),
+            Cell::new(&s.sql_module),
+            Cell::new(&format!("{:.2}", s.elapsed_time_s)),
+            Cell::new(&format!("{}", s.executions)),
+            Cell::new(&format!("{:.4}", s.elpased_time_exec_s)),
+            Cell::new(&format!("{:.1}", s.pct_total)),
+            Cell::new(&format!("{:.2}", med)),
+            Cell::new(&change_pct(s.elapsed_time_s, med)),
+        ]));
+    }
+    html.push_str(&table_to_html_string(&table, "Top SQLs by elapsed time", &headers));
+
+    // IO functions
+    let functions: BTreeMap<&String, _> = awr.io_stats_byfunc.iter().collect();
+    let headers = ["Function", "Reads MB/s", "Writes MB/s", "Waits", "Avg Time (ms)", "Window Median (ms)", "Change"];
+    let mut table = Table::new();
+    for (func, io) in functions {
+        let avg = io.avg_time.unwrap_or(0.0);
+        let med = window_median(window, |w| w.io_stats_byfunc.get(func).and_then(|x| x.avg_time).unwrap_or(0.0));
+        table.add_row(Row::new(vec![
+            Cell::new(func),
+            Cell::new(&format!("{:.2}", io.reads_data_s)),
+            Cell::new(&format!("{:.2}", io.writes_data_s)),
+            Cell::new(&format!("{}", io.waits_count)),
+            Cell::new(&format!("{:.2}", avg)),
+            Cell::new(&format!("{:.2}", med)),
+            Cell::new(&change_pct(avg, med)),
+        ]));
+    }
+    html.push_str(&table_to_html_string(&table, "IO Stats by Function", &headers));
+
+    // Segments - top objects of every segment statistic
+    let statistics: BTreeMap<&String, _> = awr.segment_stats.iter().collect();
+    let headers = ["Statistic", "Object", "Type", "Value", "Window Median", "Change"];
+    let mut table = Table::new();
+    for (stat, segments) in statistics {
+        let mut segments: Vec<_> = segments.iter().collect();
+        segments.sort_by(|a, b| b.stat_vlalue.partial_cmp(&a.stat_vlalue).unwrap_or(std::cmp::Ordering::Equal));
+        for seg in segments.iter().take(TOP_SEGMENTS) {
+            let med = window_median(window, |w| w.segment_stats.get(stat)
+                .and_then(|v| v.iter().find(|x| x.obj == seg.obj && x.objd == seg.objd && x.object_name == seg.object_name))
+                .map_or(0.0, |x| x.stat_vlalue));
+            table.add_row(Row::new(vec![
+                Cell::new(stat),
+                Cell::new(&seg.object_name),
+                Cell::new(&seg.object_type),
+                Cell::new(&format!("{:.0}", seg.stat_vlalue)),
+                Cell::new(&format!("{:.0}", med)),
+                Cell::new(&change_pct(seg.stat_vlalue, med)),
+            ]));
+        }
+    }
+    html.push_str(&table_to_html_string(&table, "Segment Statistics", &headers));
+
+    // Anomalies detected in the snapshot
+    let mut table = Table::new();
+    for a in anomalies {
+        table.add_row(Row::new(vec![Cell::new(a)]));
+    }
+    html.push_str(&table_to_html_string(&table, &format!("Anomalies detected in this snapshot: {}", anomalies.len()), &["Anomaly"]));
+
+    // Window snapshots for quick navigation
+    html.push_str("<p>Window: ");
+    html.push_str(&window_awrs.iter()
+        .map(|w| if w.snap_info.begin_snap_id == id {
+            format!("<b>{}</b>", id)
+        } else {
+            format!("<a href=\"snap_{}.html\">{}</a>", w.snap_info.begin_snap_id, w.snap_info.begin_snap_id)
+        })
+        .collect::<Vec<String>>()
+        .join(" "));
+    html.push_str("</p>\n");
+    (header, html)
+}
+
+/// Writes snapshots/snap_<begin snap id>.html for every snapshot in snap_range - the snapshot next to the median
+/// of its surrounding window. Returns true if pages were written (--snapshot-window 0 disables them).
+pub fn generate_snapshot_pages(awrs: &[AWR], snap_range: &(u64,u64), anomalies_summary: &BTreeMap<(u64, String), BTreeMap<String, Vec<String>>>, args: &Args, html_dir: &str) -> bool {
+    if args.snapshot_window == 0 {
+        return false;
+    }
+    let snaps: Vec<&AWR> = snaps_in_range(awrs, snap_range)
+        .collect();
+    if snaps.is_empty() {
+        return false;
+    }
+
+    let mut anomalies: HashMap<u64, Vec<String>> = HashMap::new();
+    for ((snap_id, _), by_type) in anomalies_summary {
+        for (anomaly_type, details) in by_type {
+            anomalies.entry(*snap_id).or_default().extend(details.iter().map(|d| format!("{}: {}", anomaly_type, d)));
+        }
+    }
+    let db_time = |awr: &AWR| db_time_s(awr);
+    let db_cpu = |awr: &AWR| awr.time_model_stats.iter().find(|tm| tm.stat_name.starts_with("DB CPU")).map_or(0.0, |tm| tm.time_s);
+
+    let w = args.snapshot_window;
+    for (i, awr) in snaps.iter().enumerate() {
+        let window_awrs = &snaps[i.saturating_sub(w)..(i + w + 1).min(snaps.len())];
+        let window: Vec<&AWR> = window_awrs.iter().filter(|x| x.snap_info.begin_snap_id != awr.snap_info.begin_snap_id).copied().collect();
+        let prev = if i > 0 { Some(snaps[i - 1].snap_info.begin_snap_id) } else { None };
+        let next = snaps.get(i + 1).map(|n| n.snap_info.begin_snap_id);
+
+        // DB Time and DB CPU in the window, this snapshot marked
+        let x_vals: Vec<String> = window_awrs.iter().map(|x| format!("{} ({})", x.snap_info.begin_snap_time, x.snap_info.begin_snap_id)).collect();
+        let mut plot = Plot::new();
+        plot.add_trace(Scatter::new(x_vals.clone(), window_awrs.iter().map(|x| db_time(x)).collect::<Vec<f64>>())
+            .mode(Mode::LinesMarkers)
+            .name("DB Time (s)"));
+        plot.add_trace(Scatter::new(x_vals.clone(), window_awrs.iter().map(|x| db_cpu(x)).collect::<Vec<f64>>())
+            .mode(Mode::LinesMarkers)
+            .name("DB CPU (s)"));
+        plot.add_trace(Scatter::new(vec![format!("{} ({})", awr.snap_info.begin_snap_time, awr.snap_info.begin_snap_id)], vec![db_time(awr)])
+            .mode(Mode::Markers)
+            .name("This snapshot")
+            .marker(Marker::new().color("#d62728").size(14).symbol(MarkerSymbol::Diamond)));
+        plot.set_layout(Layout::new()
+            .title(format!("Snapshot {} - DB Time in the surrounding window", awr.snap_info.begin_snap_id))
+            .height(400)
+            .hover_mode(HoverMode::X)
+            .y_axis(Axis::new().title("Seconds").range_mode(RangeMode::ToZero)));
+
+        let fname = format!("{}/snapshots/snap_{}.html", html_dir, awr.snap_info.begin_snap_id);
+        plot.write_html(Path::new(&fname));
+        let (header, tables) = snapshot_page(awr, &window, prev, next, window_awrs, anomalies.get(&awr.snap_info.begin_snap_id).map_or(&[], |a| a.as_slice()), html_dir);
+        if let Err(e) = inject_html_into_body(&fname, &header, &tables) {
+            eprintln!("Error writing file {}: {}", fname, e);
+        }
+    }
+    println!("Saved snapshot drill-down pages to '{}/snapshots/snap_*'", html_dir);
+    true
+}
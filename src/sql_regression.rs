@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use crate::awr::AWR;
+use crate::Args;
+use crate::make_notes;
+use crate::tools::*;
+use crate::reasonings::{ReportForAI, SQLPerformanceRegression};
+use colored::*;
+use prettytable::{Table, Row, Cell};
+use rayon::prelude::*;
+
+/* Per-SQL regression detection on per-execution metrics.
+   Totals (elapsed_time_s) grow both when a SQL runs more often and when each execution gets slower.
+   Here we look for a level shift in cost per execution and compare it with the number of executions
+   in the same periods, so "more executions" can be separated from "each execution got slower". */
+
+const ELAPSED_PER_EXEC: &str = "Elapsed Time / Exec (s)";
+const CPU_PER_EXEC: &str = "CPU Time / Exec (s)";
+const GETS_PER_EXEC: &str = "Buffer Gets / Exec";
+const READS_PER_EXEC: &str = "Physical Reads / Exec";
+const EXECUTIONS: &str = "Executions";
+
+/// Minimum number of probes on each side of the change point
+const MIN_SEGMENT: usize = 3;
+/// Minimum robust shift score (difference of medians in pooled MAD units) to accept a level shift
+const MIN_SHIFT_SCORE: f64 = 3.0;
+
+/// Single observation of SQL in Top SQL section: (index of AWR, value per execution, number of executions)
+type ExecPoint = (usize, f64, f64);
+
+//Collect per-execution values for each SQL_ID and metric - only snapshots where SQL was present in given section
+fn get_sql_per_exec_points(awrs: &[AWR], snap_range: &(u64,u64)) -> BTreeMap<String, BTreeMap<&'static str, Vec<ExecPoint>>> {
+    let (f_begin_snap, f_end_snap) = snap_range;
+    let mut points: BTreeMap<String, BTreeMap<&'static str, Vec<ExecPoint>>> = BTreeMap::new();
+
+    for (i, awr) in awrs.iter().enumerate() {
+        if awr.snap_info.begin_snap_id < *f_begin_snap || awr.snap_info.end_snap_id > *f_end_snap {
+            continue;
+        }
+        for s in &awr.sql_elapsed_time {
+            if s.executions > 0 {
+                points.entry(s.sql_id.clone()).or_default()
+                      .entry(ELAPSED_PER_EXEC).or_default()
+                      .push((i, s.elpased_time_exec_s, s.executions as f64));
+            }
+        }
+        for s in awr.sql_cpu_time.values() {
+            if s.executions > 0 {
+                points.entry(s.sql_id.clone()).or_default()
+                      .entry(CPU_PER_EXEC).or_default()
+                      .push((i, s.cpu_time_exec_s, s.executions as f64));
+            }
+        }
+        for s in awr.sql_gets.values() {
+            if s.executions > 0 {
+                points.entry(s.sql_id.clone()).or_default()
+                      .entry(GETS_PER_EXEC).or_default()
+                      .push((i, s.gets_per_exec, s.executions as f64));
+            }
+        }
+        for s in awr.sql_reads.values() {
+            if s.executions > 0 {
+                points.entry(s.sql_id.clone()).or_default()
+                      .entry(READS_PER_EXEC).or_default()
+                      .push((i, s.reads_per_exec, s.executions as f64));
+            }
+        }
+    }
+    points
+}
+
+/// Detects SQL_IDs whose cost per execution shifted up and stayed there.
+/// A shift is reported when the median after the change point is at least `min_ratio` times the median before
+/// and the robust shift score exceeds MIN_SHIFT_SCORE. For each regression the executions in the same periods are
+/// compared as well - if they also grew by `min_ratio` both effects are present.
+/// SQL_IDs with growing executions and flat per-execution cost are reported as MORE_EXECUTIONS.
+/// The split is searched over the snapshots in which the SQL_ID appeared in Top SQL sections, not over the whole
+/// snapshot sequence - MIN_SEGMENT counts appearances and medians ignore snapshots where the SQL was absent.
+/// The split index is always mapped back through the AWR index stored with each point, so the reported change
+/// snapshot is the first appearance after the shift (which may come later than the first snapshot of the gap).
+pub fn detect_sql_regressions(awrs: &[AWR], snap_range: &(u64,u64), min_ratio: f64) -> Vec<SQLPerformanceRegression> {
+    let points = get_sql_per_exec_points(awrs, snap_range);
+
+    let mut regressions: Vec<SQLPerformanceRegression> = points
+        .par_iter() //each thread will process one SQL_ID
+        .flat_map(|(sql_id, metrics)| {
+            let mut found: Vec<SQLPerformanceRegression> = Vec::new();
+
+            for (metric, pts) in metrics {
+                let values: Vec<f64> = pts.iter().map(|p| p.1).collect();
+                let Some((k, before, after, score)) = robust_level_shift(&values, MIN_SEGMENT) else { continue; };
+
+                //Only upward shifts are regressions, before has to be positive to express the change as ratio
+                if before <= 0.0 || after <= before || score < MIN_SHIFT_SCORE || after / before < min_ratio {
+                    continue;
+                }
+
+                let execs: Vec<f64> = pts.iter().map(|p| p.2).collect();
+                let exec_before = median(&execs[..k]);
+                let exec_after = median(&execs[k..]);
+                let cause = if exec_before > 0.0 && exec_after / exec_before >= min_ratio {
+                    "SLOWER_PER_EXEC_AND_MORE_EXECUTIONS"
+                } else {
+                    "SLOWER_PER_EXEC"
+                };
+
+                //k indexes SQL appearances - translate it to the AWR where the new level starts
+                let snap_info = &awrs[pts[k].0].snap_info;
+                found.push(SQLPerformanceRegression {
+                    sql_id: sql_id.clone(),
+                    metric: metric.to_string(),
+                    cause: cause.to_string(),
+                    change_begin_snap_id: snap_info.begin_snap_id,
+                    change_begin_snap_date: snap_info.begin_snap_time.clone(),
+                    median_before: before,
+                    median_after: after,
+                    change_ratio: after / before,
+                    shift_score: score,
+                    executions_median_before: exec_before,
+                    executions_median_after: exec_after,
+                });
+            }
+
+            //Workload growth without per-execution regression - the SQL is not slower, it is just executed more often
+            if found.is_empty() {
+                if let Some(pts) = metrics.get(ELAPSED_PER_EXEC) {
+                    let execs: Vec<f64> = pts.iter().map(|p| p.2).collect();
+                    if let Some((k, before, after, score)) = robust_level_shift(&execs, MIN_SEGMENT) {
+                        if before > 0.0 && after / before >= min_ratio && score >= MIN_SHIFT_SCORE {
+                            let snap_info = &awrs[pts[k].0].snap_info;
+                            found.push(SQLPerformanceRegression {
+                                sql_id: sql_id.clone(),
+                                metric: EXECUTIONS.to_string(),
+                                cause: "MORE_EXECUTIONS".to_string(),
+                                change_begin_snap_id: snap_info.begin_snap_id,
+                                change_begin_snap_date: snap_info.begin_snap_time.clone(),
+                                median_before: before,
+                                median_after: after,
+                                change_ratio: after / before,
+                                shift_score: score,
+                                executions_median_before: before,
+                                executions_median_after: after,
+                            });
+                        }
+                    }
+                }
+            }
+            found
+        })
+        .collect();
+
+    //Real regressions first, than workload growth - inside each group the biggest change goes first
+    regressions.sort_by(|a, b| {
+        (a.cause == "MORE_EXECUTIONS").cmp(&(b.cause == "MORE_EXECUTIONS"))
+            .then(b.change_ratio.partial_cmp(&a.change_ratio).unwrap_or(std::cmp::Ordering::Equal))
+    });
+    regressions
+}
+
+/// Prints detected SQL regressions to the log, stores them in ReportForAI and returns rows for the HTML table
+pub fn report_sql_regressions(awrs: &[AWR], snap_range: &(u64,u64), args: &Args, logfile_name: &str, html_dir: &str, report_for_ai: &mut ReportForAI) -> String {
+    let regressions = detect_sql_regressions(awrs, snap_range, args.sql_regression_ratio);
+
+    make_notes!(logfile_name, args.quiet, 0, "\n\n");
+    make_notes!(logfile_name, false, 2, "{} {}\n", "SQL per execution regressions - minimal change ratio:".yellow(), args.sql_regression_ratio);
+
+    let mut html_rows = String::new();
+    if regressions.is_empty() {
+        let no_regressions_txt = "\tNo SQL_ID with a level shift of per execution metrics was found\n".to_string();
+        make_notes!(logfile_name, args.quiet, 0, "{}", no_regressions_txt.green().italic());
+        return html_rows;
+    }
+
+    let mut table = Table::new();
+    table.set_titles(Row::new(vec![
+        Cell::new("SQL_ID"),
+        Cell::new("Metric"),
+        Cell::new("Cause"),
+        Cell::new("Change began"),
+        Cell::new("Median before"),
+        Cell::new("Median after"),
+        Cell::new("Ratio"),
+        Cell::new("Shift score"),
+        Cell::new("Execs before"),
+        Cell::new("Execs after"),
+    ]));
+
+    for r in &regressions {
+        table.add_row(Row::new(vec![
+            Cell::new(&r.sql_id),
+            Cell::new(&r.metric),
+            Cell::new(&r.cause),
+            Cell::new(&format!("{} ({})", r.change_begin_snap_date, r.change_begin_snap_id)),
+            Cell::new(&format!("{:.4}", r.median_before)),
+            Cell::new(&format!("{:.4}", r.median_after)),
+            Cell::new(&format!("{:.2}", r.change_ratio)),
+            Cell::new(&format!("{:.2}", r.shift_score)),
+            Cell::new(&format!("{:.0}", r.executions_median_before)),
+            Cell::new(&format!("{:.0}", r.executions_median_after)),
+        ]));
+
+        //Only TOP SQLs have their own page
+        let sql_id_html = if Path::new(&format!("{}/sqlid/sqlid_{}.html", html_dir, r.sql_id)).exists() {
+            format!(r#"<a href="sqlid/sqlid_{0}.html" target="_blank" class="nav-link" style="font-weight: bold">{0}</a>"#, r.sql_id)
+        } else {
+            r.sql_id.clone()
+        };
+
+        html_rows.push_str(&format!(
+            r#"<tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{} ({})</td>
+                <td>{:.4}</td>
+                <td>{:.4}</td>
+                <td>{:.2}</td>
+                <td>{:.2}</td>
+                <td>{:.0}</td>
+                <td>{:.0}</td>
+            </tr>"#,
+            sql_id_html, r.metric, r.cause, r.change_begin_snap_date, r.change_begin_snap_id,
+            r.median_before, r.median_after, r.change_ratio, r.shift_score,
+            r.executions_median_before, r.executions_median_after
+        ));
+    }
+
+    make_notes!(logfile_name, args.quiet, 0, "{}", indented_table(&table));
+
+    report_for_ai.sql_performance_regressions = regressions;
+    html_rows
+}
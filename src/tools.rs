@@ -11,10 +11,10 @@ use noisy_float::types::N64;
 use prettytable::{Table, Row, Cell};
 use html_escape::encode_text;
 use pulldown_cmark::{html, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
-use crate::awr::GetStats;
+use crate::awr::{AWR, GetStats};
 use tokio::sync::oneshot;
 use serde::Serialize;
-use chrono::Local;
+use chrono::{Local, NaiveDateTime};
 use regex::Regex;
 
 /// Bonferroni-corrected correlation significance threshold.
@@ -34,7 +34,7 @@ pub fn bonferroni_significance_threshold(num_tests: usize, alpha: f64, sample_si
 }
 
 /// Abramowitz & Stegun approximation for normal quantile
-fn normal_quantile(p: f64) -> f64 {
+pub fn normal_quantile(p: f64) -> f64 {
     if p <= 0.0 { return f64::NEG_INFINITY; }
     if p >= 1.0 { return f64::INFINITY; }
     if p == 0.5 { return 0.0; }
@@ -51,10 +51,81 @@ fn normal_quantile(p: f64) -> f64 {
     if negate { -result } else { result }
 }
 
+/// Lanczos approximation of ln(Gamma(x)) for x > 0
+fn ln_gamma(x: f64) -> f64 {
+    const COEF: [f64; 6] = [76.18009172947146, -86.50532032941677, 24.01409824083091,
+                            -1.231739572450155, 0.1208650973866179e-2, -0.5395239384953e-5];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut ser = 1.000000000190015;
+    for (j, c) in COEF.iter().enumerate() {
+        ser += c / (x + 1.0 + j as f64);
+    }
+    -tmp + (2.5066282746310005 * ser / x).ln()
+}
+
+/// Continued fraction for the regularized incomplete beta function (modified Lentz)
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let tiny = 1e-300;
+    let (qab, qap, qam) = (a + b, a + 1.0, a - 1.0);
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / qap;
+    if d.abs() < tiny { d = tiny; }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..300 {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < tiny { d = tiny; }
+        c = 1.0 + aa / c;
+        if c.abs() < tiny { c = tiny; }
+        d = 1.0 / d;
+        h *= d * c;
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < tiny { d = tiny; }
+        c = 1.0 + aa / c;
+        if c.abs() < tiny { c = tiny; }
+        d = 1.0 / d;
+        let del = d * c;
+        h *= del;
+        if (del - 1.0).abs() < 1e-12 {
+            break;
+        }
+    }
+    h
+}
+
+/// Regularized incomplete beta function I_x(a, b)
+pub fn regularized_incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 { return 0.0; }
+    if x >= 1.0 { return 1.0; }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - ln_front.exp() * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Two-sided p-value of Student's t statistic with df degrees of freedom
+pub fn student_t_two_sided_p_value(t: f64, df: f64) -> f64 {
+    if !t.is_finite() || df <= 0.0 {
+        return if t.is_infinite() { 0.0 } else { 1.0 };
+    }
+    regularized_incomplete_beta(df / 2.0, 0.5, df / (df + t * t)).clamp(0.0, 1.0)
+}
+
 pub fn get_timestamp() -> String {
     Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string()
 }
 
+/// Table as text with every line indented by a tab - logged with a single make_notes! call
+pub fn indented_table(table: &Table) -> String {
+    table.to_string().lines().map(|line| format!("\t{}\n", line)).collect()
+}
+
 pub fn table_to_html_string(table: &Table, title: &str, headers: &[&str]) -> String {
     let mut html = String::new();
     html.push_str(&format!(r#"<p><span style="color:blue;font-weight:bold;">{title}<br></span>"#, title=title));
@@ -788,4 +859,210 @@ pub fn abs_percentile(series: &[f64], p: f64) -> f64 {
     let hi = (lo + 1).min(n - 1);
     let frac = rank - lo as f64;
     abs_vals[lo] * (1.0 - frac) + abs_vals[hi] * frac
-}
\ No newline at end of file
+}
+
+/// Robust single level-shift (change point) detection.
+/// Every split leaving at least `min_segment` values on both sides is scored with the L1 cost
+/// (sum of absolute deviations from each segment's median) and the cheapest split wins.
+/// Returns (split_index, median_before, median_after, shift_score) where split_index is the first
+/// value of the new level and shift_score is |median_after - median_before| expressed in units of
+/// the pooled, normal-consistent MAD (1.4826 * MAD) of both segments.
+pub fn robust_level_shift(values: &[f64], min_segment: usize) -> Option<(usize, f64, f64, f64)> {
+    let min_segment = min_segment.max(2);
+    let n = values.len();
+    if n < 2 * min_segment {
+        return None;
+    }
+
+    let mut best: Option<(usize, f64)> = None;
+    for k in min_segment..=(n - min_segment) {
+        let (left, right) = values.split_at(k);
+        let med_l = median(left);
+        let med_r = median(right);
+        let cost: f64 = left.iter().map(|v| (v - med_l).abs()).sum::<f64>()
+                      + right.iter().map(|v| (v - med_r).abs()).sum::<f64>();
+        if best.is_none_or(|(_, c)| cost < c) {
+            best = Some((k, cost));
+        }
+    }
+
+    let (k, _) = best?;
+    let (left, right) = values.split_at(k);
+    let med_before = median(left);
+    let med_after = median(right);
+    let residuals: Vec<f64> = left.iter().map(|v| (v - med_before).abs())
+                                .chain(right.iter().map(|v| (v - med_after).abs()))
+                                .collect();
+    // Floor the scale so that perfectly flat segments do not produce infinite scores
+    let scale = (1.4826 * median(&residuals)).max(1e-6 * med_before.abs().max(med_after.abs()).max(1e-9));
+    let score = ((med_after - med_before).abs() / scale).min(1000.0);
+
+    Some((k, med_before, med_after, score))
+}
+
+/// Parses snapshot time as printed in AWR and STATSPACK reports (e.g. 12-Mar-25 13:00:00).
+/// Returns None for formats that could not be recognized.
+pub fn parse_snap_time(snap_time: &str) -> Option<NaiveDateTime> {
+    let snap_time = snap_time.trim();
+    ["%d-%b-%y %H:%M:%S", "%d-%b-%Y %H:%M:%S", "%d-%b-%y %H:%M", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(snap_time, fmt).ok())
+}
+
+/// True when snapshot lies within --snap-range (BEGIN_ID-END_ID)
+pub fn in_snap_range(awr: &AWR, snap_range: &(u64,u64)) -> bool {
+    awr.snap_info.begin_snap_id >= snap_range.0 && awr.snap_info.end_snap_id <= snap_range.1
+}
+
+/// Snapshots within --snap-range in the order they were loaded
+pub fn snaps_in_range<'a>(awrs: &'a [AWR], snap_range: &(u64,u64)) -> impl Iterator<Item = &'a AWR> {
+    let snap_range = *snap_range;
+    awrs.iter().filter(move |awr| in_snap_range(awr, &snap_range))
+}
+
+/// DB time (s) of the snapshot from Time Model Statistics, 0 when missing
+pub fn db_time_s(awr: &AWR) -> f64 {
+    awr.time_model_stats.iter().find(|tm| tm.stat_name.starts_with("DB time")).map_or(0.0, |tm| tm.time_s)
+}
+
+/// DB Time per second (average active sessions) of the snapshot from Load Profile, 0 when missing
+pub fn db_time_per_s(awr: &AWR) -> f64 {
+    awr.load_profile.iter()
+        .find(|lp| lp.stat_name.starts_with("DB Time") || lp.stat_name.starts_with("DB time"))
+        .map_or(0.0, |lp| lp.per_second)
+}
+
+/// Puts HTML fragments right after <body> and right before </body> of a page written by plotly write_html.
+/// Pages with several plots are built this way - first plot creates the page and loads plotly.js,
+/// the next plots and tables are injected below it as inline HTML.
+pub fn inject_html_into_body(path: &str, after_body_start: &str, before_body_end: &str) -> std::io::Result<()> {
+    let mut page = fs::read_to_string(path)?;
+    if !after_body_start.is_empty() {
+        page = page.replacen("<body>", &format!("<body>\n{}", after_body_start), 1);
+    }
+    if !before_body_end.is_empty() {
+        page = page.replace("</body>", &format!("{}\n</body>", before_body_end));
+    }
+    fs::write(path, page)
+}
+
+/// Appends HTML fragment at the end of the page body - see inject_html_into_body
+pub fn append_html_before_body_end(path: &str, html: &str) -> std::io::Result<()> {
+    inject_html_into_body(path, "", html)
+}
+
+/// Small deterministic pseudo-random generator (SplitMix64) - resampling results are reproducible between runs
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index from 0..bound
+    pub fn next_index(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn robust_level_shift_finds_step() {
+        let values = [1.0, 1.1, 0.9, 1.0, 1.05, 5.0, 5.2, 4.9, 5.1, 5.0];
+        let (k, before, after, score) = robust_level_shift(&values, 3).unwrap();
+        assert_eq!(k, 5);
+        assert!((before - 1.0).abs() < 1e-9);
+        assert!((after - 5.0).abs() < 1e-9);
+        assert!(score > 10.0);
+    }
+
+    #[test]
+    fn robust_level_shift_needs_two_segments() {
+        assert!(robust_level_shift(&[1.0, 2.0, 3.0, 4.0, 5.0], 3).is_none());
+        assert!(robust_level_shift(&[], 3).is_none());
+    }
+
+    #[test]
+    fn robust_level_shift_flat_series_scores_zero() {
+        let (_, before, after, score) = robust_level_shift(&[2.0; 8], 2).unwrap();
+        assert_eq!(before, after);
+        assert_eq!(score, 0.0);
+    }
+
+    #[test]
+    fn regularized_incomplete_beta_known_values() {
+        // I_x(1, 1) is the uniform CDF and I_0.5(a, a) = 0.5 by symmetry
+        for x in [0.1, 0.25, 0.5, 0.9] {
+            assert!((regularized_incomplete_beta(1.0, 1.0, x) - x).abs() < 1e-9);
+        }
+        assert!((regularized_incomplete_beta(3.5, 3.5, 0.5) - 0.5).abs() < 1e-9);
+        // I_x(a, 1) = x^a
+        assert!((regularized_incomplete_beta(2.0, 1.0, 0.3) - 0.09).abs() < 1e-9);
+        assert_eq!(regularized_incomplete_beta(2.0, 3.0, 0.0), 0.0);
+        assert_eq!(regularized_incomplete_beta(2.0, 3.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn student_t_p_value_known_values() {
+        assert!((student_t_two_sided_p_value(0.0, 10.0) - 1.0).abs() < 1e-9);
+        // df = 1 is Cauchy: P(|T| > 1) = 0.5
+        assert!((student_t_two_sided_p_value(1.0, 1.0) - 0.5).abs() < 1e-9);
+        assert!((student_t_two_sided_p_value(-1.0, 1.0) - 0.5).abs() < 1e-9);
+        // Tabulated critical values: t(0.975, 10) = 2.228, large df approaches the normal 1.96
+        assert!((student_t_two_sided_p_value(2.228, 10.0) - 0.05).abs() < 1e-3);
+        assert!((student_t_two_sided_p_value(1.96, 100000.0) - 0.05).abs() < 1e-3);
+    }
+
+    #[test]
+    fn student_t_p_value_degenerate_input() {
+        assert_eq!(student_t_two_sided_p_value(f64::INFINITY, 10.0), 0.0);
+        assert_eq!(student_t_two_sided_p_value(f64::NAN, 10.0), 1.0);
+        assert_eq!(student_t_two_sided_p_value(2.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn parse_snap_time_formats() {
+        let expected = NaiveDateTime::parse_from_str("2025-03-12 13:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(parse_snap_time("12-Mar-25 13:00:00"), Some(expected));
+        assert_eq!(parse_snap_time(" 12-Mar-2025 13:00:00 "), Some(expected));
+        assert_eq!(parse_snap_time("12-Mar-25 13:00"), Some(expected));
+        assert_eq!(parse_snap_time("2025-03-12 13:00:00"), Some(expected));
+        assert_eq!(parse_snap_time("not a date"), None);
+    }
+
+    #[test]
+    fn split_mix_64_is_deterministic() {
+        let mut a = SplitMix64::new(42);
+        let mut b = SplitMix64::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        let mut c = SplitMix64::new(43);
+        assert_ne!(SplitMix64::new(42).next_u64(), c.next_u64());
+    }
+
+    #[test]
+    fn split_mix_64_index_in_bounds() {
+        let mut rng = SplitMix64::new(7);
+        assert_eq!(rng.next_index(0), 0);
+        for _ in 0..1000 {
+            assert!(rng.next_index(13) < 13);
+        }
+    }
+}